@@ -0,0 +1,386 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorts producing a permutation of row indices rather than moving data.
+
+use std::ptr;
+
+use super::{introsort_impl, record_sorted};
+
+/// A column of values that rows can be ordered by.
+///
+/// This trait is object safe so columns of different element types can be
+/// passed together to `lexsort`. It is implemented for `Vec<T>`, arrays and
+/// slice references of any `T: PartialOrd`.
+pub trait SortColumn {
+    /// Returns the number of rows in the column.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the column has no rows.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if row `a` sorts before row `b`.
+    fn row_lt(&self, a: usize, b: usize) -> bool;
+}
+
+impl<T: PartialOrd> SortColumn for &[T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn row_lt(&self, a: usize, b: usize) -> bool {
+        self[a].lt(&self[b])
+    }
+}
+
+impl<T: PartialOrd> SortColumn for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn row_lt(&self, a: usize, b: usize) -> bool {
+        self[a].lt(&self[b])
+    }
+}
+
+impl<T: PartialOrd, const N: usize> SortColumn for [T; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn row_lt(&self, a: usize, b: usize) -> bool {
+        self[a].lt(&self[b])
+    }
+}
+
+/// Returns the row indices that sort the given columns, comparing by the
+/// first column, then the second for rows that are equal in the first, and so
+/// on, with the last column least significant.
+///
+/// The result is stable: rows that compare equal in every column keep their
+/// original relative order. No column data is moved; apply the returned
+/// permutation to each column with `apply_permutation`. Returns an empty
+/// `Vec` if `keys` is empty.
+///
+/// # Panics
+///
+/// Panics if the columns don't all have the same length.
+///
+/// # Examples
+///
+/// ```rust
+/// let last_names = vec!["smith", "jones", "smith", "jones"];
+/// let ages = vec![30, 40, 20, 40];
+/// let order = sortrs::lexsort(&[&last_names, &ages]);
+/// assert!(order == [1, 3, 2, 0]);
+/// ```
+pub fn lexsort(keys: &[&dyn SortColumn]) -> Vec<usize> {
+    let len = match keys.first() {
+        Some(column) => column.len(),
+        None => return Vec::new(),
+    };
+    assert!(
+        keys.iter().all(|column| column.len() == len),
+        "lexsort columns must have the same length"
+    );
+
+    let mut indices = (0..len).collect::<Vec<usize>>();
+    introsort_impl(&mut indices, |&a, &b| {
+        for column in keys {
+            if column.row_lt(a, b) {
+                return true;
+            }
+            if column.row_lt(b, a) {
+                return false;
+            }
+        }
+        // equal rows keep their original order
+        a < b
+    });
+    indices
+}
+
+/// A column whose rows may be null, such as an Arrow array with a validity
+/// bitmap.
+///
+/// `row_lt` is only ever called with rows that are valid, so null rows may
+/// hold any value.
+pub trait NullableColumn: SortColumn {
+    /// Returns `true` if row `i` holds a value, `false` if it is null.
+    fn is_valid(&self, i: usize) -> bool;
+}
+
+/// A column of values paired with an Arrow-style validity bitmap.
+///
+/// Row `i` is valid when bit `i % 8` of byte `i / 8` of the bitmap is set,
+/// counting bits from the least significant.
+#[derive(Clone, Copy, Debug)]
+pub struct ValidityColumn<'a, T: 'a> {
+    values: &'a [T],
+    validity: &'a [u8],
+}
+
+impl<'a, T: PartialOrd> ValidityColumn<'a, T> {
+    /// Creates a column of `values` with nulls marked by `validity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `validity` has fewer bits than there are `values`.
+    pub fn new(values: &'a [T], validity: &'a [u8]) -> ValidityColumn<'a, T> {
+        assert!(
+            validity.len() * 8 >= values.len(),
+            "validity bitmap is shorter than the values"
+        );
+        ValidityColumn { values, validity }
+    }
+}
+
+impl<'a, T: PartialOrd> SortColumn for ValidityColumn<'a, T> {
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn row_lt(&self, a: usize, b: usize) -> bool {
+        self.values[a].lt(&self.values[b])
+    }
+}
+
+impl<'a, T: PartialOrd> NullableColumn for ValidityColumn<'a, T> {
+    fn is_valid(&self, i: usize) -> bool {
+        self.validity[i / 8] & (1 << (i % 8)) != 0
+    }
+}
+
+/// Where `argsort_nullable` places null rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NullPlacement {
+    /// Null rows sort before every valid row.
+    First,
+    /// Null rows sort after every valid row.
+    Last,
+}
+
+/// Returns the row indices that sort `column`, placing null rows according
+/// to `nulls`.
+///
+/// The result is stable: valid rows that compare equal, and all null rows,
+/// keep their original relative order.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::{argsort_nullable, NullPlacement, ValidityColumn};
+///
+/// // the row holding 0 is null
+/// let column = ValidityColumn::new(&[3, 0, 1, 2], &[0b1101]);
+/// assert!(argsort_nullable(&column, NullPlacement::First) == [1, 2, 3, 0]);
+/// assert!(argsort_nullable(&column, NullPlacement::Last) == [2, 3, 0, 1]);
+/// ```
+pub fn argsort_nullable(column: &dyn NullableColumn, nulls: NullPlacement) -> Vec<usize> {
+    let nulls_first = nulls == NullPlacement::First;
+    let mut indices = (0..column.len()).collect::<Vec<usize>>();
+    introsort_impl(&mut indices, |&a, &b| {
+        match (column.is_valid(a), column.is_valid(b)) {
+            (true, true) => {
+                if column.row_lt(a, b) {
+                    return true;
+                }
+                if column.row_lt(b, a) {
+                    return false;
+                }
+            }
+            (false, true) => return nulls_first,
+            (true, false) => return !nulls_first,
+            (false, false) => {}
+        }
+        // equal rows keep their original order
+        a < b
+    });
+    indices
+}
+
+/// A fixed size set of bits, one per element of a slice.
+struct Bitmap {
+    words: Vec<u64>,
+}
+
+impl Bitmap {
+    fn new(len: usize) -> Bitmap {
+        Bitmap {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    #[inline]
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    #[inline]
+    fn set(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn clear(&mut self) {
+        for word in &mut self.words {
+            *word = 0;
+        }
+    }
+}
+
+/// Reorders `v` in place so that `v[i]` becomes the element previously at
+/// `v[perm[i]]`.
+///
+/// This applies the index permutations returned by the argsort functions.
+/// The permutation is followed one cycle at a time, tracking visited indices
+/// in a bitmap, so every element is moved exactly once and `T` doesn't need
+/// to be `Clone`. Besides the bitmap of `n` bits no memory is allocated.
+///
+/// # Panics
+///
+/// Panics if `perm` is not a permutation of `0..v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut names = vec!["carol".to_string(), "alice".to_string(), "bob".to_string()];
+/// let order = sortrs::lexsort(&[&names]);
+/// sortrs::apply_permutation(&mut names, &order);
+/// assert!(names == ["alice", "bob", "carol"]);
+/// ```
+pub fn apply_permutation<T>(v: &mut [T], perm: &[usize]) {
+    let len = v.len();
+    assert_eq!(
+        perm.len(),
+        len,
+        "permutation length doesn't match the slice"
+    );
+
+    // every index must appear exactly once, or the moves below would
+    // duplicate some elements and lose others
+    let mut visited = Bitmap::new(len);
+    for &p in perm {
+        assert!(p < len && !visited.get(p), "not a permutation");
+        visited.set(p);
+    }
+    visited.clear();
+
+    let ptr = v.as_mut_ptr();
+    for leader in 0..len {
+        if visited.get(leader) {
+            continue;
+        }
+        // lift the cycle leader out, shift each element of the cycle into
+        // its place, then drop the leader into the last hole
+        unsafe {
+            let tmp = ptr::read(ptr.add(leader));
+            let mut hole = leader;
+            loop {
+                visited.set(hole);
+                let next = perm[hole];
+                if next == leader {
+                    break;
+                }
+                ptr::copy_nonoverlapping(ptr.add(next), ptr.add(hole), 1);
+                hole = next;
+            }
+            ptr::write(ptr.add(hole), tmp);
+        }
+    }
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements and breaking
+/// ties by original position, so equal elements keep their relative order.
+///
+/// The output is the same as `sort_by`, but the sorting is done by
+/// `introsort_by`. Indices rather than elements are sorted, with the index
+/// as the final tiebreaker, then the permutation is applied with
+/// `apply_permutation`. This allocates a `usize` per element and is useful
+/// when reproducible output is wanted from the unstable sort, for example
+/// when it is faster than `sort_by` for the comparator at hand.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// sortrs::introsort_tiebreak_by(&mut v, |a, b| a.0 < b.0);
+/// assert!(v == [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+pub fn introsort_tiebreak_by<T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    let mut indices = (0..v.len()).collect::<Vec<usize>>();
+    {
+        let v = &*v;
+        introsort_impl(&mut indices, |&a, &b| {
+            lt(&v[a], &v[b]) || (!lt(&v[b], &v[a]) && a < b)
+        });
+    }
+    apply_permutation(v, &indices);
+}
+
+/// Sorts the slice, in place, with introsort, keeping equal elements in
+/// their original order.
+///
+/// This is equivalent to `introsort_tiebreak_by(v, |a, b| a.lt(b))`, see
+/// `introsort_tiebreak_by` for its costs.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [3, 1, 2];
+/// sortrs::introsort_tiebreak(&mut v);
+/// assert!(v == [1, 2, 3]);
+/// ```
+pub fn introsort_tiebreak<T: PartialOrd>(v: &mut [T]) {
+    introsort_tiebreak_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts `values` and `keys` together, in place, by comparing `keys`.
+///
+/// `keys[i]` is the sort key of `values[i]`, as when keys are held in a
+/// separate column. The keys are compared directly, so nothing is extracted
+/// or copied, and equal keys keep their original order. The index
+/// permutation that sorts `keys` is computed with `introsort_by` and then
+/// applied to both slices with `apply_permutation`, allocating a `usize` per
+/// element.
+///
+/// # Panics
+///
+/// Panics if `values` and `keys` have different lengths.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut names = ["carol", "alice", "bob", "dave"];
+/// let mut ages = [35, 30, 41, 30];
+/// sortrs::sort_by_key_slice(&mut names, &mut ages);
+/// assert!(names == ["alice", "dave", "carol", "bob"]);
+/// assert!(ages == [30, 30, 35, 41]);
+/// ```
+pub fn sort_by_key_slice<T, K: PartialOrd>(values: &mut [T], keys: &mut [K]) {
+    assert_eq!(
+        values.len(),
+        keys.len(),
+        "values and keys must have the same length"
+    );
+    record_sorted(keys.len());
+    let mut indices = (0..keys.len()).collect::<Vec<usize>>();
+    {
+        let keys = &*keys;
+        introsort_impl(&mut indices, |&a, &b| {
+            keys[a].lt(&keys[b]) || (!keys[b].lt(&keys[a]) && a < b)
+        });
+    }
+    apply_permutation(keys, &indices);
+    apply_permutation(values, &indices);
+}
@@ -0,0 +1,250 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Timing sort functions from within a program.
+//!
+//! `BenchRunner` runs a sort function over a corpus of input patterns and
+//! returns the measurements, so algorithm comparisons can be made at runtime
+//! rather than only through `cargo bench`.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use super::{introsort_with_config_by, IntrosortConfig};
+
+/// The shape of an input in the benchmark corpus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// Uniformly random values.
+    Random,
+    /// Ascending values.
+    Sorted,
+    /// Descending values.
+    Reversed,
+    /// Ascending values with about 1% of elements swapped at random.
+    NearlySorted,
+    /// Random values drawn from 16 distinct keys.
+    FewUnique,
+}
+
+impl Pattern {
+    /// Every pattern in the corpus.
+    pub fn all() -> Vec<Pattern> {
+        vec![
+            Pattern::Random,
+            Pattern::Sorted,
+            Pattern::Reversed,
+            Pattern::NearlySorted,
+            Pattern::FewUnique,
+        ]
+    }
+}
+
+/// The measurements for one pattern and length.
+#[derive(Clone, Debug)]
+pub struct BenchResult {
+    /// The pattern that was sorted.
+    pub pattern: Pattern,
+    /// The number of elements that were sorted.
+    pub len: usize,
+    /// The fastest of the timed runs.
+    pub min: Duration,
+    /// The average of the timed runs.
+    pub mean: Duration,
+    /// The average number of comparisons per run, only recorded by
+    /// `BenchRunner::run_counting`.
+    pub comparisons: Option<u64>,
+}
+
+/// Runs sort functions over a corpus of generated inputs.
+///
+/// Inputs are generated as `u64` values from a fixed seed and converted to
+/// the element type with `element`, so every sort function sees the same
+/// data. Each input is copied before every run and only the sort itself is
+/// timed.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::bench::{BenchRunner, Pattern};
+///
+/// let mut runner = BenchRunner::new(|x| x as u32);
+/// runner.lens = vec![1000];
+/// runner.patterns = vec![Pattern::Random, Pattern::Sorted];
+/// let introsort = runner.run(|v| sortrs::introsort(v));
+/// let heapsort = runner.run(|v| sortrs::heapsort(v));
+/// assert_eq!(introsort.len(), 2);
+/// assert_eq!(heapsort.len(), 2);
+/// ```
+pub struct BenchRunner<T> {
+    /// The input lengths to run, defaults to the lengths used by the crate's
+    /// benchmarks.
+    pub lens: Vec<usize>,
+    /// The input patterns to run, defaults to `Pattern::all()`.
+    pub patterns: Vec<Pattern>,
+    /// The number of timed runs per input, defaults to 10.
+    pub iterations: u32,
+    /// The seed the inputs are generated from.
+    pub seed: u64,
+    /// Converts a generated value to the element type. It should preserve
+    /// the order of values.
+    pub element: fn(u64) -> T,
+}
+
+impl<T: Clone> BenchRunner<T> {
+    /// Creates a runner with the default corpus, converting generated values
+    /// to the element type with `element`.
+    pub fn new(element: fn(u64) -> T) -> BenchRunner<T> {
+        BenchRunner {
+            lens: vec![5, 100, 10000],
+            patterns: Pattern::all(),
+            iterations: 10,
+            seed: 0x2545_f491_4f6c_dd1d,
+            element,
+        }
+    }
+
+    /// Generates the input for `pattern` with `len` elements.
+    pub fn input(&self, pattern: Pattern, len: usize) -> Vec<T> {
+        let mut rng = XorShift(self.seed | 1);
+        let values = match pattern {
+            Pattern::Random => (0..len).map(|_| rng.next()).collect::<Vec<u64>>(),
+            Pattern::Sorted => (0..len as u64).collect(),
+            Pattern::Reversed => (0..len as u64).rev().collect(),
+            Pattern::NearlySorted => {
+                let mut values = (0..len as u64).collect::<Vec<u64>>();
+                if len > 0 {
+                    for _ in 0..len / 100 + 1 {
+                        let a = (rng.next() % len as u64) as usize;
+                        let b = (rng.next() % len as u64) as usize;
+                        values.swap(a, b);
+                    }
+                }
+                values
+            }
+            Pattern::FewUnique => (0..len).map(|_| rng.next() % 16).collect(),
+        };
+        values.into_iter().map(self.element).collect()
+    }
+
+    /// Times `sortfn` over every pattern and length, returning one result for
+    /// each.
+    pub fn run<F>(&self, sortfn: F) -> Vec<BenchResult>
+    where
+        F: Fn(&mut [T]),
+    {
+        self.run_inputs(|v| sortfn(v), None)
+    }
+
+    /// Times `sortfn` over every pattern and length like `run`, and also
+    /// counts the comparisons made through the less than function it is
+    /// given.
+    ///
+    /// Counting adds a little overhead to every comparison, so prefer `run`
+    /// when only timings are wanted.
+    pub fn run_counting<F>(&self, sortfn: F) -> Vec<BenchResult>
+    where
+        T: PartialOrd,
+        F: Fn(&mut [T], &dyn Fn(&T, &T) -> bool),
+    {
+        let count = Cell::new(0u64);
+        let lt = |a: &T, b: &T| {
+            count.set(count.get() + 1);
+            a.lt(b)
+        };
+        self.run_inputs(|v| sortfn(v, &lt), Some(&count))
+    }
+
+    fn run_inputs<F>(&self, sortfn: F, count: Option<&Cell<u64>>) -> Vec<BenchResult>
+    where
+        F: Fn(&mut [T]),
+    {
+        let iterations = self.iterations.max(1);
+        let mut results = Vec::with_capacity(self.patterns.len() * self.lens.len());
+        for &pattern in &self.patterns {
+            for &len in &self.lens {
+                let input = self.input(pattern, len);
+                let mut total = Duration::new(0, 0);
+                let mut min = None;
+                if let Some(count) = count {
+                    count.set(0);
+                }
+                for _ in 0..iterations {
+                    let mut v = input.clone();
+                    let start = Instant::now();
+                    sortfn(&mut v);
+                    let elapsed = start.elapsed();
+                    total += elapsed;
+                    min = Some(min.map_or(elapsed, |min: Duration| min.min(elapsed)));
+                }
+                results.push(BenchResult {
+                    pattern,
+                    len,
+                    min: min.unwrap(),
+                    mean: total / iterations,
+                    comparisons: count.map(|count| count.get() / u64::from(iterations)),
+                });
+            }
+        }
+        results
+    }
+}
+
+/// Measures introsort on this machine and returns the configuration that
+/// sorted fastest, converting generated values to the element type with
+/// `element`.
+///
+/// The insertion sort threshold is chosen from a handful of candidates by
+/// timing random inputs, as the best cutoff depends on the CPU's caches and
+/// branch predictor and on the cost of comparing and moving the element type.
+/// This takes a few milliseconds, so call it once and keep the result. The
+/// other settings are left at their defaults.
+///
+/// # Examples
+///
+/// ```rust
+/// let config = sortrs::bench::tune(|x| x as u32);
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn tune<T: PartialOrd + Clone>(element: fn(u64) -> T) -> IntrosortConfig<T> {
+    const CANDIDATES: [usize; 6] = [8, 12, 16, 24, 32, 48];
+
+    let mut runner = BenchRunner::new(element);
+    runner.lens = vec![10000];
+    runner.patterns = vec![Pattern::Random];
+    runner.iterations = 5;
+    let mut best = IntrosortConfig::default();
+    let mut best_time = Duration::MAX;
+    for &insertion_threshold in &CANDIDATES {
+        let config = IntrosortConfig {
+            insertion_threshold,
+            ..Default::default()
+        };
+        let results = runner.run(|v| introsort_with_config_by(v, &config, |a, b| a.lt(b)));
+        let time = results.iter().map(|result| result.min).sum::<Duration>();
+        if time < best_time {
+            best = config;
+            best_time = time;
+        }
+    }
+    best
+}
+
+/// A small, fast generator so the corpus doesn't need a `rand` dependency.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorting bits, and sets of `u32` IDs as sorted lists or bitmaps.
+
+use std::ops::Range;
+
+/// A sequence of bits that `sort_bits` can sort.
+///
+/// This is implemented for `[bool]` and for `[u64]`, where bit `i` is bit
+/// `i % 64` of word `i / 64`, counting from the least significant bit.
+pub trait Bits {
+    /// Returns the number of bits.
+    fn bit_len(&self) -> usize;
+
+    /// Returns the number of bits that are set.
+    fn count_ones(&self) -> usize;
+
+    /// Sets every bit in `range` to `value`.
+    fn set_range(&mut self, range: Range<usize>, value: bool);
+}
+
+impl Bits for [bool] {
+    fn bit_len(&self) -> usize {
+        self.len()
+    }
+
+    fn count_ones(&self) -> usize {
+        self.iter().filter(|&&b| b).count()
+    }
+
+    fn set_range(&mut self, range: Range<usize>, value: bool) {
+        for b in &mut self[range] {
+            *b = value;
+        }
+    }
+}
+
+impl Bits for [u64] {
+    fn bit_len(&self) -> usize {
+        self.len() * 64
+    }
+
+    fn count_ones(&self) -> usize {
+        self.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn set_range(&mut self, range: Range<usize>, value: bool) {
+        if range.start >= range.end {
+            return;
+        }
+        let (first, last) = (range.start / 64, (range.end - 1) / 64);
+        for (i, w) in self[first..last + 1].iter_mut().enumerate() {
+            let lo = if i == 0 { range.start % 64 } else { 0 };
+            let hi = if first + i == last {
+                (range.end - 1) % 64 + 1
+            } else {
+                64
+            };
+            let mask = (!0u64 >> (64 - (hi - lo))) << lo;
+            if value {
+                *w |= mask;
+            } else {
+                *w &= !mask;
+            }
+        }
+    }
+}
+
+/// Sorts the bits, in place, so the unset bits come before the set ones.
+///
+/// The set bits are counted and the bits are then overwritten, a counting
+/// sort that is `O(n)` and works a word at a time for `[u64]`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [true, false, true, false];
+/// sortrs::sort_bits(&mut v[..]);
+/// assert!(v == [false, false, true, true]);
+///
+/// let mut words = [0b1011u64, 1 << 63];
+/// sortrs::sort_bits(&mut words[..]);
+/// assert!(words == [0, !0u64 << 60]);
+/// ```
+pub fn sort_bits<B: Bits + ?Sized>(v: &mut B) {
+    let len = v.bit_len();
+    let ones = v.count_ones();
+    v.set_range(0..len - ones, false);
+    v.set_range(len - ones..len, true);
+}
+
+/// A set of `u32` IDs stored as a compressed bitmap.
+///
+/// The IDs are grouped into 64 bit words and only the words with at least
+/// one ID are stored, in ascending order, so sparse sets stay small while
+/// dense ones take a bit per ID. Set operations merge the words, working on
+/// 64 IDs at a time.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Bitmap;
+///
+/// let a = Bitmap::from_sorted_ids(&[1, 5, 100, 1 << 30]);
+/// let b = Bitmap::from_sorted_ids(&[5, 6, 1 << 30]);
+/// assert!(a.intersection(&b).to_sorted_ids() == [5, 1 << 30]);
+/// assert!(a.difference(&b).to_sorted_ids() == [1, 100]);
+/// assert_eq!(a.union(&b).len(), 5);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    // (id / 64, bits) for every non-zero word, by ascending index
+    words: Vec<(u32, u64)>,
+}
+
+impl Bitmap {
+    /// Returns an empty bitmap.
+    pub fn new() -> Bitmap {
+        Bitmap { words: Vec::new() }
+    }
+
+    /// Returns the bitmap of the IDs in the sorted `ids`. Repeated IDs are
+    /// stored once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ids` is not sorted in ascending order.
+    pub fn from_sorted_ids(ids: &[u32]) -> Bitmap {
+        let mut words: Vec<(u32, u64)> = Vec::new();
+        let mut last = 0;
+        for &id in ids {
+            assert!(id >= last, "ids must be sorted");
+            last = id;
+            let (index, bit) = (id / 64, 1u64 << (id % 64));
+            match words.last_mut() {
+                Some(w) if w.0 == index => w.1 |= bit,
+                _ => words.push((index, bit)),
+            }
+        }
+        Bitmap { words }
+    }
+
+    /// Returns the IDs in the bitmap in ascending order.
+    pub fn to_sorted_ids(&self) -> Vec<u32> {
+        let mut ids = Vec::with_capacity(self.len());
+        for &(index, mut bits) in &self.words {
+            while bits != 0 {
+                ids.push(index * 64 + bits.trailing_zeros());
+                // clear the lowest set bit
+                bits &= bits - 1;
+            }
+        }
+        ids
+    }
+
+    /// Returns the number of IDs in the bitmap.
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.1.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if the bitmap holds no IDs.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns `true` if the bitmap holds `id`.
+    pub fn contains(&self, id: u32) -> bool {
+        match self.words.binary_search_by_key(&(id / 64), |w| w.0) {
+            Ok(i) => self.words[i].1 & (1 << (id % 64)) != 0,
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the IDs in either bitmap.
+    pub fn union(&self, other: &Bitmap) -> Bitmap {
+        self.combine(other, true, true, |a, b| a | b)
+    }
+
+    /// Returns the IDs in both bitmaps.
+    pub fn intersection(&self, other: &Bitmap) -> Bitmap {
+        self.combine(other, false, false, |a, b| a & b)
+    }
+
+    /// Returns the IDs in this bitmap but not in `other`.
+    pub fn difference(&self, other: &Bitmap) -> Bitmap {
+        self.combine(other, true, false, |a, b| a & !b)
+    }
+
+    /// Merges the words of two bitmaps, keeping the words only in `self` if
+    /// `keep_self`, those only in `other` if `keep_other` and combining those
+    /// in both with `both`.
+    fn combine<F>(&self, other: &Bitmap, keep_self: bool, keep_other: bool, both: F) -> Bitmap
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        // words left with no bits are dropped
+        let combined = |a: &u64, b: &u64| Some(both(*a, *b)).filter(|&bits| bits != 0);
+        let words = merge_keyed(&self.words, &other.words, keep_self, keep_other, combined);
+        Bitmap { words }
+    }
+}
+
+/// Merges two lists of `(key, value)` pairs with ascending and distinct
+/// keys, keeping the pairs only in `a` if `keep_a`, those only in `b` if
+/// `keep_b` and combining the values of those in both with `both`, which
+/// drops the pair when it returns `None`.
+pub(crate) fn merge_keyed<K, V, F>(
+    a: &[(K, V)],
+    b: &[(K, V)],
+    keep_a: bool,
+    keep_b: bool,
+    both: F,
+) -> Vec<(K, V)>
+where
+    K: Ord + Copy,
+    V: Clone,
+    F: Fn(&V, &V) -> Option<V>,
+{
+    let mut merged = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i].0 < b[j].0 {
+            if keep_a {
+                merged.push(a[i].clone());
+            }
+            i += 1;
+        } else if b[j].0 < a[i].0 {
+            if keep_b {
+                merged.push(b[j].clone());
+            }
+            j += 1;
+        } else {
+            if let Some(value) = both(&a[i].1, &b[j].1) {
+                merged.push((a[i].0, value));
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+    if keep_a {
+        merged.extend_from_slice(&a[i..]);
+    }
+    if keep_b {
+        merged.extend_from_slice(&b[j..]);
+    }
+    merged
+}
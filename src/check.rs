@@ -0,0 +1,183 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers for checking the results of sorts.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::{introsort_impl, Compare};
+
+/// Returns `true` if `a` and `b` contain the same elements with the same
+/// multiplicities, in any order.
+///
+/// Use this to check that a sort neither lost nor duplicated elements. The
+/// check counts elements in a `HashMap` in `O(n)` expected time; for types
+/// that aren't `Hash` use `is_permutation_of_by`.
+///
+/// # Examples
+///
+/// ```rust
+/// let original = [3, 1, 2, 1];
+/// let mut v = original;
+/// sortrs::introsort(&mut v);
+/// assert!(sortrs::is_permutation_of(&v, &original));
+/// assert!(!sortrs::is_permutation_of(&[1, 1, 2, 2], &original));
+/// ```
+pub fn is_permutation_of<T: Eq + Hash>(a: &[T], b: &[T]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut counts = HashMap::with_capacity(a.len());
+    for x in a {
+        *counts.entry(x).or_insert(0isize) += 1;
+    }
+    for x in b {
+        match counts.get_mut(x) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Returns `true` if `a` and `b` contain the same elements with the same
+/// multiplicities, in any order, using `lt` to compare elements.
+///
+/// Elements are considered equal when neither is less than the other. This
+/// sorts references to the elements of both slices, so it is `O(n log n)` and
+/// works for any type `lt` can order, such as floats.
+///
+/// # Examples
+///
+/// ```rust
+/// let original = [2.5, -1.0, 0.5];
+/// let mut v = original;
+/// sortrs::introsort(&mut v);
+/// assert!(sortrs::is_permutation_of_by(&v, &original, |a, b| a.lt(b)));
+/// ```
+pub fn is_permutation_of_by<T, F>(a: &[T], b: &[T], lt: F) -> bool
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut a = a.iter().collect::<Vec<&T>>();
+    let mut b = b.iter().collect::<Vec<&T>>();
+    introsort_impl(&mut a, |x, y| lt(x, y));
+    introsort_impl(&mut b, |x, y| lt(x, y));
+    a.iter().zip(b.iter()).all(|(x, y)| !lt(x, y) && !lt(y, x))
+}
+
+/// A less than function wrapper for debugging comparators.
+///
+/// `CheckedCmp` counts the comparisons made through it, checks that the
+/// wrapped function never reports an element as less than itself, and can
+/// panic once a budget of comparisons is exceeded, which turns a runaway
+/// sort caused by a broken comparator into a test failure. Pass it by
+/// reference to any `_with` function, or to any `_by` function through a
+/// closure calling `CheckedCmp::lt`.
+///
+/// Each comparison also calls the wrapped function with both arguments the
+/// same to check reflexivity, so this roughly triples the cost of comparing.
+///
+/// # Examples
+///
+/// ```rust
+/// let checked = sortrs::CheckedCmp::with_budget(|a: &u32, b: &u32| a < b, 1000);
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::introsort_by(&mut v, |a, b| checked.lt(a, b));
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// assert!(checked.calls() > 0);
+/// assert_eq!(checked.reflexivity_violations(), 0);
+///
+/// // <= is not a strict ordering
+/// let broken = sortrs::CheckedCmp::new(|a: &u32, b: &u32| a <= b);
+/// sortrs::introsort_by(&mut v, |a, b| broken.lt(a, b));
+/// assert!(broken.reflexivity_violations() > 0);
+/// ```
+pub struct CheckedCmp<F> {
+    lt: F,
+    calls: Cell<u64>,
+    violations: Cell<u64>,
+    budget: Option<u64>,
+}
+
+impl<F> CheckedCmp<F> {
+    /// Wraps the less than function `lt` with no limit on comparisons.
+    pub fn new(lt: F) -> CheckedCmp<F> {
+        CheckedCmp {
+            lt,
+            calls: Cell::new(0),
+            violations: Cell::new(0),
+            budget: None,
+        }
+    }
+
+    /// Wraps the less than function `lt`, panicking if it is called more than
+    /// `budget` times.
+    pub fn with_budget(lt: F, budget: u64) -> CheckedCmp<F> {
+        CheckedCmp {
+            budget: Some(budget),
+            ..CheckedCmp::new(lt)
+        }
+    }
+
+    /// Compares `a` and `b` with the wrapped function, recording the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this call exceeds the comparison budget.
+    pub fn lt<T>(&self, a: &T, b: &T) -> bool
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        let calls = self.calls.get() + 1;
+        self.calls.set(calls);
+        if let Some(budget) = self.budget {
+            assert!(
+                calls <= budget,
+                "comparator called more than its budget of {} times",
+                budget
+            );
+        }
+        if (self.lt)(a, a) || (self.lt)(b, b) {
+            self.violations.set(self.violations.get() + 1);
+        }
+        (self.lt)(a, b)
+    }
+
+    /// Returns the number of comparisons made so far.
+    pub fn calls(&self) -> u64 {
+        self.calls.get()
+    }
+
+    /// Returns the number of comparisons in which the wrapped function
+    /// reported an argument as less than itself.
+    pub fn reflexivity_violations(&self) -> u64 {
+        self.violations.get()
+    }
+
+    /// Resets the call and violation counts, keeping the budget.
+    pub fn reset(&self) {
+        self.calls.set(0);
+        self.violations.set(0);
+    }
+}
+
+impl<T, F> Compare<T> for CheckedCmp<F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    #[inline]
+    fn lt(&self, a: &T, b: &T) -> bool {
+        CheckedCmp::lt(self, a, b)
+    }
+}
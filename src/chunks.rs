@@ -0,0 +1,159 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorting fixed size chunks of a slice.
+
+use super::{introsort_impl, record_sorted};
+
+/// Comparator pairs of the smallest known sorting network for each length up
+/// to 8, indexed by length.
+const NETWORKS: [&[(usize, usize)]; 9] = [
+    &[],
+    &[],
+    &[(0, 1)],
+    &[(0, 2), (0, 1), (1, 2)],
+    &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    &[
+        (0, 3),
+        (1, 4),
+        (0, 2),
+        (1, 3),
+        (0, 1),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (2, 3),
+    ],
+    &[
+        (0, 5),
+        (1, 3),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (0, 3),
+        (2, 5),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+    ],
+    &[
+        (0, 6),
+        (2, 3),
+        (4, 5),
+        (0, 2),
+        (1, 4),
+        (3, 6),
+        (0, 1),
+        (2, 5),
+        (3, 4),
+        (1, 2),
+        (4, 6),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+    &[
+        (0, 2),
+        (1, 3),
+        (4, 6),
+        (5, 7),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (6, 7),
+        (2, 4),
+        (3, 5),
+        (1, 4),
+        (3, 6),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+];
+
+/// Sorts `chunk`, whose length must be at most 8, with a sorting network.
+#[inline(always)]
+fn network_sort<T, F>(chunk: &mut [T], lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    for &(i, j) in NETWORKS[chunk.len()] {
+        if lt(&chunk[j], &chunk[i]) {
+            chunk.swap(i, j);
+        }
+    }
+}
+
+/// Sorts each consecutive `N` element chunk of `v` independently, in place,
+/// using `lt` to compare elements.
+///
+/// The chunks are those of `v.chunks_mut(N)`, so if `N` does not divide the
+/// length of `v` the last chunk is shorter and is sorted on its own too. The
+/// order of equal elements is not guaranteed to be preserved.
+///
+/// For `N` up to 8 each chunk is sorted with a fixed sorting network. The
+/// network is selected at compile time, so its comparisons can be fully
+/// unrolled. Larger chunks are sorted with `introsort_by`.
+///
+/// # Panics
+///
+/// Panics if `N` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [3, 1, 2, 9, 8, 7, 5, 4];
+/// sortrs::sort_chunks_by::<3, _, _>(&mut v, |a, b| b.lt(a));
+/// assert!(v == [3, 2, 1, 9, 8, 7, 5, 4]);
+/// ```
+pub fn sort_chunks_by<const N: usize, T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(N != 0, "chunk size must be non-zero");
+    record_sorted(v.len());
+    if N <= 8 {
+        let mut chunks = v.chunks_exact_mut(N);
+        for chunk in &mut chunks {
+            network_sort(chunk, &lt);
+        }
+        network_sort(chunks.into_remainder(), &lt);
+    } else {
+        for chunk in v.chunks_mut(N) {
+            introsort_impl(chunk, &lt);
+        }
+    }
+}
+
+/// Sorts each consecutive `N` element chunk of `v` independently, in place.
+///
+/// This is equivalent to `sort_chunks_by::<N, _, _>(v, |a, b| a.lt(b))`, see
+/// `sort_chunks_by` for details.
+///
+/// # Panics
+///
+/// Panics if `N` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [4, 3, 2, 1, 8, 7, 6, 5, 10, 9];
+/// sortrs::sort_chunks::<4, _>(&mut v);
+/// assert!(v == [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+/// ```
+pub fn sort_chunks<const N: usize, T: PartialOrd>(v: &mut [T]) {
+    sort_chunks_by::<N, _, _>(v, |a, b| a.lt(b));
+}
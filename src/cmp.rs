@@ -0,0 +1,303 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorts taking comparators other than a less than closure.
+//!
+//! Each `_by_cmp` function is its `_by` counterpart with `cmp(a, b) ==
+//! Ordering::Less` as the `lt` closure, so `Ord::cmp`, `f64::total_cmp` and
+//! comparators written for the standard library's sorts can be passed as
+//! they are. Each `_with` function takes a `Compare` implementation, so a
+//! comparator can be a named type holding its own context.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+use super::{
+    heapsort_impl, insertsort_impl, introsort_impl, record_sorted, sortable_len, timsort_by,
+};
+
+/// Adapts an `FnMut` `Ordering` comparator into the `Fn` less than closure
+/// the sorts take. The sorts never compare from inside a comparison, so the
+/// borrow can't be taken twice.
+#[inline]
+pub(crate) fn less<T, F>(cmp: F) -> impl Fn(&T, &T) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let cmp = RefCell::new(cmp);
+    move |a, b| (cmp.borrow_mut())(a, b) == Ordering::Less
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// insertion sort.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::insertsort_by_cmp(&mut v, |a, b| b.cmp(a));
+/// assert!(v == [5, 4, 3, 2, 1]);
+/// ```
+pub fn insertsort_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        insertsort_impl(v.as_mut_ptr(), len, &less(cmp));
+    }
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// heapsort.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::heapsort_by_cmp(&mut v, Ord::cmp);
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn heapsort_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        heapsort_impl(v.as_mut_ptr(), len, &less(cmp));
+    }
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// introsort.
+///
+/// See `introsort_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [2.5, -0.0, 1.0, -3.5, 0.0];
+/// sortrs::introsort_by_cmp(&mut v, f64::total_cmp);
+/// assert!(v == [-3.5, -0.0, 0.0, 1.0, 2.5]);
+/// ```
+pub fn introsort_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    record_sorted(v.len());
+    introsort_impl(v, less(cmp));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, preserving
+/// the order of equal elements, with timsort.
+///
+/// See `timsort_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// sortrs::timsort_by_cmp(&mut v, |a, b| a.0.cmp(&b.0));
+/// assert!(v == [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+pub fn timsort_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_by(v, less(cmp));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, preserving
+/// the order of equal elements.
+///
+/// See `sort_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [("b", 2), ("a", 2), ("c", 1)];
+/// sortrs::sort_by_cmp(&mut v, |a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)));
+/// assert!(v == [("c", 1), ("a", 2), ("b", 2)]);
+/// ```
+pub fn sort_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_by(v, less(cmp));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements.
+///
+/// See `sort_unstable_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = ["pear", "Fig", "apple"];
+/// sortrs::sort_unstable_by_cmp(&mut v, |a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+/// assert!(v == ["apple", "Fig", "pear"]);
+/// ```
+pub fn sort_unstable_by_cmp<T, F>(v: &mut [T], cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    record_sorted(v.len());
+    introsort_impl(v, less(cmp));
+}
+
+/// A less than comparison between values of type `T`, implemented by
+/// comparators that carry context, such as a collation table or a side table
+/// of weights.
+///
+/// Every `Fn(&T, &T) -> bool` closure implements `Compare`, so the `_with`
+/// sorts also accept anything the `_by` sorts do.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Compare;
+///
+/// // orders indices by the weight stored for them
+/// struct ByWeight<'a>(&'a [u32]);
+///
+/// impl<'a> Compare<usize> for ByWeight<'a> {
+///     fn lt(&self, a: &usize, b: &usize) -> bool {
+///         self.0[*a] < self.0[*b]
+///     }
+/// }
+///
+/// let weights = [30, 10, 20];
+/// let mut v = [0, 1, 2];
+/// sortrs::sort_with(&mut v, &ByWeight(&weights));
+/// assert!(v == [1, 2, 0]);
+/// ```
+pub trait Compare<T: ?Sized> {
+    /// Returns `true` if `a` is less than `b`.
+    fn lt(&self, a: &T, b: &T) -> bool;
+}
+
+impl<T: ?Sized, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> bool,
+{
+    #[inline]
+    fn lt(&self, a: &T, b: &T) -> bool {
+        self(a, b)
+    }
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// insertion sort.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::insertsort_with(&mut v, &|a: &i32, b: &i32| b < a);
+/// assert!(v == [5, 4, 3, 2, 1]);
+/// ```
+pub fn insertsort_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        insertsort_impl(v.as_mut_ptr(), len, &|a: &T, b: &T| cmp.lt(a, b));
+    }
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// heapsort.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::heapsort_with(&mut v, &|a: &i32, b: &i32| a < b);
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn heapsort_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        heapsort_impl(v.as_mut_ptr(), len, &|a: &T, b: &T| cmp.lt(a, b));
+    }
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, with
+/// introsort.
+///
+/// See `introsort_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::introsort_with(&mut v, &|a: &i32, b: &i32| a < b);
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn introsort_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    record_sorted(v.len());
+    introsort_impl(v, |a, b| cmp.lt(a, b));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, preserving
+/// the order of equal elements, with timsort.
+///
+/// See `timsort_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// sortrs::timsort_with(&mut v, &|a: &(i32, char), b: &(i32, char)| a.0 < b.0);
+/// assert!(v == [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+pub fn timsort_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    timsort_by(v, |a, b| cmp.lt(a, b));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements, preserving
+/// the order of equal elements.
+///
+/// See `sort_by` for its guarantees, and `Compare` for an example.
+pub fn sort_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    timsort_by(v, |a, b| cmp.lt(a, b));
+}
+
+/// Sorts the slice, in place, using `cmp` to compare elements.
+///
+/// See `sort_unstable_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let checked = sortrs::CheckedCmp::new(|a: &u32, b: &u32| a < b);
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::sort_unstable_with(&mut v, &checked);
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// assert!(checked.calls() > 0);
+/// ```
+pub fn sort_unstable_with<T, C>(v: &mut [T], cmp: &C)
+where
+    C: Compare<T> + ?Sized,
+{
+    record_sorted(v.len());
+    introsort_impl(v, |a, b| cmp.lt(a, b));
+}
@@ -0,0 +1,162 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Free functions with the names and signatures of the standard library's
+//! slice sorting methods, backed by this crate's sorts.
+//!
+//! Each function takes the slice as its first argument and otherwise matches
+//! the method of the same name: `Ord` bounds, `FnMut` comparators returning
+//! an `Ordering` and the same panics. Code written against the methods in
+//! their function call form, such as `<[T]>::sort_unstable(v)`, switches to
+//! this crate by calling `compat::sort_unstable(v)` instead.
+//!
+//! The stable sorts are `timsort_by` and the unstable ones `introsort_by`,
+//! each `O(n log n)` worst-case, and the selections are
+//! `select_nth_unstable_by`, `O(n)` worst-case.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sortrs::compat;
+//!
+//! let mut v = [5, 4, 1, 3, 2];
+//! compat::sort_unstable_by(&mut v, |a, b| b.cmp(a));
+//! assert!(v == [5, 4, 3, 2, 1]);
+//!
+//! let (_, median, _) = compat::select_nth_unstable(&mut v, 2);
+//! assert_eq!(*median, 3);
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+use super::cmp::less;
+use super::select::select_nth_unstable_by as select_nth;
+use super::{introsort_by_cached_key, introsort_impl, record_sorted, timsort_by};
+
+/// Adapts an `FnMut` key extraction into a `Fn` one, see `cmp::less`.
+fn key_fn<T, K, F>(f: F) -> impl Fn(&T) -> K
+where
+    F: FnMut(&T) -> K,
+{
+    let f = RefCell::new(f);
+    move |x| (f.borrow_mut())(x)
+}
+
+/// Sorts the slice, preserving the order of equal elements, like
+/// `slice::sort`.
+pub fn sort<T: Ord>(v: &mut [T]) {
+    timsort_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts the slice with a comparator, preserving the order of equal
+/// elements, like `slice::sort_by`.
+pub fn sort_by<T, F>(v: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_by(v, less(compare));
+}
+
+/// Sorts the slice with a key extraction function, preserving the order of
+/// equal elements, like `slice::sort_by_key`.
+pub fn sort_by_key<T, K, F>(v: &mut [T], f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let f = key_fn(f);
+    timsort_by(v, |a, b| f(a).lt(&f(b)));
+}
+
+/// Sorts the slice with a key extraction function called once per element,
+/// preserving the order of equal elements, like `slice::sort_by_cached_key`.
+pub fn sort_by_cached_key<T, K, F>(v: &mut [T], f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    introsort_by_cached_key(v, key_fn(f));
+}
+
+/// Sorts the slice without preserving the order of equal elements, like
+/// `slice::sort_unstable`.
+pub fn sort_unstable<T: Ord>(v: &mut [T]) {
+    record_sorted(v.len());
+    introsort_impl(v, |a, b| a.lt(b));
+}
+
+/// Sorts the slice with a comparator, without preserving the order of equal
+/// elements, like `slice::sort_unstable_by`.
+pub fn sort_unstable_by<T, F>(v: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    record_sorted(v.len());
+    introsort_impl(v, less(compare));
+}
+
+/// Sorts the slice with a key extraction function, without preserving the
+/// order of equal elements, like `slice::sort_unstable_by_key`.
+pub fn sort_unstable_by_key<T, K, F>(v: &mut [T], f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let f = key_fn(f);
+    record_sorted(v.len());
+    introsort_impl(v, |a, b| f(a).lt(&f(b)));
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position
+/// and splits the slice around it, like `slice::select_nth_unstable`.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+pub fn select_nth_unstable<T: Ord>(v: &mut [T], index: usize) -> (&mut [T], &mut T, &mut [T]) {
+    select_nth(v, index, |a, b| a.lt(b))
+}
+
+/// Reorders the slice with a comparator so the element at `index` is in its
+/// sorted position and splits the slice around it, like
+/// `slice::select_nth_unstable_by`.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+pub fn select_nth_unstable_by<T, F>(
+    v: &mut [T],
+    index: usize,
+    compare: F,
+) -> (&mut [T], &mut T, &mut [T])
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select_nth(v, index, less(compare))
+}
+
+/// Reorders the slice with a key extraction function so the element at
+/// `index` is in its sorted position and splits the slice around it, like
+/// `slice::select_nth_unstable_by_key`.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+pub fn select_nth_unstable_by_key<T, K, F>(
+    v: &mut [T],
+    index: usize,
+    f: F,
+) -> (&mut [T], &mut T, &mut [T])
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let f = key_fn(f);
+    select_nth(v, index, |a, b| f(a).lt(&f(b)))
+}
@@ -0,0 +1,694 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The crate's in place sorts as methods on slices.
+
+use std::cmp::Ordering;
+
+use super::{
+    approx_sort_by, heap_pop_k_by, heapsort_by, heapsort_by_cmp, heapsort_by_key, heapsort_with,
+    insertsort_by, insertsort_by_cmp, insertsort_by_key, insertsort_with, introsort_by,
+    introsort_by_cached_key, introsort_by_cmp, introsort_by_key, introsort_tiebreak_by,
+    introsort_with, introsort_with_config_by, partial_sort_by, partition3_stable, partition_at_by,
+    radix_sort_by_key, radix_sort_multi_key, repair_sorted_by, sort_by_cmp, sort_chunks_by,
+    sort_ord, sort_presorted_by, sort_strided_by, sort_strings, sort_strings_ci,
+    sort_unstable_by_cmp, sort_unstable_ord, sort_unstable_with, sort_with, timsort_by,
+    timsort_by_cmp, timsort_with, Class, Compare, IntrosortConfig, Order,
+};
+
+/// The crate's in place sorts as methods on slices, and so on anything that
+/// derefs to one such as `Vec`.
+///
+/// Each method calls the free function of the same name with the slice as
+/// its first argument, see the function for details. Every function that
+/// sorts or partitions a single slice in place has a method, except:
+///
+/// * `sort`, `sort_by`, `sort_unstable`, `sort_unstable_by`,
+///   `select_nth_unstable`, `select_nth_unstable_by` and
+///   `select_nth_unstable_by_key`, as the slice methods of those names would
+///   be called instead. Replace `v.sort()` with `v.timsort()` and
+///   `v.sort_unstable()` with `v.introsort()`.
+/// * The selection and statistics functions, such as `median`, `quantiles`
+///   and `extremes_k`, which are used for the values they return.
+/// * Functions of more than one slice or that write their output elsewhere,
+///   such as `sort_by_key_slice`, `partial_sort_copy` and
+///   `sort_copy_into_uninit`, and the unsafe sorts of `MaybeUninit` buffers
+///   such as `introsort_uninit`.
+/// * The functions of the `compat`, `offload` and `wasm` modules.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::SliceSortExt;
+///
+/// let mut v = vec![5, 4, 1, 3, 2];
+/// v.introsort();
+/// assert!(v == [1, 2, 3, 4, 5]);
+///
+/// v.heapsort_by(|a, b| b.lt(a));
+/// assert!(v == [5, 4, 3, 2, 1]);
+///
+/// v.partial_sort(2);
+/// assert!(v[..2] == [1, 2]);
+/// ```
+pub trait SliceSortExt<T> {
+    /// Sorts the slice with insertion sort, see `insertsort`.
+    fn insertsort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice with insertion sort, see `insertsort_by`.
+    fn insertsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice by a key with insertion sort, see `insertsort_by_key`.
+    fn insertsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K;
+
+    /// Sorts the slice with insertion sort, see `insertsort_by_cmp`.
+    fn insertsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice with insertion sort, see `insertsort_with`.
+    fn insertsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Sorts the slice with heapsort, see `heapsort`.
+    fn heapsort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice with heapsort, see `heapsort_by`.
+    fn heapsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice by a key with heapsort, see `heapsort_by_key`.
+    fn heapsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K;
+
+    /// Sorts the slice with heapsort, see `heapsort_by_cmp`.
+    fn heapsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice with heapsort, see `heapsort_with`.
+    fn heapsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Moves the `k` largest elements, in ascending order, to the end of the
+    /// slice, see `heap_pop_k`.
+    fn heap_pop_k(&mut self, k: usize)
+    where
+        T: PartialOrd;
+
+    /// Moves the `k` largest elements, in ascending order, to the end of the
+    /// slice, see `heap_pop_k_by`.
+    fn heap_pop_k_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the `k` smallest elements into the first `k` places, see
+    /// `partial_sort`.
+    fn partial_sort(&mut self, k: usize)
+    where
+        T: PartialOrd;
+
+    /// Sorts the `k` smallest elements into the first `k` places, see
+    /// `partial_sort_by`.
+    fn partial_sort_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice with introsort, see `introsort`.
+    fn introsort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice with introsort, see `introsort_by`.
+    fn introsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice by a key with introsort, see `introsort_by_key`.
+    fn introsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K;
+
+    /// Sorts the slice by a key extracted once per element, see
+    /// `introsort_by_cached_key`.
+    fn introsort_by_cached_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K;
+
+    /// Sorts the slice with introsort, see `introsort_by_cmp`.
+    fn introsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice with introsort, see `introsort_with`.
+    fn introsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Sorts the slice with introsort tuned by `config`, see
+    /// `introsort_with_config_by`.
+    fn introsort_with_config_by<F>(&mut self, config: &IntrosortConfig<T>, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice with introsort keeping equal elements in order, see
+    /// `introsort_tiebreak`.
+    fn introsort_tiebreak(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice with introsort keeping equal elements in order, see
+    /// `introsort_tiebreak_by`.
+    fn introsort_tiebreak_by<F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice with timsort, see `timsort`.
+    fn timsort(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice with timsort, see `timsort_by`.
+    fn timsort_by<F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice with timsort, see `timsort_by_cmp`.
+    fn timsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice with timsort, see `timsort_with`.
+    fn timsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Sorts the slice of totally ordered elements, preserving the order of
+    /// equal elements, see `sort_ord`.
+    fn sort_ord(&mut self)
+    where
+        T: Ord;
+
+    /// Sorts the slice of totally ordered elements, see `sort_unstable_ord`.
+    fn sort_unstable_ord(&mut self)
+    where
+        T: Ord;
+
+    /// Sorts the slice, preserving the order of equal elements, see
+    /// `sort_by_cmp`.
+    fn sort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice, see `sort_unstable_by_cmp`.
+    fn sort_unstable_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering;
+
+    /// Sorts the slice, preserving the order of equal elements, see
+    /// `sort_with`.
+    fn sort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Sorts the slice, see `sort_unstable_with`.
+    fn sort_unstable_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized;
+
+    /// Sorts the slice by an integer key with radix sort, see
+    /// `radix_sort_by_key`.
+    fn radix_sort_by_key<F>(&mut self, key: F)
+    where
+        F: Fn(&T) -> u64;
+
+    /// Sorts the slice by several integer keys with radix sort, see
+    /// `radix_sort_multi_key`.
+    fn radix_sort_multi_key(&mut self, keys: &[fn(&T) -> u64]);
+
+    /// Sorts the strings in byte order, see `sort_strings`.
+    fn sort_strings(&mut self)
+    where
+        T: AsRef<str>;
+
+    /// Sorts the strings ignoring case, see `sort_strings_ci`.
+    fn sort_strings_ci(&mut self)
+    where
+        T: AsRef<str>;
+
+    /// Sorts each `N` element chunk of the slice, see `sort_chunks`.
+    fn sort_chunks<const N: usize>(&mut self)
+    where
+        T: PartialOrd;
+
+    /// Sorts each `N` element chunk of the slice, see `sort_chunks_by`.
+    fn sort_chunks_by<const N: usize, F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts every `stride`th element starting at `channel`, see
+    /// `sort_strided`.
+    fn sort_strided(&mut self, stride: usize, channel: usize)
+    where
+        T: PartialOrd;
+
+    /// Sorts every `stride`th element starting at `channel`, see
+    /// `sort_strided_by`.
+    fn sort_strided_by<F>(&mut self, stride: usize, channel: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts the slice, reversing or rotating it when that is enough, see
+    /// `sort_presorted`.
+    fn sort_presorted(&mut self) -> Order
+    where
+        T: PartialOrd;
+
+    /// Sorts the slice, reversing or rotating it when that is enough, see
+    /// `sort_presorted_by`.
+    fn sort_presorted_by<F>(&mut self, lt: F) -> Order
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts a slice whose elements are at most `k` places from their sorted
+    /// positions, see `approx_sort`.
+    fn approx_sort(&mut self, k: usize)
+    where
+        T: PartialOrd;
+
+    /// Sorts a slice whose elements are at most `k` places from their sorted
+    /// positions, see `approx_sort_by`.
+    fn approx_sort_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Sorts an almost sorted slice by moving the elements out of place, see
+    /// `repair_sorted`.
+    fn repair_sorted(&mut self, max_displaced: usize) -> Option<usize>
+    where
+        T: PartialOrd;
+
+    /// Sorts an almost sorted slice by moving the elements out of place, see
+    /// `repair_sorted_by`.
+    fn repair_sorted_by<F>(&mut self, max_displaced: usize, lt: F) -> Option<usize>
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Partitions the slice about the element at `pivot`, see
+    /// `partition_at`.
+    fn partition_at(&mut self, pivot: usize) -> (usize, usize)
+    where
+        T: PartialOrd;
+
+    /// Partitions the slice about the element at `pivot`, see
+    /// `partition_at_by`.
+    fn partition_at_by<F>(&mut self, pivot: usize, lt: F) -> (usize, usize)
+    where
+        F: Fn(&T, &T) -> bool;
+
+    /// Partitions the slice into three classes keeping their order, see
+    /// `partition3_stable`.
+    fn partition3_stable<F>(&mut self, classify: F) -> (usize, usize)
+    where
+        F: Fn(&T) -> Class;
+}
+
+impl<T> SliceSortExt<T> for [T] {
+    fn insertsort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        insertsort_by(self, |a, b| a.lt(b));
+    }
+
+    fn insertsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool,
+    {
+        insertsort_by(self, lt);
+    }
+
+    fn insertsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        insertsort_by_key(self, key);
+    }
+
+    fn insertsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        insertsort_by_cmp(self, cmp);
+    }
+
+    fn insertsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        insertsort_with(self, cmp);
+    }
+
+    fn heapsort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        heapsort_by(self, |a, b| a.lt(b));
+    }
+
+    fn heapsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool,
+    {
+        heapsort_by(self, lt);
+    }
+
+    fn heapsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        heapsort_by_key(self, key);
+    }
+
+    fn heapsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        heapsort_by_cmp(self, cmp);
+    }
+
+    fn heapsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        heapsort_with(self, cmp);
+    }
+
+    fn heap_pop_k(&mut self, k: usize)
+    where
+        T: PartialOrd,
+    {
+        heap_pop_k_by(self, k, |a, b| a.lt(b));
+    }
+
+    fn heap_pop_k_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        heap_pop_k_by(self, k, lt);
+    }
+
+    fn partial_sort(&mut self, k: usize)
+    where
+        T: PartialOrd,
+    {
+        partial_sort_by(self, k, |a, b| a.lt(b));
+    }
+
+    fn partial_sort_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        partial_sort_by(self, k, lt);
+    }
+
+    fn introsort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        introsort_by(self, |a, b| a.lt(b));
+    }
+
+    fn introsort_by<F>(&mut self, lt: F)
+    where
+        T: PartialOrd,
+        F: Fn(&T, &T) -> bool,
+    {
+        introsort_by(self, lt);
+    }
+
+    fn introsort_by_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        introsort_by_key(self, key);
+    }
+
+    fn introsort_by_cached_key<K, F>(&mut self, key: F)
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        introsort_by_cached_key(self, key);
+    }
+
+    fn introsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        introsort_by_cmp(self, cmp);
+    }
+
+    fn introsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        introsort_with(self, cmp);
+    }
+
+    fn introsort_with_config_by<F>(&mut self, config: &IntrosortConfig<T>, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        introsort_with_config_by(self, config, lt);
+    }
+
+    fn introsort_tiebreak(&mut self)
+    where
+        T: PartialOrd,
+    {
+        introsort_tiebreak_by(self, |a, b| a.lt(b));
+    }
+
+    fn introsort_tiebreak_by<F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        introsort_tiebreak_by(self, lt);
+    }
+
+    fn timsort(&mut self)
+    where
+        T: PartialOrd,
+    {
+        timsort_by(self, |a, b| a.lt(b));
+    }
+
+    fn timsort_by<F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        timsort_by(self, lt);
+    }
+
+    fn timsort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        timsort_by_cmp(self, cmp);
+    }
+
+    fn timsort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        timsort_with(self, cmp);
+    }
+
+    fn sort_ord(&mut self)
+    where
+        T: Ord,
+    {
+        sort_ord(self);
+    }
+
+    fn sort_unstable_ord(&mut self)
+    where
+        T: Ord,
+    {
+        sort_unstable_ord(self);
+    }
+
+    fn sort_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sort_by_cmp(self, cmp);
+    }
+
+    fn sort_unstable_by_cmp<F>(&mut self, cmp: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        sort_unstable_by_cmp(self, cmp);
+    }
+
+    fn sort_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        sort_with(self, cmp);
+    }
+
+    fn sort_unstable_with<C>(&mut self, cmp: &C)
+    where
+        C: Compare<T> + ?Sized,
+    {
+        sort_unstable_with(self, cmp);
+    }
+
+    fn radix_sort_by_key<F>(&mut self, key: F)
+    where
+        F: Fn(&T) -> u64,
+    {
+        radix_sort_by_key(self, key);
+    }
+
+    fn radix_sort_multi_key(&mut self, keys: &[fn(&T) -> u64]) {
+        radix_sort_multi_key(self, keys);
+    }
+
+    fn sort_strings(&mut self)
+    where
+        T: AsRef<str>,
+    {
+        sort_strings(self);
+    }
+
+    fn sort_strings_ci(&mut self)
+    where
+        T: AsRef<str>,
+    {
+        sort_strings_ci(self);
+    }
+
+    fn sort_chunks<const N: usize>(&mut self)
+    where
+        T: PartialOrd,
+    {
+        sort_chunks_by::<N, _, _>(self, |a, b| a.lt(b));
+    }
+
+    fn sort_chunks_by<const N: usize, F>(&mut self, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        sort_chunks_by::<N, _, _>(self, lt);
+    }
+
+    fn sort_strided(&mut self, stride: usize, channel: usize)
+    where
+        T: PartialOrd,
+    {
+        sort_strided_by(self, stride, channel, |a, b| a.lt(b));
+    }
+
+    fn sort_strided_by<F>(&mut self, stride: usize, channel: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        sort_strided_by(self, stride, channel, lt);
+    }
+
+    fn sort_presorted(&mut self) -> Order
+    where
+        T: PartialOrd,
+    {
+        sort_presorted_by(self, |a, b| a.lt(b))
+    }
+
+    fn sort_presorted_by<F>(&mut self, lt: F) -> Order
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        sort_presorted_by(self, lt)
+    }
+
+    fn approx_sort(&mut self, k: usize)
+    where
+        T: PartialOrd,
+    {
+        approx_sort_by(self, k, |a, b| a.lt(b));
+    }
+
+    fn approx_sort_by<F>(&mut self, k: usize, lt: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        approx_sort_by(self, k, lt);
+    }
+
+    fn repair_sorted(&mut self, max_displaced: usize) -> Option<usize>
+    where
+        T: PartialOrd,
+    {
+        repair_sorted_by(self, max_displaced, |a, b| a.lt(b))
+    }
+
+    fn repair_sorted_by<F>(&mut self, max_displaced: usize, lt: F) -> Option<usize>
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        repair_sorted_by(self, max_displaced, lt)
+    }
+
+    fn partition_at(&mut self, pivot: usize) -> (usize, usize)
+    where
+        T: PartialOrd,
+    {
+        partition_at_by(self, pivot, |a, b| a.lt(b))
+    }
+
+    fn partition_at_by<F>(&mut self, pivot: usize, lt: F) -> (usize, usize)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        partition_at_by(self, pivot, lt)
+    }
+
+    fn partition3_stable<F>(&mut self, classify: F) -> (usize, usize)
+    where
+        F: Fn(&T) -> Class,
+    {
+        partition3_stable(self, classify)
+    }
+}
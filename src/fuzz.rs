@@ -0,0 +1,223 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! An oracle for fuzzing the sorts.
+//!
+//! `sort_oracle` turns any byte string into a sort to run and checks the
+//! result, panicking if an invariant is broken. Call it from a `cargo fuzz`
+//! target:
+//!
+//! ```rust,ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| sortrs::fuzz::sort_oracle(data));
+//! ```
+//!
+//! Most of the sorts work on raw pointers, so the fuzz target is best built
+//! with a sanitizer, which `cargo fuzz` does by default. The oracle's checks
+//! catch lost or duplicated elements and the sanitizer catches the out of
+//! bounds accesses and double drops that may cause them.
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+use super::{
+    heapsort_by, insertsort_by, introsort_by, introsort_impl, introsort_with_config_by,
+    repair_sorted_by, sort_by, sort_chunks_by, sort_presorted_by, sort_unstable_by, timsort_by,
+    Fallback, Insertion, IntrosortConfig,
+};
+
+/// Bytes at the front of the input that choose what to run.
+const HEADER_LEN: usize = 5;
+
+/// Number of algorithms `run` chooses between.
+const ALGORITHMS: u8 = 12;
+
+/// Runs the sort an input describes and checks the result.
+///
+/// The first five bytes choose the algorithm, the element type (`u8`, `i32`,
+/// `f64` or `String`), the input pattern (as is, sorted, reversed, few
+/// unique or nearly sorted) and a comparator quirk and its parameter. The
+/// rest of the input is decoded into the keys. Inputs shorter than the
+/// header do nothing.
+///
+/// Each key is paired with its position, which lets the oracle check that
+/// every element comes out exactly once whatever the comparator does, even
+/// when it panics part way through the sort. When the comparator is a
+/// consistent order the output must also be sorted, and stable algorithms
+/// must keep equal keys in input order. The quirks are a reversed order, a
+/// non-strict order (`<=`), random answers and a panic after some number of
+/// comparisons. A sort may panic when the order is inconsistent, but must
+/// not otherwise.
+///
+/// # Panics
+///
+/// Panics if the sort breaks one of these invariants.
+pub fn sort_oracle(input: &[u8]) {
+    if input.len() < HEADER_LEN {
+        return;
+    }
+    let (header, data) = input.split_at(HEADER_LEN);
+    let case = Case {
+        algorithm: header[0] % ALGORITHMS,
+        pattern: header[2] % 5,
+        quirk: header[3] % 5,
+        param: header[4],
+    };
+    match header[1] % 4 {
+        0 => case.check(data.to_vec(), true),
+        1 => case.check(
+            data.chunks_exact(4)
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            true,
+        ),
+        2 => {
+            let keys = data
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect::<Vec<f64>>();
+            // NaN makes the order partial, so only the permutation is checked
+            let total = !keys.iter().any(|x| x.is_nan());
+            case.check(keys, total)
+        }
+        _ => case.check(
+            data.split(|&b| b == b',')
+                .map(|s| String::from_utf8_lossy(s).into_owned())
+                .collect(),
+            true,
+        ),
+    }
+}
+
+/// The decoded header of an oracle input.
+struct Case {
+    algorithm: u8,
+    pattern: u8,
+    quirk: u8,
+    param: u8,
+}
+
+impl Case {
+    fn check<K: PartialOrd + Clone>(&self, mut keys: Vec<K>, total: bool) {
+        let len = keys.len();
+        match self.pattern {
+            1 => introsort_impl(&mut keys, |a, b| a.lt(b)),
+            2 => introsort_impl(&mut keys, |a, b| b.lt(a)),
+            3 => {
+                for i in 4..len {
+                    keys[i] = keys[i % 4].clone();
+                }
+            }
+            4 => {
+                introsort_impl(&mut keys, |a, b| a.lt(b));
+                if len > 1 {
+                    keys.swap(0, len / 2);
+                }
+            }
+            _ => {}
+        }
+        let mut v = keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect::<Vec<(K, usize)>>();
+
+        let calls = Cell::new(0usize);
+        let state = Cell::new(u64::from(self.param) | 1);
+        let quirk = self.quirk;
+        let param = self.param as usize;
+        let lt = |a: &(K, usize), b: &(K, usize)| {
+            calls.set(calls.get() + 1);
+            match quirk {
+                1 => b.0.lt(&a.0),
+                2 => !b.0.lt(&a.0),
+                3 => {
+                    let mut x = state.get();
+                    x ^= x << 13;
+                    x ^= x >> 7;
+                    x ^= x << 17;
+                    state.set(x);
+                    x & 1 == 0
+                }
+                4 if calls.get() > param * 4 => panic!("comparator panicked on purpose"),
+                _ => a.0.lt(&b.0),
+            }
+        };
+        let algorithm = self.algorithm;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run(algorithm, &mut v, &lt)));
+
+        // whatever happened every element must be there exactly once
+        let mut seen = vec![false; len];
+        for x in &v {
+            assert!(!seen[x.1], "element {} was duplicated", x.1);
+            seen[x.1] = true;
+        }
+        assert_eq!(v.len(), len);
+
+        // a sort may panic on an inconsistent order, but not on a consistent one
+        let consistent = total && (quirk == 0 || quirk == 1);
+        let stable = match result {
+            Ok(stable) => stable,
+            Err(_) => {
+                assert!(
+                    quirk == 4 || !consistent,
+                    "sort panicked on a consistent order"
+                );
+                return;
+            }
+        };
+        if !consistent {
+            return;
+        }
+        let less = |a: &K, b: &K| if quirk == 1 { b.lt(a) } else { a.lt(b) };
+        for w in v.windows(2) {
+            assert!(!less(&w[1].0, &w[0].0), "output is not sorted");
+            if stable && !less(&w[0].0, &w[1].0) {
+                assert!(w[0].1 < w[1].1, "equal elements were reordered");
+            }
+        }
+    }
+}
+
+/// Sorts `v` with the algorithm numbered `algorithm`, returning whether that
+/// algorithm is stable.
+fn run<T: PartialOrd>(algorithm: u8, v: &mut [T], lt: &dyn Fn(&T, &T) -> bool) -> bool {
+    let config = |fallback, insertion| IntrosortConfig {
+        fallback,
+        insertion,
+        depth_multiplier: 1,
+        insertion_threshold: 8,
+    };
+    match algorithm {
+        0 => insertsort_by(v, lt),
+        1 => heapsort_by(v, lt),
+        2 => introsort_by(v, lt),
+        3 => timsort_by(v, lt),
+        4 => sort_by(v, lt),
+        5 => sort_unstable_by(v, lt),
+        6 => introsort_with_config_by(v, &config(Fallback::Heapsort, Insertion::PerLeaf), lt),
+        7 => introsort_with_config_by(v, &config(Fallback::Insertsort, Insertion::Final), lt),
+        8 => {
+            sort_chunks_by::<5, _, _>(v, lt);
+            // only each chunk is sorted, finish with a stable sort
+            timsort_by(v, lt);
+        }
+        9 => {
+            sort_presorted_by(v, lt);
+        }
+        10 => {
+            repair_sorted_by(v, 4, lt);
+        }
+        _ => {
+            let mut config = config(Fallback::Heapsort, Insertion::Final);
+            config.depth_multiplier = 0;
+            introsort_with_config_by(v, &config, lt);
+        }
+    }
+    matches!(algorithm, 0 | 3 | 4)
+}
@@ -0,0 +1,249 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Operations on groups of equal elements in sorted data.
+
+use std::ops::Range;
+
+use super::argsort::apply_permutation;
+use super::sched::{Scheduler, Task, ThreadScheduler};
+use super::shard::{range_partition_by, sample_splitters_by};
+use super::{introsort_impl, partial_sort_impl};
+
+/// Run-length encodes `v` into `(value, count)` pairs.
+///
+/// Each maximal run of equal adjacent elements becomes a single pair, so
+/// when `v` is sorted every distinct value appears exactly once in the
+/// output, in ascending order.
+///
+/// # Examples
+///
+/// ```rust
+/// let runs = sortrs::rle_encode(&[1, 1, 2, 5, 5, 5]);
+/// assert!(runs == [(1, 2), (2, 1), (5, 3)]);
+/// ```
+pub fn rle_encode<T: PartialEq + Clone>(v: &[T]) -> Vec<(T, usize)> {
+    let mut runs: Vec<(T, usize)> = Vec::new();
+    let mut i = 0;
+    while i < v.len() {
+        let mut j = i + 1;
+        while j < v.len() && v[j] == v[i] {
+            j += 1;
+        }
+        runs.push((v[i].clone(), j - i));
+        i = j;
+    }
+    runs
+}
+
+/// Expands `(value, count)` pairs produced by `rle_encode` back into the
+/// original sequence.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = sortrs::rle_decode(&[(1, 2), (2, 1), (5, 3)]);
+/// assert!(v == [1, 1, 2, 5, 5, 5]);
+/// ```
+pub fn rle_decode<T: Clone>(runs: &[(T, usize)]) -> Vec<T> {
+    let mut v = Vec::with_capacity(runs.iter().map(|run| run.1).sum());
+    for &(ref x, count) in runs {
+        for _ in 0..count {
+            v.push(x.clone());
+        }
+    }
+    v
+}
+
+/// Folds each run of consecutive elements of `v` with equal keys into one
+/// value, returning a `(key, value)` pair per run.
+///
+/// Every run starts from a clone of `init` and folds its elements in order
+/// with `fold`. When `v` is sorted by `key` this is a group-by: each distinct
+/// key appears exactly once in the output, in ascending order. `key` is
+/// called once per element. This is a single `O(n)` pass.
+///
+/// # Examples
+///
+/// ```rust
+/// let sales = [("apple", 3), ("apple", 2), ("kiwi", 5), ("pear", 1), ("pear", 4)];
+/// let totals = sortrs::aggregate_sorted_by_key(&sales, |s| s.0, 0, |sum, s| sum + s.1);
+/// assert!(totals == [("apple", 5), ("kiwi", 5), ("pear", 5)]);
+/// ```
+pub fn aggregate_sorted_by_key<T, K, A, F, G>(
+    v: &[T],
+    mut key: F,
+    init: A,
+    mut fold: G,
+) -> Vec<(K, A)>
+where
+    K: PartialEq,
+    A: Clone,
+    F: FnMut(&T) -> K,
+    G: FnMut(A, &T) -> A,
+{
+    let mut groups = Vec::new();
+    // the key and accumulator of the group being folded
+    let mut current: Option<(K, A)> = None;
+    for x in v {
+        let k = key(x);
+        current = Some(match current.take() {
+            Some((last, acc)) if last == k => (last, fold(acc, x)),
+            Some(done) => {
+                groups.push(done);
+                (k, fold(init.clone(), x))
+            }
+            None => (k, fold(init.clone(), x)),
+        });
+    }
+    groups.extend(current);
+    groups
+}
+
+/// Sorts `v` by the key extracted with `key` using up to `threads` threads
+/// and returns each distinct key with the range of `v` holding its group.
+///
+/// `key` is called once per element. The `(key, index)` pairs are divided
+/// into `threads` buckets of similar size by key range, with
+/// `sample_splitters_by` and `range_partition_by`, so every group falls in a
+/// single bucket. The buckets are sorted on scoped threads and the resulting
+/// order is applied to `v` with `apply_permutation`. This is
+/// `par_group_by_key_with` using a `ThreadScheduler`. Groups are returned in
+/// ascending key order and the sort is stable, so the elements of a group
+/// keep their input order.
+///
+/// # Panics
+///
+/// Panics if `threads` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut words = ["pear", "fig", "kiwi", "plum", "date", "apple"];
+/// let groups = sortrs::par_group_by_key(&mut words, |w| w.len(), 2);
+/// assert!(words == ["fig", "pear", "kiwi", "plum", "date", "apple"]);
+/// assert!(groups == [(3, 0..1), (4, 1..5), (5, 5..6)]);
+/// ```
+pub fn par_group_by_key<T, K, F>(v: &mut [T], key: F, threads: usize) -> Vec<(K, Range<usize>)>
+where
+    K: PartialOrd + Clone + Send,
+    F: Fn(&T) -> K,
+{
+    par_group_by_key_with(v, key, threads, &ThreadScheduler)
+}
+
+/// Sorts `v` by the key extracted with `key` using up to `threads` tasks run
+/// by `scheduler` and returns each distinct key with the range of `v` holding
+/// its group.
+///
+/// This is `par_group_by_key` with the bucket sorts handed to `scheduler`
+/// rather than spawned on threads, one task per bucket of more than one
+/// element. The result is the same whatever order the tasks run in.
+///
+/// # Panics
+///
+/// Panics if `threads` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::{Scheduler, Task};
+///
+/// // run the tasks one at a time, last first
+/// struct Reversed;
+///
+/// impl Scheduler for Reversed {
+///     fn join_all<'a>(&self, tasks: Vec<Task<'a>>) {
+///         for task in tasks.into_iter().rev() {
+///             task();
+///         }
+///     }
+/// }
+///
+/// let mut v = [3, 1, 2, 3, 1];
+/// let groups = sortrs::par_group_by_key_with(&mut v, |&x| x, 2, &Reversed);
+/// assert!(v == [1, 1, 2, 3, 3]);
+/// assert!(groups == [(1, 0..2), (2, 2..3), (3, 3..5)]);
+/// ```
+pub fn par_group_by_key_with<T, K, F, S>(
+    v: &mut [T],
+    key: F,
+    threads: usize,
+    scheduler: &S,
+) -> Vec<(K, Range<usize>)>
+where
+    K: PartialOrd + Clone + Send,
+    F: Fn(&T) -> K,
+    S: Scheduler + ?Sized,
+{
+    assert!(threads != 0, "there must be at least one thread");
+    let by_key = |a: &(K, usize), b: &(K, usize)| a.0.lt(&b.0);
+    let mut pairs = v
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (key(x), i))
+        .collect::<Vec<(K, usize)>>();
+    let splitters = sample_splitters_by(pairs.iter().cloned(), threads, by_key);
+    let bounds = range_partition_by(&mut pairs, &splitters, by_key);
+
+    // sort each bucket in its own task, by key and then input position
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut rest = &mut pairs[..];
+    for w in bounds.windows(2) {
+        let (bucket, tail) = rest.split_at_mut(w[1] - w[0]);
+        rest = tail;
+        if bucket.len() > 1 {
+            tasks.push(Box::new(move || {
+                introsort_impl(bucket, |a, b| by_key(a, b) || (!by_key(b, a) && a.1 < b.1))
+            }));
+        }
+    }
+    scheduler.join_all(tasks);
+
+    let perm = pairs.iter().map(|p| p.1).collect::<Vec<usize>>();
+    apply_permutation(v, &perm);
+
+    let mut groups: Vec<(K, Range<usize>)> = Vec::new();
+    for (i, (k, _)) in pairs.into_iter().enumerate() {
+        match groups.last_mut() {
+            // sorted, so a key not above the last group's is equal to it
+            Some(last) if !last.0.lt(&k) => last.1.end = i + 1,
+            _ => groups.push((k, i..i + 1)),
+        }
+    }
+    groups
+}
+
+/// Returns the `k` most frequent values in `v` with their number of
+/// occurrences, most frequent first.
+///
+/// The slice is sorted in place as a side effect. Values with the same count
+/// are returned in ascending order. If `v` holds fewer than `k` distinct
+/// values all of them are returned. Only the `k` most frequent of the `r`
+/// distinct values are put in order, with a partial sort in `O(r log k)`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [3, 1, 2, 3, 1, 3];
+/// let top = sortrs::top_k_frequent(&mut v, 2);
+/// assert!(top == [(3, 3), (1, 2)]);
+/// ```
+pub fn top_k_frequent<T: PartialOrd + Clone>(v: &mut [T], k: usize) -> Vec<(T, usize)> {
+    introsort_impl(v, |a, b| a.lt(b));
+
+    let mut runs = rle_encode(v);
+
+    // order by descending count, ties broken by ascending value, only the
+    // first k runs need to be in order
+    partial_sort_impl(&mut runs, k, &|a: &(T, usize), b: &(T, usize)| {
+        a.1 > b.1 || (a.1 == b.1 && a.0 < b.0)
+    });
+    runs.truncate(k);
+    runs
+}
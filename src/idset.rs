@@ -0,0 +1,304 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A set of `u32` IDs split into chunks of 65536, in the style of roaring
+//! bitmaps.
+
+use super::bits::merge_keyed;
+use super::iter::merge;
+use super::radix::lsd_radix_sort;
+use super::search::intersect_sorted;
+
+/// Words in the bitmap of a full chunk.
+const CHUNK_WORDS: usize = 1024;
+
+/// The IDs in one chunk, by their low 16 bits.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Container {
+    /// Sorted values.
+    Array(Vec<u16>),
+    /// Bit `x % 64` of word `x / 64` is set for each value `x`.
+    Bitmap(Box<[u64; CHUNK_WORDS]>),
+    /// Sorted, disjoint and non-adjacent inclusive ranges of values.
+    Runs(Vec<(u16, u16)>),
+}
+
+impl Container {
+    /// Stores the non-empty sorted and deduplicated `values` in whichever
+    /// container is smallest.
+    fn from_sorted(values: &[u16]) -> Container {
+        let runs = 1 + values.windows(2).filter(|w| w[1] != w[0] + 1).count();
+        let (runs_size, array_size, bitmap_size) = (4 * runs, 2 * values.len(), 8 * CHUNK_WORDS);
+        if runs_size < array_size && runs_size < bitmap_size {
+            let mut ranges: Vec<(u16, u16)> = Vec::with_capacity(runs);
+            for &x in values {
+                match ranges.last_mut() {
+                    Some(r) if r.1 + 1 == x => r.1 = x,
+                    _ => ranges.push((x, x)),
+                }
+            }
+            Container::Runs(ranges)
+        } else if array_size <= bitmap_size {
+            Container::Array(values.to_vec())
+        } else {
+            let mut words = Box::new([0u64; CHUNK_WORDS]);
+            for &x in values {
+                words[x as usize / 64] |= 1 << (x % 64);
+            }
+            Container::Bitmap(words)
+        }
+    }
+
+    fn len(&self) -> usize {
+        match *self {
+            Container::Array(ref values) => values.len(),
+            Container::Bitmap(ref words) => words.iter().map(|w| w.count_ones() as usize).sum(),
+            Container::Runs(ref ranges) => ranges.iter().map(|r| (r.1 - r.0) as usize + 1).sum(),
+        }
+    }
+
+    fn contains(&self, x: u16) -> bool {
+        match *self {
+            Container::Array(ref values) => values.binary_search(&x).is_ok(),
+            Container::Bitmap(ref words) => words[x as usize / 64] & (1 << (x % 64)) != 0,
+            Container::Runs(ref ranges) => {
+                // the last range starting at or before x
+                let i = ranges.partition_point(|r| r.0 <= x);
+                i > 0 && x <= ranges[i - 1].1
+            }
+        }
+    }
+
+    /// Returns the values in ascending order.
+    fn values(&self) -> Vec<u16> {
+        match *self {
+            Container::Array(ref values) => values.clone(),
+            Container::Bitmap(ref words) => {
+                let mut values = Vec::with_capacity(self.len());
+                for (i, &w) in words.iter().enumerate() {
+                    let mut bits = w;
+                    while bits != 0 {
+                        values.push((i * 64) as u16 + bits.trailing_zeros() as u16);
+                        // clear the lowest set bit
+                        bits &= bits - 1;
+                    }
+                }
+                values
+            }
+            Container::Runs(ref ranges) => ranges.iter().flat_map(|r| r.0..=r.1).collect(),
+        }
+    }
+
+    /// Returns the values as a bitmap.
+    fn words(&self) -> Box<[u64; CHUNK_WORDS]> {
+        match *self {
+            Container::Bitmap(ref words) => words.clone(),
+            _ => {
+                let mut words = Box::new([0u64; CHUNK_WORDS]);
+                for x in self.values() {
+                    words[x as usize / 64] |= 1 << (x % 64);
+                }
+                words
+            }
+        }
+    }
+
+    fn is_bitmap(&self) -> bool {
+        matches!(*self, Container::Bitmap(_))
+    }
+
+    /// Returns the values in both containers, or `None` if there are none.
+    fn intersection(&self, other: &Container) -> Option<Container> {
+        let values = if self.is_bitmap() && other.is_bitmap() {
+            let mut words = self.words();
+            for (w, o) in words.iter_mut().zip(other.words().iter()) {
+                *w &= *o;
+            }
+            Container::Bitmap(words).values()
+        } else {
+            intersect_sorted(&self.values(), &other.values())
+        };
+        non_empty(&values)
+    }
+
+    /// Returns the values in either container.
+    fn union(&self, other: &Container) -> Container {
+        let values = if self.is_bitmap() || other.is_bitmap() {
+            let mut words = self.words();
+            for (w, o) in words.iter_mut().zip(other.words().iter()) {
+                *w |= *o;
+            }
+            Container::Bitmap(words).values()
+        } else {
+            let mut values = merge(self.values(), other.values()).collect::<Vec<u16>>();
+            values.dedup();
+            values
+        };
+        Container::from_sorted(&values)
+    }
+
+    /// Returns the values in this container but not `other`, or `None` if
+    /// there are none.
+    fn difference(&self, other: &Container) -> Option<Container> {
+        let values = self
+            .values()
+            .into_iter()
+            .filter(|&x| !other.contains(x))
+            .collect::<Vec<u16>>();
+        non_empty(&values)
+    }
+}
+
+fn non_empty(values: &[u16]) -> Option<Container> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(Container::from_sorted(values))
+    }
+}
+
+/// A set of `u32` IDs stored as sorted chunks, in the style of roaring
+/// bitmaps.
+///
+/// IDs are grouped into chunks by their high 16 bits and only the chunks
+/// holding IDs are stored, in ascending order. Each chunk keeps the low 16
+/// bits of its IDs in whichever is smallest of a sorted array, a bitmap of
+/// 65536 bits or a list of runs of consecutive IDs, so sparse, dense and
+/// clustered sets all stay compact. Set operations work chunk by chunk.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::SortedIdSet;
+///
+/// let a = SortedIdSet::from_ids(&[70000, 3, 5, 4, 1 << 31]);
+/// let b = SortedIdSet::from_ids(&[4, 5, 6, 70000]);
+/// assert!(a.intersection(&b).to_sorted_ids() == [4, 5, 70000]);
+/// assert_eq!(a.union(&b).len(), 6);
+/// assert!(a.contains(1 << 31));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SortedIdSet {
+    chunks: Vec<(u16, Container)>,
+}
+
+impl SortedIdSet {
+    /// Returns an empty set.
+    pub fn new() -> SortedIdSet {
+        SortedIdSet { chunks: Vec::new() }
+    }
+
+    /// Returns the set of the IDs in `ids`, which may be in any order and
+    /// hold repeats.
+    ///
+    /// The IDs are copied and radix sorted, so this is `O(n)`.
+    pub fn from_ids(ids: &[u32]) -> SortedIdSet {
+        let pairs = ids.iter().map(|&id| (u64::from(id), 0)).collect();
+        let sorted = lsd_radix_sort(pairs)
+            .into_iter()
+            .map(|(id, _)| id as u32)
+            .collect::<Vec<u32>>();
+        SortedIdSet::from_sorted_ids(&sorted)
+    }
+
+    /// Returns the set of the IDs in the sorted `ids`. Repeated IDs are
+    /// stored once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ids` is not sorted in ascending order.
+    pub fn from_sorted_ids(ids: &[u32]) -> SortedIdSet {
+        let mut chunks = Vec::new();
+        let mut values: Vec<u16> = Vec::new();
+        for (i, &id) in ids.iter().enumerate() {
+            if i > 0 {
+                let last = ids[i - 1];
+                assert!(last <= id, "ids must be sorted");
+                if last >> 16 != id >> 16 {
+                    chunks.push(((last >> 16) as u16, Container::from_sorted(&values)));
+                    values.clear();
+                }
+            }
+            if values.last() != Some(&(id as u16)) {
+                values.push(id as u16);
+            }
+        }
+        if let Some(&last) = ids.last() {
+            chunks.push(((last >> 16) as u16, Container::from_sorted(&values)));
+        }
+        SortedIdSet { chunks }
+    }
+
+    /// Returns the number of IDs in the set.
+    pub fn len(&self) -> usize {
+        self.chunks.iter().map(|c| c.1.len()).sum()
+    }
+
+    /// Returns `true` if the set holds no IDs.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Returns `true` if the set holds `id`.
+    pub fn contains(&self, id: u32) -> bool {
+        match self
+            .chunks
+            .binary_search_by_key(&((id >> 16) as u16), |c| c.0)
+        {
+            Ok(i) => self.chunks[i].1.contains(id as u16),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns an iterator over the IDs in ascending order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = u32> + 'a {
+        self.chunks.iter().flat_map(|&(high, ref container)| {
+            container
+                .values()
+                .into_iter()
+                .map(move |low| u32::from(high) << 16 | u32::from(low))
+        })
+    }
+
+    /// Returns the IDs in the set in ascending order.
+    pub fn to_sorted_ids(&self) -> Vec<u32> {
+        self.iter().collect()
+    }
+
+    /// Returns the IDs in either set.
+    pub fn union(&self, other: &SortedIdSet) -> SortedIdSet {
+        self.combine(other, true, true, |a, b| Some(a.union(b)))
+    }
+
+    /// Returns the IDs in both sets.
+    pub fn intersection(&self, other: &SortedIdSet) -> SortedIdSet {
+        self.combine(other, false, false, Container::intersection)
+    }
+
+    /// Returns the IDs in this set but not in `other`.
+    pub fn difference(&self, other: &SortedIdSet) -> SortedIdSet {
+        self.combine(other, true, false, Container::difference)
+    }
+
+    /// Merges the chunks of two sets, keeping the chunks only in `self` if
+    /// `keep_self`, those only in `other` if `keep_other` and combining those
+    /// in both with `both`.
+    fn combine<F>(
+        &self,
+        other: &SortedIdSet,
+        keep_self: bool,
+        keep_other: bool,
+        both: F,
+    ) -> SortedIdSet
+    where
+        F: Fn(&Container, &Container) -> Option<Container>,
+    {
+        let chunks = merge_keyed(&self.chunks, &other.chunks, keep_self, keep_other, both);
+        SortedIdSet { chunks }
+    }
+}
@@ -0,0 +1,407 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lazy iterator adaptors producing sorted output.
+
+use std::iter::{Fuse, FusedIterator, Peekable};
+
+use super::{heapify, shift_down, shift_up};
+
+/// The less than function used by the adaptors that compare with `lt`.
+type LtFn<T> = fn(&T, &T) -> bool;
+
+/// Removes and returns the least element of `heap`, a heap with the least
+/// element at the root by `lt`, restoring the heap afterwards.
+fn pop_least<T, F>(heap: &mut Vec<T>, lt: &F) -> Option<T>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = heap.len();
+    if len == 0 {
+        return None;
+    }
+    heap.swap(0, len - 1);
+    let least = heap.pop();
+    if len > 2 {
+        let greater = |a: &T, b: &T| lt(b, a);
+        shift_down(heap.as_mut_ptr(), 0, len as isize - 2, &greater);
+    }
+    least
+}
+
+/// An iterator that sorts an almost sorted iterator, created by `ksorted`
+/// and `ksorted_by`.
+pub struct KSorted<I: Iterator, F> {
+    iter: Fuse<I>,
+    // a heap with the least element at the root
+    heap: Vec<I::Item>,
+    k: usize,
+    lt: F,
+}
+
+impl<I, F> KSorted<I, F>
+where
+    I: Iterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    fn push(&mut self, x: I::Item) {
+        let lt = &self.lt;
+        let greater = |a: &I::Item, b: &I::Item| lt(b, a);
+        self.heap.push(x);
+        let last = self.heap.len() as isize - 1;
+        shift_up(self.heap.as_mut_ptr(), last, &greater);
+    }
+}
+
+impl<I, F> Iterator for KSorted<I, F>
+where
+    I: Iterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        // buffer k + 1 elements, the next in sorted order must be among them
+        while self.heap.len() <= self.k {
+            match self.iter.next() {
+                Some(x) => self.push(x),
+                None => break,
+            }
+        }
+        pop_least(&mut self.heap, &self.lt)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.iter.size_hint();
+        let buffered = self.heap.len();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+/// Sorts an iterator whose items are each at most `k` positions from their
+/// sorted position, using `lt` to compare items.
+///
+/// Items are pulled lazily and at most `k + 1` are buffered in a heap, so
+/// each item costs `O(log k)` comparisons. If an item is further than `k`
+/// positions out of place the output is not sorted, but every item is still
+/// produced exactly once. The order of equal items is not preserved.
+///
+/// # Examples
+///
+/// ```rust
+/// // events carry a timestamp and can arrive up to 2 places late
+/// let events = vec![(1, 'a'), (3, 'c'), (2, 'b'), (4, 'd'), (6, 'f'), (5, 'e')];
+/// let sorted = sortrs::ksorted_by(events, 2, |a, b| a.0 < b.0)
+///     .map(|e| e.1)
+///     .collect::<String>();
+/// assert_eq!(sorted, "abcdef");
+/// ```
+pub fn ksorted_by<I, F>(iter: I, k: usize, lt: F) -> KSorted<I::IntoIter, F>
+where
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    KSorted {
+        iter: iter.into_iter().fuse(),
+        heap: Vec::with_capacity(k.saturating_add(1).min(1024)),
+        k,
+        lt,
+    }
+}
+
+/// Sorts an iterator whose items are each at most `k` positions from their
+/// sorted position.
+///
+/// This is equivalent to `ksorted_by(iter, k, |a, b| a.lt(b))`, see
+/// `ksorted_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = sortrs::ksorted(vec![2, 1, 3, 5, 4], 1).collect::<Vec<_>>();
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn ksorted<I>(iter: I, k: usize) -> KSorted<I::IntoIter, LtFn<I::Item>>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    ksorted_by(iter, k, PartialOrd::lt)
+}
+
+/// An iterator that sorts its items on demand, created by `incremental_sort`
+/// and `incremental_sort_by`.
+pub struct IncrementalSort<T, F> {
+    // a heap with the least element at the root
+    heap: Vec<T>,
+    lt: F,
+}
+
+impl<T, F> Iterator for IncrementalSort<T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        pop_least(&mut self.heap, &self.lt)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T, F> ExactSizeIterator for IncrementalSort<T, F> where F: Fn(&T, &T) -> bool {}
+
+impl<T, F> FusedIterator for IncrementalSort<T, F> where F: Fn(&T, &T) -> bool {}
+
+/// Sorts the items of `iter` lazily, producing them in ascending order as
+/// they are asked for, using `lt` to compare items.
+///
+/// The items are collected into a heap in `O(n)`, reusing the buffer when
+/// `iter` is a `Vec`, and each item produced then costs `O(log n)`. So a
+/// consumer that stops after `m` items pays `O(n + m log n)` rather than a
+/// full sort, which suits pagination and searches that stop early. Pass
+/// `v.iter()` to sort references to the elements of a slice without moving
+/// them. The order of equal items is not preserved.
+///
+/// # Examples
+///
+/// ```rust
+/// let names = vec!["mallory", "alice", "trent", "bob", "carol"];
+/// let mut sorted = sortrs::incremental_sort_by(names, |a, b| a.len() < b.len());
+/// assert_eq!(sorted.next(), Some("bob"));
+/// // only the remaining items are left in the heap
+/// assert_eq!(sorted.len(), 4);
+/// ```
+pub fn incremental_sort_by<I, F>(iter: I, lt: F) -> IncrementalSort<I::Item, F>
+where
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    let mut heap = iter.into_iter().collect::<Vec<I::Item>>();
+    {
+        let greater = |a: &I::Item, b: &I::Item| lt(b, a);
+        heapify(heap.as_mut_ptr(), heap.len() as isize, &greater);
+    }
+    IncrementalSort { heap, lt }
+}
+
+/// Sorts the items of `iter` lazily, producing them in ascending order as
+/// they are asked for.
+///
+/// This is equivalent to `incremental_sort_by(iter, |a, b| a.lt(b))`, see
+/// `incremental_sort_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [7, 3, 9, 1, 5, 8, 2];
+/// // the first page of results, without sorting the rest
+/// let page = sortrs::incremental_sort(&v).take(3).cloned().collect::<Vec<_>>();
+/// assert!(page == [1, 2, 3]);
+/// ```
+pub fn incremental_sort<I>(iter: I) -> IncrementalSort<I::Item, LtFn<I::Item>>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    incremental_sort_by(iter, PartialOrd::lt)
+}
+
+/// An iterator that merges two sorted iterators, created by `merge` and
+/// `merge_by`.
+pub struct MergeBy<A: Iterator, B: Iterator, F> {
+    a: Peekable<Fuse<A>>,
+    b: Peekable<Fuse<B>>,
+    lt: F,
+}
+
+impl<A, B, F> Iterator for MergeBy<A, B, F>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+    F: Fn(&A::Item, &A::Item) -> bool,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        let take_b = match (self.a.peek(), self.b.peek()) {
+            // only take from b when it is strictly less, so ties come from a
+            (Some(x), Some(y)) => (self.lt)(y, x),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        if take_b {
+            self.b.next()
+        } else {
+            self.a.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.a.size_hint();
+        let (b_lower, b_upper) = self.b.size_hint();
+        let upper = match (a_upper, b_upper) {
+            (Some(a_upper), Some(b_upper)) => a_upper.checked_add(b_upper),
+            _ => None,
+        };
+        (a_lower.saturating_add(b_lower), upper)
+    }
+}
+
+impl<A, B, F> FusedIterator for MergeBy<A, B, F>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+    F: Fn(&A::Item, &A::Item) -> bool,
+{
+}
+
+/// Lazily merges the sorted iterators `a` and `b` into one sorted iterator,
+/// using `lt` to compare items.
+///
+/// The merge is stable: when items compare equal the one from `a` is
+/// produced first, and items from the same input keep their order. Both
+/// inputs are fused, so once the merge returns `None` it keeps doing so.
+///
+/// # Examples
+///
+/// ```rust
+/// let a = [(1, 'a'), (3, 'a'), (3, 'b')];
+/// let b = [(2, 'x'), (3, 'x'), (4, 'x')];
+/// let merged = sortrs::merge_by(a.iter(), b.iter(), |x, y| x.0 < y.0)
+///     .map(|x| x.1)
+///     .collect::<String>();
+/// assert_eq!(merged, "axabxx");
+/// ```
+pub fn merge_by<A, B, F>(a: A, b: B, lt: F) -> MergeBy<A::IntoIter, B::IntoIter, F>
+where
+    A: IntoIterator,
+    B: IntoIterator<Item = A::Item>,
+    F: Fn(&A::Item, &A::Item) -> bool,
+{
+    MergeBy {
+        a: a.into_iter().fuse().peekable(),
+        b: b.into_iter().fuse().peekable(),
+        lt,
+    }
+}
+
+/// Lazily merges the sorted iterators `a` and `b` into one sorted iterator.
+///
+/// This is equivalent to `merge_by(a, b, |x, y| x.lt(y))`, see `merge_by`
+/// for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = sortrs::merge(vec![1, 4, 5], vec![2, 3, 6]).collect::<Vec<_>>();
+/// assert!(v == [1, 2, 3, 4, 5, 6]);
+/// ```
+pub fn merge<A, B>(a: A, b: B) -> MergeBy<A::IntoIter, B::IntoIter, LtFn<A::Item>>
+where
+    A: IntoIterator,
+    B: IntoIterator<Item = A::Item>,
+    A::Item: PartialOrd,
+{
+    merge_by(a, b, PartialOrd::lt)
+}
+
+/// Which input of a merge an element is taken from, produced by `MergePlan`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The next element of the first input.
+    A,
+    /// The next element of the second input.
+    B,
+}
+
+/// An iterator over the steps of a merge of two sorted sequences, created by
+/// `merge_plan`.
+pub struct MergePlan<F> {
+    a_len: usize,
+    b_len: usize,
+    i: usize,
+    j: usize,
+    b_lt_a: F,
+}
+
+impl<F> Iterator for MergePlan<F>
+where
+    F: FnMut(usize, usize) -> bool,
+{
+    type Item = Side;
+
+    fn next(&mut self) -> Option<Side> {
+        let side = if self.i < self.a_len {
+            // only take from b when it is strictly less, so ties come from a
+            if self.j < self.b_len && (self.b_lt_a)(self.i, self.j) {
+                Side::B
+            } else {
+                Side::A
+            }
+        } else if self.j < self.b_len {
+            Side::B
+        } else {
+            return None;
+        };
+        match side {
+            Side::A => self.i += 1,
+            Side::B => self.j += 1,
+        }
+        Some(side)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.a_len - self.i) + (self.b_len - self.j);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<F> ExactSizeIterator for MergePlan<F> where F: FnMut(usize, usize) -> bool {}
+
+impl<F> FusedIterator for MergePlan<F> where F: FnMut(usize, usize) -> bool {}
+
+/// Lazily computes how to merge two sorted sequences of lengths `a_len` and
+/// `b_len` without touching their elements, yielding the `Side` to take
+/// each element of the merged output from.
+///
+/// `b_lt_a(i, j)` must return whether element `j` of the second sequence is
+/// less than element `i` of the first. It is the only access to the data,
+/// so the sequences can live anywhere the caller can compare them, such as
+/// in a file or another process, and the merge is then carried out by
+/// following the plan. Like `merge_by` the merge is stable, taking from the
+/// first sequence on ties. At most `a_len + b_len - 1` comparisons are made.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Side;
+///
+/// let a = [1, 4, 4];
+/// let b = [2, 4, 5];
+/// let plan = sortrs::merge_plan(a.len(), b.len(), |i, j| b[j] < a[i]).collect::<Vec<_>>();
+/// assert!(plan == [Side::A, Side::B, Side::A, Side::A, Side::B, Side::B]);
+/// ```
+pub fn merge_plan<F>(a_len: usize, b_len: usize, b_lt_a: F) -> MergePlan<F>
+where
+    F: FnMut(usize, usize) -> bool,
+{
+    MergePlan {
+        a_len,
+        b_len,
+        i: 0,
+        j: 0,
+        b_lt_a,
+    }
+}
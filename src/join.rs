@@ -0,0 +1,338 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Merge joins of sorted slices.
+
+use std::ops::Range;
+
+/// A merge join of two slices sorted by key, yielding matched pairs and,
+/// when asked for, unmatched elements of either side.
+struct Join<'a, A: 'a, B: 'a, FA, FB> {
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+    i: usize,
+    j: usize,
+    // the elements of a and b with the same key, and the next pair of them
+    group: Option<(Range<usize>, Range<usize>)>,
+    next_pair: (usize, usize),
+    // whether unmatched elements of a and b are yielded
+    left: bool,
+    right: bool,
+}
+
+impl<'a, A, B, K, FA, FB> Join<'a, A, B, FA, FB>
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K,
+    FB: Fn(&B) -> K,
+{
+    fn new(a: &'a [A], b: &'a [B], key_a: FA, key_b: FB, left: bool, right: bool) -> Self {
+        Join {
+            a,
+            b,
+            key_a,
+            key_b,
+            i: 0,
+            j: 0,
+            group: None,
+            next_pair: (0, 0),
+            left,
+            right,
+        }
+    }
+}
+
+impl<'a, A, B, K, FA, FB> Iterator for Join<'a, A, B, FA, FB>
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K,
+    FB: Fn(&B) -> K,
+{
+    type Item = (Option<&'a A>, Option<&'a B>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((ga, gb)) = self.group.clone() {
+                // every pairing of the group, in order of a then b
+                let (x, y) = self.next_pair;
+                if y + 1 < gb.end {
+                    self.next_pair = (x, y + 1);
+                } else if x + 1 < ga.end {
+                    self.next_pair = (x + 1, gb.start);
+                } else {
+                    self.i = ga.end;
+                    self.j = gb.end;
+                    self.group = None;
+                }
+                return Some((Some(&self.a[x]), Some(&self.b[y])));
+            }
+
+            if self.i < self.a.len() && self.j < self.b.len() {
+                let ka = (self.key_a)(&self.a[self.i]);
+                let kb = (self.key_b)(&self.b[self.j]);
+                if ka.lt(&kb) {
+                    self.i += 1;
+                    if self.left {
+                        return Some((Some(&self.a[self.i - 1]), None));
+                    }
+                } else if kb.lt(&ka) {
+                    self.j += 1;
+                    if self.right {
+                        return Some((None, Some(&self.b[self.j - 1])));
+                    }
+                } else {
+                    // find the end of the run of equal keys on both sides
+                    let a_end = self.i
+                        + 1
+                        + self.a[self.i + 1..]
+                            .iter()
+                            .take_while(|x| !ka.lt(&(self.key_a)(x)))
+                            .count();
+                    let b_end = self.j
+                        + 1
+                        + self.b[self.j + 1..]
+                            .iter()
+                            .take_while(|y| !kb.lt(&(self.key_b)(y)))
+                            .count();
+                    self.next_pair = (self.i, self.j);
+                    self.group = Some((self.i..a_end, self.j..b_end));
+                }
+            } else if self.i < self.a.len() && self.left {
+                self.i += 1;
+                return Some((Some(&self.a[self.i - 1]), None));
+            } else if self.j < self.b.len() && self.right {
+                self.j += 1;
+                return Some((None, Some(&self.b[self.j - 1])));
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
+/// Returns an iterator over every pair of elements of `a` and `b` with equal
+/// keys, an inner join, where `a` is sorted by `key_a` and `b` by `key_b`.
+///
+/// Both slices are walked once from the front. When a key appears several
+/// times on either side every combination is produced, ordered by position
+/// in `a` and then in `b`. The join takes `O(n + m)` key extractions and
+/// comparisons plus the number of pairs produced, for slices of lengths `n`
+/// and `m`. Sort both sides with one of the crate's sorts first, by the
+/// same key.
+///
+/// # Examples
+///
+/// ```rust
+/// let users = [(1, "ann"), (2, "bob"), (3, "cy")];
+/// let orders = [(1, "tea"), (1, "jam"), (3, "pie"), (4, "gin")];
+/// let joined = sortrs::sorted_join(&users, &orders, |u| u.0, |o| o.0)
+///     .map(|(u, o)| (u.1, o.1))
+///     .collect::<Vec<_>>();
+/// assert!(joined == [("ann", "tea"), ("ann", "jam"), ("cy", "pie")]);
+/// ```
+pub fn sorted_join<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> impl Iterator<Item = (&'a A, &'a B)> + 'a
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K + 'a,
+    FB: Fn(&B) -> K + 'a,
+{
+    Join::new(a, b, key_a, key_b, false, false).filter_map(|pair| match pair {
+        (Some(x), Some(y)) => Some((x, y)),
+        _ => None,
+    })
+}
+
+/// Returns an iterator over every pair of elements of `a` and `b` with equal
+/// keys, plus each element of `a` without a match paired with `None`, a left
+/// join, where `a` is sorted by `key_a` and `b` by `key_b`.
+///
+/// Every element of `a` appears at least once, in order. See `sorted_join`
+/// for the order of pairs and the cost.
+///
+/// # Examples
+///
+/// ```rust
+/// let users = [(1, "ann"), (2, "bob")];
+/// let orders = [(1, "tea")];
+/// let joined = sortrs::sorted_left_join(&users, &orders, |u| u.0, |o| o.0)
+///     .map(|(u, o)| (u.1, o.map(|o| o.1)))
+///     .collect::<Vec<_>>();
+/// assert!(joined == [("ann", Some("tea")), ("bob", None)]);
+/// ```
+pub fn sorted_left_join<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> impl Iterator<Item = (&'a A, Option<&'a B>)> + 'a
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K + 'a,
+    FB: Fn(&B) -> K + 'a,
+{
+    Join::new(a, b, key_a, key_b, true, false).filter_map(|pair| match pair {
+        (Some(x), y) => Some((x, y)),
+        _ => None,
+    })
+}
+
+/// Returns an iterator over every pair of elements of `a` and `b` with equal
+/// keys, plus each element of either side without a match paired with
+/// `None`, a full outer join, where `a` is sorted by `key_a` and `b` by
+/// `key_b`.
+///
+/// Elements come out in key order, so the unmatched elements of both sides
+/// are interleaved with the matches. At least one side of every pair is
+/// `Some`. See `sorted_join` for the order of pairs and the cost.
+///
+/// # Examples
+///
+/// ```rust
+/// let old = [(1, 'a'), (2, 'b')];
+/// let new = [(2, 'B'), (3, 'C')];
+/// let diff = sortrs::sorted_outer_join(&old, &new, |x| x.0, |x| x.0)
+///     .map(|(o, n)| (o.map(|o| o.1), n.map(|n| n.1)))
+///     .collect::<Vec<_>>();
+/// assert!(diff == [(Some('a'), None), (Some('b'), Some('B')), (None, Some('C'))]);
+/// ```
+pub fn sorted_outer_join<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> impl Iterator<Item = (Option<&'a A>, Option<&'a B>)> + 'a
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K + 'a,
+    FB: Fn(&B) -> K + 'a,
+{
+    Join::new(a, b, key_a, key_b, true, true)
+}
+
+/// A walk over `a` keeping the elements that do, or don't, have a match in
+/// `b`, with both slices sorted by key.
+struct Filter<'a, A: 'a, B: 'a, FA, FB> {
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+    i: usize,
+    j: usize,
+    matched: bool,
+}
+
+impl<'a, A, B, K, FA, FB> Iterator for Filter<'a, A, B, FA, FB>
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K,
+    FB: Fn(&B) -> K,
+{
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.i < self.a.len() {
+            let x = &self.a[self.i];
+            self.i += 1;
+            let ka = (self.key_a)(x);
+            // b only moves forward, since the keys of a never go down
+            while self.j < self.b.len() && (self.key_b)(&self.b[self.j]).lt(&ka) {
+                self.j += 1;
+            }
+            let found = self.j < self.b.len() && !ka.lt(&(self.key_b)(&self.b[self.j]));
+            if found == self.matched {
+                return Some(x);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the elements of `a` with at least one element of
+/// equal key in `b`, a semi-join, where `a` is sorted by `key_a` and `b` by
+/// `key_b`.
+///
+/// Each element of `a` is yielded at most once, in order, however many
+/// matches it has. The walk takes `O(n + m)` key extractions and comparisons
+/// for slices of lengths `n` and `m`.
+///
+/// # Examples
+///
+/// ```rust
+/// let users = [(1, "ann"), (2, "bob"), (3, "cy")];
+/// let orders = [(1, "tea"), (1, "jam"), (3, "pie")];
+/// let buyers = sortrs::sorted_semi_join(&users, &orders, |u| u.0, |o| o.0)
+///     .map(|u| u.1)
+///     .collect::<Vec<_>>();
+/// assert!(buyers == ["ann", "cy"]);
+/// ```
+pub fn sorted_semi_join<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> impl Iterator<Item = &'a A> + 'a
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K + 'a,
+    FB: Fn(&B) -> K + 'a,
+{
+    Filter {
+        a,
+        b,
+        key_a,
+        key_b,
+        i: 0,
+        j: 0,
+        matched: true,
+    }
+}
+
+/// Returns an iterator over the elements of `a` with no element of equal key
+/// in `b`, an anti-join, where `a` is sorted by `key_a` and `b` by `key_b`.
+///
+/// This filters a sorted slice against a sorted blocklist. Elements are
+/// yielded in order. See `sorted_semi_join` for the cost.
+///
+/// # Examples
+///
+/// ```rust
+/// let emails = ["ann@a.org", "bob@b.org", "cy@c.org"];
+/// let blocked = ["bob@b.org", "dee@d.org"];
+/// let allowed = sortrs::sorted_anti_join(&emails, &blocked, |e| *e, |e| *e)
+///     .cloned()
+///     .collect::<Vec<_>>();
+/// assert!(allowed == ["ann@a.org", "cy@c.org"]);
+/// ```
+pub fn sorted_anti_join<'a, A, B, K, FA, FB>(
+    a: &'a [A],
+    b: &'a [B],
+    key_a: FA,
+    key_b: FB,
+) -> impl Iterator<Item = &'a A> + 'a
+where
+    K: PartialOrd,
+    FA: Fn(&A) -> K + 'a,
+    FB: Fn(&B) -> K + 'a,
+{
+    Filter {
+        a,
+        b,
+        key_a,
+        key_b,
+        i: 0,
+        j: 0,
+        matched: false,
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Order preserving conversions to unsigned integer keys.
+//!
+//! Each `*_key` function maps its type to an unsigned integer such that
+//! `a < b` exactly when `key(a) < key(b)`, except the float keys, which
+//! follow the IEEE 754 total order instead of `<`: `-0.0` is less than `0.0`
+//! and NaNs are ordered beyond the infinities. The integer and float
+//! conversions are bijections with a matching `*_from_key` inverse.
+//!
+//! Keys of 64 bits or fewer can be used with `radix_sort_by_key` and
+//! `radix_sort_multi_key` directly. The 128 bit keys of `i128_key`,
+//! `duration_key`, `system_time_key` and `ipv6_key` can be compared with
+//! `sort_by_key_slice`, or split with `key_halves` into two `u64` keys for
+//! `radix_sort_multi_key`. `duration_key64` and `system_time_key64` are
+//! 64 bit keys for times in a narrower range.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sortrs::keys;
+//!
+//! let mut v = [2.5f64, -1.0, 0.0, -0.0, 1e9];
+//! sortrs::radix_sort_by_key(&mut v, |&x| keys::f64_key(x));
+//! assert!(v == [-1.0, -0.0, 0.0, 2.5, 1e9]);
+//! ```
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+macro_rules! signed_keys {
+    ($($key:ident, $from_key:ident, $i:ty, $u:ty;)*) => {$(
+        /// Maps a signed integer to an unsigned one of the same width,
+        /// preserving order, by flipping the sign bit.
+        #[inline]
+        pub fn $key(x: $i) -> $u {
+            (x as $u) ^ (1 << (<$u>::BITS - 1))
+        }
+
+        #[doc = concat!("The inverse of `", stringify!($key), "`.")]
+        #[inline]
+        pub fn $from_key(key: $u) -> $i {
+            (key ^ (1 << (<$u>::BITS - 1))) as $i
+        }
+    )*};
+}
+
+signed_keys! {
+    i8_key, i8_from_key, i8, u8;
+    i16_key, i16_from_key, i16, u16;
+    i32_key, i32_from_key, i32, u32;
+    i64_key, i64_from_key, i64, u64;
+    i128_key, i128_from_key, i128, u128;
+    isize_key, isize_from_key, isize, usize;
+}
+
+macro_rules! float_keys {
+    ($($key:ident, $from_key:ident, $f:ty, $u:ty;)*) => {$(
+        /// Maps a float to an unsigned integer of the same width following
+        /// the IEEE 754 total order, so `-0.0` sorts before `0.0`, and
+        /// negative NaNs sort before `-inf` and positive NaNs after `inf`.
+        ///
+        /// Negative values have every bit flipped, reversing their order, and
+        /// positive values just the sign bit.
+        #[inline]
+        pub fn $key(x: $f) -> $u {
+            let bits = x.to_bits();
+            let sign = 1 << (<$u>::BITS - 1);
+            if bits & sign != 0 {
+                !bits
+            } else {
+                bits | sign
+            }
+        }
+
+        #[doc = concat!("The inverse of `", stringify!($key), "`.")]
+        #[inline]
+        pub fn $from_key(key: $u) -> $f {
+            let sign = 1 << (<$u>::BITS - 1);
+            <$f>::from_bits(if key & sign != 0 { key & !sign } else { !key })
+        }
+    )*};
+}
+
+float_keys! {
+    f32_key, f32_from_key, f32, u32;
+    f64_key, f64_from_key, f64, u64;
+}
+
+/// Maps a `Duration` to its length in nanoseconds.
+///
+/// The inverse is `Duration::from_nanos` for durations that fit in a `u64`
+/// of nanoseconds, about 584 years.
+#[inline]
+pub fn duration_key(d: Duration) -> u128 {
+    d.as_nanos()
+}
+
+/// Maps a `Duration` to its length in nanoseconds as a `u64`, saturating at
+/// `u64::MAX`.
+///
+/// Durations of about 584 years or more all map to `u64::MAX`, so they
+/// compare equal to each other. Shorter ones keep their order.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use sortrs::keys;
+///
+/// let mut v = [Duration::from_millis(20), Duration::from_nanos(5), Duration::from_secs(1)];
+/// sortrs::radix_sort_by_key(&mut v, |&d| keys::duration_key64(d));
+/// assert!(v == [Duration::from_nanos(5), Duration::from_millis(20), Duration::from_secs(1)]);
+/// ```
+#[inline]
+pub fn duration_key64(d: Duration) -> u64 {
+    d.as_nanos().min(u64::MAX as u128) as u64
+}
+
+/// Maps a `SystemTime` to an unsigned nanosecond count, preserving order for
+/// times both before and after the Unix epoch.
+///
+/// The key is the signed number of nanoseconds since the epoch with its sign
+/// bit flipped, as `i128_key` does.
+#[inline]
+pub fn system_time_key(t: SystemTime) -> u128 {
+    let nanos = match t.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    };
+    i128_key(nanos)
+}
+
+/// Maps a `SystemTime` to a `u64` nanosecond count, preserving order for
+/// times both before and after the Unix epoch, saturating outside the range
+/// of an `i64` of nanoseconds since the epoch.
+///
+/// That range is about 292 years either side of 1970, from 1677 to 2262.
+/// Times outside it compare equal to the nearest end. The key is the signed
+/// number of nanoseconds since the epoch with its sign bit flipped, as
+/// `i64_key` does.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use sortrs::keys;
+///
+/// let hour = Duration::from_secs(3600);
+/// let mut v = [UNIX_EPOCH + hour, UNIX_EPOCH - hour, UNIX_EPOCH];
+/// sortrs::radix_sort_by_key(&mut v, |&t| keys::system_time_key64(t));
+/// assert!(v == [UNIX_EPOCH - hour, UNIX_EPOCH, UNIX_EPOCH + hour]);
+/// ```
+#[inline]
+pub fn system_time_key64(t: SystemTime) -> u64 {
+    let nanos = match t.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_nanos().min(i64::MAX as u128) as i64,
+        Err(before) => {
+            let nanos = before.duration().as_nanos();
+            if nanos > i64::MAX as u128 {
+                i64::MIN
+            } else {
+                -(nanos as i64)
+            }
+        }
+    };
+    i64_key(nanos)
+}
+
+/// Maps an IPv4 address to its 32 bit big endian value, which orders
+/// addresses numerically.
+#[inline]
+pub fn ipv4_key(addr: Ipv4Addr) -> u32 {
+    u32::from(addr)
+}
+
+/// Maps an IPv6 address to its 128 bit big endian value, which orders
+/// addresses numerically.
+#[inline]
+pub fn ipv6_key(addr: Ipv6Addr) -> u128 {
+    u128::from(addr)
+}
+
+/// Splits a 128 bit key into its high and low 64 bits.
+///
+/// Ordering by the high half and then by the low half is the same as
+/// ordering by the whole key, so the halves can be passed to
+/// `radix_sort_multi_key` in that order.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::net::Ipv6Addr;
+/// use sortrs::keys;
+///
+/// let mut v = [
+///     Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1),
+///     Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 2),
+///     Ipv6Addr::LOCALHOST,
+/// ];
+/// sortrs::radix_sort_multi_key(&mut v, &[
+///     |a: &Ipv6Addr| keys::key_halves(keys::ipv6_key(*a)).0,
+///     |a: &Ipv6Addr| keys::key_halves(keys::ipv6_key(*a)).1,
+/// ]);
+/// assert!(v[0] == Ipv6Addr::LOCALHOST);
+/// ```
+#[inline]
+pub fn key_halves(key: u128) -> (u64, u64) {
+    ((key >> 64) as u64, key as u64)
+}
@@ -6,8 +6,186 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(feature = "metrics")]
+#[macro_use]
+extern crate metrics;
+#[cfg(feature = "unicode-segmentation")]
+extern crate unicode_segmentation;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
 use std::mem;
 use std::ptr;
+use std::slice;
+
+mod argsort;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod bits;
+mod check;
+mod chunks;
+mod cmp;
+#[cfg(feature = "compat")]
+pub mod compat;
+mod ext;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+mod group;
+mod idset;
+mod iter;
+mod join;
+pub mod keys;
+mod merge;
+#[cfg(feature = "offload")]
+pub mod offload;
+mod partition;
+#[cfg(feature = "pool")]
+pub mod pool;
+mod radix;
+mod reorder;
+mod rotate;
+mod runs;
+mod sched;
+mod search;
+mod select;
+mod shard;
+mod sorted_vec;
+mod spec;
+mod strided;
+mod strings;
+mod timsort;
+mod uninit;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use argsort::{
+    apply_permutation, argsort_nullable, introsort_tiebreak, introsort_tiebreak_by, lexsort,
+    sort_by_key_slice, NullPlacement, NullableColumn, SortColumn, ValidityColumn,
+};
+pub use bits::{sort_bits, Bitmap, Bits};
+pub use check::{is_permutation_of, is_permutation_of_by, CheckedCmp};
+pub use chunks::{sort_chunks, sort_chunks_by};
+pub use cmp::{
+    heapsort_by_cmp, heapsort_with, insertsort_by_cmp, insertsort_with, introsort_by_cmp,
+    introsort_with, sort_by_cmp, sort_unstable_by_cmp, sort_unstable_with, sort_with,
+    timsort_by_cmp, timsort_with, Compare,
+};
+pub use ext::SliceSortExt;
+pub use group::{
+    aggregate_sorted_by_key, par_group_by_key, par_group_by_key_with, rle_decode, rle_encode,
+    top_k_frequent,
+};
+pub use idset::SortedIdSet;
+pub use iter::{
+    incremental_sort, incremental_sort_by, ksorted, ksorted_by, merge, merge_by, merge_plan,
+    IncrementalSort, KSorted, MergeBy, MergePlan, Side,
+};
+pub use join::{
+    sorted_anti_join, sorted_join, sorted_left_join, sorted_outer_join, sorted_semi_join,
+};
+pub use merge::{kmerge_dedup, kmerge_dedup_by, kmerge_dedup_prioritized_by};
+pub use partition::{partition3_stable, partition_at, partition_at_by, Class};
+pub use radix::{radix_sort_by_key, radix_sort_multi_key};
+pub use reorder::ReorderBuffer;
+pub use rotate::{move_range, rotate_range_left, rotate_range_right};
+pub use runs::{
+    approx_sort, approx_sort_by, detect_order, detect_order_by, repair_sorted, repair_sorted_by,
+    reverse_descending_runs, reverse_descending_runs_by, sort_presorted, sort_presorted_by,
+    split_into_sorted_chunks, split_into_sorted_chunks_by, Order,
+};
+pub use sched::{Scheduler, Task, ThreadScheduler};
+pub use search::{
+    contains_sorted_by_key, find_sorted_mut, intersect_sorted, intersect_sorted_by,
+    position_sorted_by_key, range_sorted, select_nth_from_sorted, select_nth_from_sorted_by,
+    split_sorted, split_sorted_by,
+};
+pub use select::{
+    extremes_k, extremes_k_by, interquartile_range, max_by_lt, median, median_by, min_by_lt,
+    minmax_by_lt, quantiles, quantiles_by, select_nth_unstable, select_nth_unstable_by,
+    select_nth_unstable_by_key, select_nth_unstable_equal, select_nth_unstable_equal_by, top_k,
+    top_k_by, trimmed_mean, SlidingMedian,
+};
+pub use shard::{
+    equi_depth_splitters, equi_depth_splitters_by, range_partition, range_partition_by,
+    sample_splitters, sample_splitters_by, shard_for_key, shard_for_key_by, splitters_from_sample,
+    splitters_from_sample_by,
+};
+pub use sorted_vec::{dedup_sorted_by_key, extract_if_sorted, merge_insert, remove_sorted_items};
+pub use spec::SortSpec;
+pub use strided::{sort_strided, sort_strided_by};
+#[cfg(feature = "unicode-segmentation")]
+pub use strings::grapheme_count;
+pub use strings::{byte_len, char_count, sort_strings, sort_strings_ci};
+pub use timsort::{timsort, timsort_by};
+pub use uninit::{
+    heapsort_uninit, heapsort_uninit_by, introsort_uninit, introsort_uninit_by,
+    sort_copy_into_uninit, sort_copy_into_uninit_by, SortedBuffer,
+};
+
+/// Returns the length of `v` as the `isize` used by the pointer based sorts,
+/// or `None` if there is nothing to sort.
+///
+/// A slice of a sized type never spans more than `isize::MAX` bytes, so its
+/// length always fits in an `isize`. Zero-sized values are indistinguishable
+/// from each other, so those slices are always sorted and are skipped; their
+/// length may exceed `isize::MAX` and they would otherwise divide by a zero
+/// element size in `ptr_distance`.
+#[inline]
+fn sortable_len<T>(v: &[T]) -> Option<isize> {
+    if mem::size_of::<T>() == 0 || v.len() < 2 {
+        None
+    } else {
+        Some(v.len() as isize)
+    }
+}
+
+/// Distance in bytes ahead of the current position to prefetch.
+const PREFETCH_BYTES: usize = 256;
+
+/// Hints the CPU to start loading the cache line containing `p`.
+///
+/// Only emits an instruction when the `prefetch` feature is enabled on x86 and
+/// x86_64 targets, elsewhere this is a no-op. `p` may point outside the slice
+/// being sorted as it is never dereferenced.
+#[inline(always)]
+fn prefetch<T>(p: *const T) {
+    #[cfg(all(feature = "prefetch", target_arch = "x86_64"))]
+    unsafe {
+        use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(p as *const i8);
+    }
+    #[cfg(all(feature = "prefetch", target_arch = "x86", target_feature = "sse"))]
+    unsafe {
+        use std::arch::x86::{_mm_prefetch, _MM_HINT_T0};
+        _mm_prefetch::<_MM_HINT_T0>(p as *const i8);
+    }
+    let _ = p;
+}
+
+/// Adds `len` to the `sortrs.elements_sorted` counter of the `metrics`
+/// facade. Only records anything when the `metrics` feature is enabled.
+///
+/// Only the public sorts call this, once each with the number of elements
+/// they put in order, so the sorts of keys, indices and scratch data done by
+/// other functions aren't counted.
+#[inline(always)]
+fn record_sorted(len: usize) {
+    #[cfg(feature = "metrics")]
+    {
+        counter!("sortrs.elements_sorted").increment(len as u64);
+    }
+    let _ = len;
+}
+
+/// Increments the `sortrs.introsort_fallbacks` counter of the `metrics`
+/// facade. Only records anything when the `metrics` feature is enabled.
+#[inline(always)]
+fn record_fallback() {
+    #[cfg(feature = "metrics")]
+    {
+        counter!("sortrs.introsort_fallbacks").increment(1);
+    }
+}
 
 ///
 /// Insertion sort (based off libstd collections slice version)
@@ -54,13 +232,40 @@ pub fn insertsort_by<T: PartialOrd, F>(v: &mut [T], lt: F)
 where
     F: Fn(&T, &T) -> bool,
 {
-    insertsort_impl(v.as_mut_ptr(), v.len() as isize, &lt);
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        insertsort_impl(v.as_mut_ptr(), len, &lt);
+    }
 }
 
 pub fn insertsort<T: PartialOrd>(v: &mut [T]) {
     insertsort_by(v, |a, b| a.lt(b));
 }
 
+/// Sorts the slice, in place, by the key extracted with `key`, using
+/// insertion sort.
+///
+/// `key` is called twice per comparison, so this suits keys that are cheap
+/// to extract, such as a field.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(3, 'a'), (1, 'b'), (2, 'c')];
+/// sortrs::insertsort_by_key(&mut v, |x| x.0);
+/// assert!(v == [(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+pub fn insertsort_by_key<T, K, F>(v: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        insertsort_impl(v.as_mut_ptr(), len, &|a: &T, b: &T| key(a).lt(&key(b)));
+    }
+}
+
 ///
 /// Heap sort
 ///
@@ -91,11 +296,13 @@ where
     F: Fn(&T, &T) -> bool,
 {
     let mut root = start;
-    let mut next_root = root * 2;
-    // while the root has at least one child
-    while next_root < end {
+    // while the root has at least one child, written as `root < end - root`
+    // rather than `root * 2 < end` so it can't overflow on 16-bit targets
+    while root < end - root {
         // left child
-        let left_child = next_root + 1;
+        let left_child = root * 2 + 1;
+        // fetch the grandchildren ahead of the next iteration
+        prefetch(ptr.wrapping_offset(left_child.wrapping_mul(2).wrapping_add(1)));
         // keep track of child to swap with
         let mut swap = root;
         unsafe {
@@ -115,38 +322,74 @@ where
         }
         // repeat to continue shifting down the child
         root = swap;
-        next_root = root * 2;
     }
 }
 
-/// Internal heapsort implementation
-fn heapsort_impl<T, F>(ptr: *mut T, len: isize, lt: &F)
+fn shift_up<T, F>(ptr: *mut T, mut child: isize, lt: &F)
 where
     F: Fn(&T, &T) -> bool,
 {
-    // build the heap in-place so the largest value is at the root
-    heapify(ptr, len, lt);
+    // while the node is greater than its parent swap them
+    while child > 0 {
+        let parent = (child - 1) / 2;
+        unsafe {
+            if !lt(&*ptr.offset(parent), &*ptr.offset(child)) {
+                return;
+            }
+            ptr::swap(ptr.offset(parent), ptr.offset(child));
+        }
+        child = parent;
+    }
+}
+
+/// Pops the `count` largest elements off the heap of `len` elements at `ptr`,
+/// leaving them in ascending order at the end of the heap's storage.
+fn heap_pop<T, F>(ptr: *mut T, len: isize, count: isize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let stop = len - count;
     let mut end = len - 1;
-    while end > 0 {
+    while end > 0 && end >= stop {
         // ptr is the root and largest value, swap it to the end of the sorted elements
         unsafe {
             ptr::swap(ptr.offset(end), ptr);
         }
         // the heap size is reduced by one
-        end = end - 1;
+        end -= 1;
         // the swap invalidated the heap, so restore it
         shift_down(ptr, 0, end, lt);
     }
 }
 
+/// Heap size at or below which heapsort finishes with an insertion sort.
+const HEAPSORT_INSERTION_THRESHOLD: isize = 16;
+
+/// Internal heapsort implementation
+fn heapsort_impl<T, F>(ptr: *mut T, len: isize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    // small ranges, common when introsort falls back, aren't worth a heap
+    if len <= HEAPSORT_INSERTION_THRESHOLD {
+        insertsort_impl(ptr, len, lt);
+        return;
+    }
+    // build the heap in-place so the largest value is at the root
+    heapify(ptr, len, lt);
+    // popping the largest elements leaves them sorted at the end, and the
+    // small heap left over is quicker to insertion sort than to pop
+    heap_pop(ptr, len, len - HEAPSORT_INSERTION_THRESHOLD, lt);
+    insertsort_impl(ptr, HEAPSORT_INSERTION_THRESHOLD, lt);
+}
+
 pub fn heapsort_by<T: PartialOrd, F>(v: &mut [T], lt: F)
 where
     F: Fn(&T, &T) -> bool,
 {
-    let len = v.len() as isize;
-    if len > 0 {
-        let ptr = v.as_mut_ptr();
-        heapsort_impl(ptr, len, &lt);
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        heapsort_impl(v.as_mut_ptr(), len, &lt);
     }
 }
 
@@ -154,6 +397,211 @@ pub fn heapsort<T: PartialOrd>(v: &mut [T]) {
     heapsort_by(v, |a, b| a.lt(b));
 }
 
+/// Sorts the slice, in place, by the key extracted with `key`, using
+/// heapsort.
+///
+/// `key` is called twice per comparison, so this suits keys that are cheap
+/// to extract, such as a field.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(3, 'a'), (1, 'b'), (2, 'c')];
+/// sortrs::heapsort_by_key(&mut v, |x| x.0);
+/// assert!(v == [(1, 'b'), (2, 'c'), (3, 'a')]);
+/// ```
+pub fn heapsort_by_key<T, K, F>(v: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    record_sorted(v.len());
+    if let Some(len) = sortable_len(v) {
+        heapsort_impl(v.as_mut_ptr(), len, &|a: &T, b: &T| key(a).lt(&key(b)));
+    }
+}
+
+/// Moves the `k` largest elements of the slice, in ascending order, to its
+/// end using `lt` to compare elements.
+///
+/// The order of the remaining elements is unspecified. This builds a heap in
+/// `O(n)` and pops `k` elements from it in `O(k log n)`, which is cheaper than
+/// a partial sort when `k` is small. If `k` is at least the length of the
+/// slice the whole slice is sorted.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// sortrs::heap_pop_k_by(&mut v, 2, |a, b| b.lt(a));
+/// assert!(v[3..] == [2, 1]);
+/// ```
+pub fn heap_pop_k_by<T, F>(v: &mut [T], k: usize, lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(k.min(v.len()));
+    if let Some(len) = sortable_len(v) {
+        let ptr = v.as_mut_ptr();
+        heapify(ptr, len, &lt);
+        heap_pop(ptr, len, k.min(len as usize) as isize, &lt);
+    }
+}
+
+/// Moves the `k` largest elements of the slice, in ascending order, to its
+/// end.
+///
+/// This is equivalent to `heap_pop_k_by(v, k, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// sortrs::heap_pop_k(&mut v, 2);
+/// assert!(v[3..] == [4, 5]);
+/// ```
+pub fn heap_pop_k<T: PartialOrd>(v: &mut [T], k: usize) {
+    heap_pop_k_by(v, k, |a, b| a.lt(b));
+}
+
+/// Sorts the `k` smallest elements of the slice into its first `k` places,
+/// using `lt` to compare elements.
+///
+/// The order of the remaining elements is unspecified. The first `k`
+/// elements are made into a heap, each later element smaller than the
+/// largest of them replaces it, and the heap is then popped into order. This
+/// is `O(n log k)`, much cheaper than a full sort when only the first few
+/// elements of a large slice are wanted. If `k` is at least the length of
+/// the slice the whole slice is sorted.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// sortrs::partial_sort_by(&mut v, 2, |a, b| b.lt(a));
+/// assert!(v[..2] == [5, 4]);
+/// ```
+pub fn partial_sort_by<T, F>(v: &mut [T], k: usize, lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(k.min(v.len()));
+    partial_sort_impl(v, k, &lt);
+}
+
+/// Sorts the `k` smallest elements of the slice into its first `k` places.
+pub(crate) fn partial_sort_impl<T, F>(v: &mut [T], k: usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if let Some(len) = sortable_len(v) {
+        let k = k.min(len as usize) as isize;
+        if k == 0 {
+            return;
+        }
+        let ptr = v.as_mut_ptr();
+        heapify(ptr, k, lt);
+        for i in k..len {
+            unsafe {
+                // the root is the largest of the smallest k seen so far
+                if lt(&*ptr.offset(i), &*ptr) {
+                    ptr::swap(ptr.offset(i), ptr);
+                    shift_down(ptr, 0, k - 1, lt);
+                }
+            }
+        }
+        heap_pop(ptr, k, k, lt);
+    }
+}
+
+/// Sorts the `k` smallest elements of the slice into its first `k` places.
+///
+/// This is equivalent to `partial_sort_by(v, k, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// sortrs::partial_sort(&mut v, 3);
+/// assert!(v[..3] == [1, 2, 3]);
+/// ```
+pub fn partial_sort<T: PartialOrd>(v: &mut [T], k: usize) {
+    partial_sort_by(v, k, |a, b| a.lt(b));
+}
+
+/// Fills `dst` with clones of the `dst.len()` smallest elements of `src` in
+/// sorted order, using `lt` to compare elements, and returns how many were
+/// copied.
+///
+/// `src` is not modified, so it may be shared or read-only. If `src` is
+/// shorter than `dst` all of `src` is copied and sorted into the front of
+/// `dst` and the rest of `dst` is left as it was. `dst` is used as the heap
+/// of `partial_sort_by`, so this is `O(n log k)` for `k = dst.len()` and
+/// clones only the elements that are among the smallest seen so far.
+///
+/// # Examples
+///
+/// ```rust
+/// let src = [5, 1, 4, 2, 3];
+/// let mut top = [0; 2];
+/// assert_eq!(sortrs::partial_sort_copy_by(&src, &mut top, |a, b| b.lt(a)), 2);
+/// assert!(top == [5, 4]);
+/// ```
+pub fn partial_sort_copy_by<T, F>(src: &[T], dst: &mut [T], lt: F) -> usize
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let k = src.len().min(dst.len());
+    record_sorted(k);
+    dst[..k].clone_from_slice(&src[..k]);
+    partial_sort_rest(&mut dst[..k], &src[k..], &lt);
+    k
+}
+
+/// Sorts the `dst.len()` smallest elements of `dst` and `rest` into `dst`,
+/// cloning the elements of `rest` that belong there.
+pub(crate) fn partial_sort_rest<T, F>(dst: &mut [T], rest: &[T], lt: &F)
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let k = dst.len() as isize;
+    // even a one element dst has to be compared with the rest
+    if k == 0 || mem::size_of::<T>() == 0 {
+        return;
+    }
+    let ptr = dst.as_mut_ptr();
+    heapify(ptr, k, lt);
+    for x in rest {
+        unsafe {
+            // the root is the largest of the smallest k seen so far
+            if lt(x, &*ptr) {
+                *ptr = x.clone();
+                shift_down(ptr, 0, k - 1, lt);
+            }
+        }
+    }
+    heap_pop(ptr, k, k, lt);
+}
+
+/// Fills `dst` with clones of the `dst.len()` smallest elements of `src` in
+/// sorted order, and returns how many were copied.
+///
+/// This is equivalent to `partial_sort_copy_by(src, dst, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let src = [5, 1, 4, 2, 3];
+/// let mut smallest = [0; 3];
+/// sortrs::partial_sort_copy(&src, &mut smallest);
+/// assert!(smallest == [1, 2, 3]);
+/// ```
+pub fn partial_sort_copy<T: PartialOrd + Clone>(src: &[T], dst: &mut [T]) -> usize {
+    partial_sort_copy_by(src, dst, |a, b| a.lt(b))
+}
+
 ///
 /// Introspection sort
 ///
@@ -168,48 +616,55 @@ fn ptr_distance<T>(last: *const T, first: *const T) -> isize {
     ((last as usize - first as usize) / mem::size_of::<T>()) as isize
 }
 
+/// Sorts the three elements `a`, `b` and `c` in place.
 #[inline]
-fn median_3<T, F>(a: *mut T, b: *mut T, c: *mut T, lt: &F) -> *mut T
+fn sort_3<T, F>(a: *mut T, b: *mut T, c: *mut T, lt: &F)
 where
     F: Fn(&T, &T) -> bool,
 {
     unsafe {
-        if lt(&*a, &*b) {
-            if lt(&*b, &*c) {
-                b
-            } else if lt(&*a, &*c) {
-                c
-            } else {
-                a
+        if lt(&*b, &*a) {
+            ptr::swap(a, b);
+        }
+        if lt(&*c, &*b) {
+            ptr::swap(b, c);
+            if lt(&*b, &*a) {
+                ptr::swap(a, b);
             }
-        } else if lt(&*a, &*c) {
-            a
-        } else if lt(&*b, &*c) {
-            c
-        } else {
-            b
         }
     }
 }
 
+/// Partitions `[first, last)` about `pivot`, returning the first element of
+/// the upper part.
+///
+/// The caller installs sentinels either side of the range so the scans stop
+/// before running off either end with a consistent comparator. The scans are
+/// still bounded by the range so an inconsistent `lt` can't take them out of
+/// bounds, those checks never fail in practice and are well predicted.
 #[inline]
 fn partition<T, F>(mut first: *mut T, mut last: *mut T, pivot: *mut T, lt: &F) -> *mut T
 where
     F: Fn(&T, &T) -> bool,
 {
+    let distance = (PREFETCH_BYTES / mem::size_of::<T>().max(1)) as isize;
+    let begin = first;
+    let end = last;
     unsafe {
         loop {
             // find first element greater than the pivot
-            while lt(&*first, &*pivot) {
+            while first < end && lt(&*first, &*pivot) {
                 first = first.offset(1);
+                prefetch(first.wrapping_offset(distance));
             }
             // find last element smaller than the pivot
             last = last.offset(-1);
-            while lt(&*pivot, &*last) {
+            while last > begin && lt(&*pivot, &*last) {
                 last = last.offset(-1);
+                prefetch(last.wrapping_offset(-distance));
             }
             // if first and last have met then partitioning is complete
-            if !((first as usize) < (last as usize)) {
+            if first >= last {
                 return first;
             }
             // swap the first and last elements to be on the right side of the pivot
@@ -220,67 +675,251 @@ where
     }
 }
 
+/// Partitions the `len >= 4` elements at `ptr` about a median of 3 pivot,
+/// which is left at `ptr[0]`, returning the first element of the upper part.
 #[inline]
 fn partition_pivot<T, F>(ptr: *mut T, len: isize, lt: &F) -> *mut T
 where
     F: Fn(&T, &T) -> bool,
 {
     unsafe {
-        // choose a pivot based on media of 3 elements
-        let pivot = median_3(ptr.offset(1), ptr.offset(len / 2), ptr.offset(len - 1), lt);
-        // swap the pivot with the first element so it's already partitioned
-        ptr::swap(ptr, pivot);
-        // partition elements on either side of the pivot
-        partition(ptr.offset(1), ptr.offset(len), ptr, lt)
+        // sort the pivot candidates so the smallest and largest act as
+        // sentinels at either end of the range to partition
+        sort_3(ptr.offset(1), ptr.offset(len / 2), ptr.offset(len - 1), lt);
+        // swap the median with the first element so it's already partitioned
+        ptr::swap(ptr, ptr.offset(len / 2));
+        // partition elements between the sentinels
+        partition(ptr.offset(2), ptr.offset(len - 1), ptr, lt)
+    }
+}
+
+/// Moves the elements of `v` that satisfy `pred` to its front, returning how
+/// many there are.
+#[inline]
+fn group_front<T, P: Fn(&T) -> bool>(v: &mut [T], pred: P) -> usize {
+    let mut count = 0;
+    for i in 0..v.len() {
+        if pred(&v[i]) {
+            v.swap(count, i);
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Moves the elements of `v` that satisfy `pred` to its back, returning how
+/// many there are.
+#[inline]
+fn group_back<T, P: Fn(&T) -> bool>(v: &mut [T], pred: P) -> usize {
+    let len = v.len();
+    let mut count = 0;
+    for i in (0..len).rev() {
+        if pred(&v[i]) {
+            v.swap(len - 1 - count, i);
+            count += 1;
+        }
     }
+    count
 }
 
-fn introsort_loop<T, F>(ptr: *mut T, mut last: *mut T, mut depth_limit: usize, lt: &F)
+/// Partitions the non-empty `v` about `v[pivot]`, returning `(mid, equal)`
+/// where `v[..mid]` is less than the pivot, `v[mid..mid + equal]` is
+/// equivalent to it and `v[mid + equal..]` is greater.
+fn partition_at_impl<T, F>(v: &mut [T], pivot: usize, lt: &F) -> (usize, usize)
 where
     F: Fn(&T, &T) -> bool,
 {
-    // Threshold at which we stop and let the insertsort finish off
-    const THRESHOLD: isize = 32;
+    let len = v.len();
+    if mem::size_of::<T>() == 0 {
+        // zero-sized values are all equal
+        return (0, len);
+    }
+    v.swap(0, pivot);
+    let ptr = v.as_mut_ptr();
+    let split = unsafe { ptr_distance(partition(ptr.add(1), ptr.add(len), ptr, lt), ptr) };
+    // move the pivot from the front to its final position
+    let mid = split as usize - 1;
+    v.swap(0, mid);
+    // equal elements may be on either side, group them next to the pivot
+    let (lower, rest) = v.split_at_mut(mid);
+    let (pivot, upper) = rest.split_first_mut().unwrap();
+    let lower_equal = group_back(lower, |x| !lt(x, pivot));
+    let upper_equal = group_front(upper, |x| !lt(pivot, x));
+    (mid - lower_equal, lower_equal + 1 + upper_equal)
+}
+
+/// The algorithm `introsort_with_config_by` switches to for a partition once
+/// the depth limit is reached.
+pub enum Fallback<T> {
+    /// Heapsort the partition. This keeps introsort `O(n log n)` worst-case
+    /// and is the default.
+    Heapsort,
+    /// Insertion sort the partition, which is `O(n^2)` worst-case.
+    Insertsort,
+    /// Call the function with the partition and the less than function. It
+    /// must leave the partition sorted.
+    Custom(FallbackFn<T>),
+}
+
+/// A user supplied `Fallback` sort, called with the partition to sort and the
+/// less than function.
+pub type FallbackFn<T> = fn(&mut [T], &dyn Fn(&T, &T) -> bool);
+
+impl<T> Clone for Fallback<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Fallback<T> {}
+
+impl<T> Fallback<T> {
+    fn sort<F>(&self, ptr: *mut T, len: isize, lt: &F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        match *self {
+            Fallback::Heapsort => heapsort_impl(ptr, len, lt),
+            Fallback::Insertsort => insertsort_impl(ptr, len, lt),
+            Fallback::Custom(f) => f(unsafe { slice::from_raw_parts_mut(ptr, len as usize) }, lt),
+        }
+    }
+}
+
+/// When `introsort_with_config_by` insertion sorts the partitions that are
+/// too small for quicksort.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Insertion {
+    /// Leave them and make one insertion sort pass over the whole slice once
+    /// partitioning is done. Each element only moves within its partition,
+    /// so the pass is linear, and it needs no per-partition bookkeeping. This
+    /// is the default.
+    Final,
+    /// Insertion sort each partition as soon as it's small enough, while
+    /// it's still in cache. This avoids streaming the whole slice through
+    /// the cache a second time, which dominates on very large slices.
+    PerLeaf,
+}
+
+/// Options for `introsort_with_config_by`.
+///
+/// The default configuration is what `introsort_by` uses.
+///
+/// # Examples
+///
+/// ```rust
+/// let config = sortrs::IntrosortConfig {
+///     fallback: sortrs::Fallback::Insertsort,
+///     ..Default::default()
+/// };
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub struct IntrosortConfig<T> {
+    /// The algorithm used once the depth limit is reached.
+    pub fallback: Fallback<T>,
+    /// The depth limit is `depth_multiplier * lg(len)` partitioning steps.
+    /// Lower values hand adversarial inputs to the fallback sooner, higher
+    /// values give quicksort longer to recover from bad pivots. Defaults to
+    /// 2, and 0 skips partitioning altogether.
+    pub depth_multiplier: usize,
+    /// Partitions of at most this many elements are left for insertion sort.
+    /// Defaults to 32, values below 3 are treated as 3.
+    pub insertion_threshold: usize,
+    /// When those partitions are insertion sorted. Defaults to
+    /// `Insertion::Final`.
+    pub insertion: Insertion,
+}
+
+impl<T> Clone for IntrosortConfig<T> {
+    fn clone(&self) -> Self {
+        IntrosortConfig {
+            fallback: self.fallback,
+            depth_multiplier: self.depth_multiplier,
+            insertion_threshold: self.insertion_threshold,
+            insertion: self.insertion,
+        }
+    }
+}
+
+impl<T> Default for IntrosortConfig<T> {
+    fn default() -> Self {
+        IntrosortConfig {
+            fallback: Fallback::Heapsort,
+            depth_multiplier: 2,
+            insertion_threshold: 32,
+            insertion: Insertion::Final,
+        }
+    }
+}
+
+fn introsort_loop<T, F>(
+    ptr: *mut T,
+    mut last: *mut T,
+    mut depth_limit: usize,
+    config: &IntrosortConfig<T>,
+    lt: &F,
+) where
+    F: Fn(&T, &T) -> bool,
+{
+    // threshold at which we stop and let the insertsort finish off, the
+    // partition needs at least 4 elements
+    let threshold = config.insertion_threshold.max(3) as isize;
 
     let mut len = ptr_distance(last, ptr);
-    while len > THRESHOLD {
-        // if the depth limit has been reached switch to heapsort
+    while len > threshold {
+        // if the depth limit has been reached switch to the fallback sort
         if depth_limit == 0 {
-            heapsort_impl(ptr, len, lt);
+            record_fallback();
+            config.fallback.sort(ptr, len, lt);
             return;
         }
         depth_limit -= 1;
         // choose partition and pivot
         let pivot = partition_pivot(ptr, len, lt);
         // introsort the elements after the pivot
-        introsort_loop(pivot, last, depth_limit, lt);
+        introsort_loop(pivot, last, depth_limit, config, lt);
         len = ptr_distance(pivot, ptr);
         last = pivot;
     }
+    if config.insertion == Insertion::PerLeaf {
+        insertsort_impl(ptr, len, lt);
+    }
 }
 
 #[inline]
-fn introsort_impl<T: PartialOrd, F>(v: &mut [T], lt: F)
+fn introsort_config_impl<T, F>(v: &mut [T], config: &IntrosortConfig<T>, lt: F)
 where
     F: Fn(&T, &T) -> bool,
 {
-    let len = v.len() as isize;
-    if len > 0 {
+    if let Some(len) = sortable_len(v) {
         let ptr = v.as_mut_ptr();
         unsafe {
-            introsort_loop(ptr, ptr.offset(len), 2 * lg(len as usize), &lt);
+            let depth_limit = config.depth_multiplier.saturating_mul(lg(len as usize));
+            introsort_loop(ptr, ptr.offset(len), depth_limit, config, &lt);
+        }
+        if config.insertion == Insertion::Final {
+            // insertsort mostly sorted data
+            insertsort_impl(ptr, len, &lt);
         }
-        // insertsort mostly sorted data
-        insertsort_impl(ptr, len, &lt);
     }
 }
 
+#[inline]
+fn introsort_impl<T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    introsort_config_impl(v, &IntrosortConfig::default(), lt);
+}
+
 ///
 /// Sorts the slice, in place, using `lt` to compare elements.
 ///
 /// The order of equal elements is not guaranteed to be preserved.
 ///
-/// This sort is `O(n log n)` worst-case and stable.
+/// This sort is `O(n log n)` worst-case, unstable and does not allocate.
 ///
 /// The sort is implemented using the Introsort algorithm. Introsort or
 /// introspective sort is a hybrid sorting algorithm that provides both fast
@@ -306,6 +945,7 @@ pub fn introsort_by<T: PartialOrd, F>(v: &mut [T], lt: F)
 where
     F: Fn(&T, &T) -> bool,
 {
+    record_sorted(v.len());
     introsort_impl(v, lt);
 }
 
@@ -322,5 +962,217 @@ where
 /// assert!(v == [-5, -3, 1, 2, 4]);
 /// ```
 pub fn introsort<T: PartialOrd>(v: &mut [T]) {
-    introsort_impl(v, |a, b| a.lt(b))
+    introsort_by(v, |a, b| a.lt(b))
+}
+
+/// Sorts the slice, in place, by the key extracted with `key`.
+///
+/// This is equivalent to `introsort_by(v, |a, b| key(a).lt(&key(b)))`.
+/// `key` is called twice per comparison, so this suits keys that are cheap
+/// to extract, such as a field.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [-5i32, 4, 1, -3, 2];
+///
+/// sortrs::introsort_by_key(&mut v, |x| x.abs());
+/// assert!(v == [1, 2, -3, 4, -5]);
+/// ```
+pub fn introsort_by_key<T, K, F>(v: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    record_sorted(v.len());
+    introsort_impl(v, |a, b| key(a).lt(&key(b)))
+}
+
+/// Sorts the slice, in place, by the key extracted with `key`, calling `key`
+/// only once per element.
+///
+/// The keys are collected with their indices into a scratch buffer, the
+/// `(key, index)` pairs are sorted with introsort and the resulting order is
+/// applied to the slice with `apply_permutation`. This allocates `O(n)` space
+/// but saves calling `key` `O(n log n)` times, so it's worth it for keys that
+/// are expensive to compute, such as lowercased or parsed strings. For keys
+/// that are cheap to extract `introsort_by_key` is faster.
+///
+/// Equal keys are ordered by their index, so unlike `introsort_by_key` the
+/// sort is stable.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut files = ["b.TXT", "A.txt", "c.txt", "a.txt"];
+/// sortrs::introsort_by_cached_key(&mut files, |f| f.to_lowercase());
+/// assert!(files == ["A.txt", "a.txt", "b.TXT", "c.txt"]);
+/// ```
+pub fn introsort_by_cached_key<T, K, F>(v: &mut [T], key: F)
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    record_sorted(v.len());
+    if sortable_len(v).is_none() {
+        return;
+    }
+    let mut pairs = v
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (key(x), i))
+        .collect::<Vec<(K, usize)>>();
+    introsort_impl(&mut pairs, |a, b| {
+        a.0.lt(&b.0) || (!b.0.lt(&a.0) && a.1 < b.1)
+    });
+    let perm = pairs.into_iter().map(|(_, i)| i).collect::<Vec<usize>>();
+    apply_permutation(v, &perm);
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements and `config` to
+/// control the introsort.
+///
+/// This is `introsort_by` with its algorithm choices exposed, for comparing
+/// hybrid sorting strategies. With the default configuration it behaves
+/// exactly like `introsort_by`. The worst-case complexity depends on the
+/// configured `Fallback`.
+///
+/// # Examples
+///
+/// ```rust
+/// fn my_fallback(v: &mut [i32], lt: &dyn Fn(&i32, &i32) -> bool) {
+///     sortrs::insertsort_by(v, |a, b| lt(a, b));
+/// }
+///
+/// let config = sortrs::IntrosortConfig {
+///     fallback: sortrs::Fallback::Custom(my_fallback),
+///     depth_multiplier: 3,
+///     insertion_threshold: 16,
+///     insertion: sortrs::Insertion::PerLeaf,
+/// };
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::introsort_with_config_by(&mut v, &config, |a, b| b.lt(a));
+/// assert!(v == [5, 4, 3, 2, 1]);
+/// ```
+pub fn introsort_with_config_by<T, F>(v: &mut [T], config: &IntrosortConfig<T>, lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    introsort_config_impl(v, config, lt);
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements, preserving the
+/// order of equal elements.
+///
+/// This sort is stable and `O(n log n)` worst-case. It allocates a scratch
+/// buffer of up to half the length of the slice. It is currently
+/// `timsort_by`, which is also `O(n)` on data made of a few sorted runs. Use
+/// `sort_unstable_by` if the order of equal elements doesn't matter and
+/// allocation should be avoided.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// sortrs::sort_by(&mut v, |a, b| a.0 < b.0);
+/// assert!(v == [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+/// ```
+pub fn sort_by<T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    timsort_by(v, lt);
+}
+
+/// Sorts the slice, in place, preserving the order of equal elements.
+///
+/// This is equivalent to `sort_by(v, |a, b| a.lt(b))`, see `sort_by` for its
+/// guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [-5, 4, 1, -3, 2];
+///
+/// sortrs::sort(&mut v);
+/// assert!(v == [-5, -3, 1, 2, 4]);
+/// ```
+pub fn sort<T: PartialOrd>(v: &mut [T]) {
+    sort_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements.
+///
+/// The order of equal elements is not guaranteed to be preserved.
+///
+/// This sort is `O(n log n)` worst-case and never allocates. It is currently
+/// `introsort_by`, which is the fastest general purpose sort in this crate.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 4, 1, 3, 2];
+/// sortrs::sort_unstable_by(&mut v, |a, b| b.lt(a));
+/// assert!(v == [5, 4, 3, 2, 1]);
+/// ```
+pub fn sort_unstable_by<T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    introsort_impl(v, lt);
+}
+
+/// Sorts the slice, in place.
+///
+/// This is equivalent to `sort_unstable_by(v, |a, b| a.lt(b))`, see
+/// `sort_unstable_by` for its guarantees.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [-5, 4, 1, -3, 2];
+///
+/// sortrs::sort_unstable(&mut v);
+/// assert!(v == [-5, -3, 1, 2, 4]);
+/// ```
+pub fn sort_unstable<T: PartialOrd>(v: &mut [T]) {
+    sort_unstable_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts the slice of totally ordered elements, in place, preserving the
+/// order of equal elements.
+///
+/// This is `sort` restricted to types implementing `Ord`, for which every
+/// pair of elements compares consistently. The bound keeps floats out, since
+/// NaN makes their order partial; sort those with `sort_by_cmp(v,
+/// f64::total_cmp)` or `radix_sort_by_key(v, |&x| keys::f64_key(x))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = ["pear", "fig", "apple"];
+///
+/// sortrs::sort_ord(&mut v);
+/// assert!(v == ["apple", "fig", "pear"]);
+/// ```
+pub fn sort_ord<T: Ord>(v: &mut [T]) {
+    timsort_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts the slice of totally ordered elements, in place.
+///
+/// This is `sort_unstable` for types implementing `Ord`, see `sort_ord`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [-5, 4, 1, -3, 2];
+///
+/// sortrs::sort_unstable_ord(&mut v);
+/// assert!(v == [-5, -3, 1, 2, 4]);
+/// ```
+pub fn sort_unstable_ord<T: Ord>(v: &mut [T]) {
+    sort_unstable_by(v, |a, b| a.lt(b));
 }
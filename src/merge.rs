@@ -0,0 +1,139 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Merging of many sorted sequences.
+
+use super::{heapify, shift_down};
+
+/// Merges the sorted `inputs`, keeping the first of each run of equivalent
+/// elements, where `wins(a, b)` orders equivalent heads of inputs `a` and `b`.
+fn kmerge_dedup_impl<T, F, P>(inputs: &[&[T]], lt: &F, wins: &P) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+    P: Fn(usize, usize) -> bool,
+{
+    // a cursor is the index of an input and the position of its head, the
+    // heap keeps the cursor that should be merged next at the root
+    let merged_after = |a: &(usize, usize), b: &(usize, usize)| {
+        let x = &inputs[a.0][a.1];
+        let y = &inputs[b.0][b.1];
+        lt(y, x) || (!lt(x, y) && wins(b.0, a.0))
+    };
+    let mut heap = inputs
+        .iter()
+        .enumerate()
+        .filter(|&(_, input)| !input.is_empty())
+        .map(|(i, _)| (i, 0))
+        .collect::<Vec<(usize, usize)>>();
+    let total = inputs.iter().map(|input| input.len()).sum();
+    let mut merged: Vec<T> = Vec::with_capacity(total);
+    heapify(heap.as_mut_ptr(), heap.len() as isize, &merged_after);
+    while !heap.is_empty() {
+        let (i, pos) = heap[0];
+        let x = &inputs[i][pos];
+        // inputs are sorted so anything not greater than the last element
+        // is equivalent to it
+        let duplicate = match merged.last() {
+            Some(last) => !lt(last, x),
+            None => false,
+        };
+        if !duplicate {
+            merged.push(x.clone());
+        }
+        // advance the cursor, dropping it once its input is exhausted
+        if pos + 1 < inputs[i].len() {
+            heap[0].1 = pos + 1;
+        } else {
+            heap.swap_remove(0);
+        }
+        let len = heap.len() as isize;
+        if len > 1 {
+            shift_down(heap.as_mut_ptr(), 0, len - 1, &merged_after);
+        }
+    }
+    merged
+}
+
+/// Merges the sorted `inputs` into a single sorted `Vec`, keeping only the
+/// first of each run of equivalent elements, using the less than function
+/// `lt` to compare elements.
+///
+/// Each input must be sorted with respect to `lt`. When equivalent elements
+/// appear in several inputs the one from the earliest input is kept, so
+/// inputs can be passed newest first to keep the latest version of each key.
+/// Duplicates within a single input are also dropped. The merge uses a heap
+/// of the inputs' heads and takes `O(n log k)` comparisons for `n` elements
+/// in `k` inputs.
+///
+/// # Examples
+///
+/// ```rust
+/// let newer = [(1, 'b'), (4, 'b')];
+/// let older = [(1, 'a'), (2, 'a'), (4, 'a')];
+/// let v = sortrs::kmerge_dedup_by(&[&newer[..], &older[..]], |a, b| a.0 < b.0);
+/// assert!(v == [(1, 'b'), (2, 'a'), (4, 'b')]);
+/// ```
+pub fn kmerge_dedup_by<T, F>(inputs: &[&[T]], lt: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    kmerge_dedup_impl(inputs, &lt, &|a, b| a < b)
+}
+
+/// Merges the sorted `inputs` into a single sorted `Vec`, keeping only one of
+/// each run of equivalent elements, using `lt` to compare elements and
+/// `wins` to choose which input's element is kept.
+///
+/// `wins(a, b)` returns true if input `a` takes priority over input `b`,
+/// given their indices in `inputs`. It must be a strict total order on the
+/// input indices, like `a < b` or `a > b`. When equivalent elements appear
+/// in several inputs the one from the input that wins over all the others
+/// is kept, so a compaction can keep the newest version of each key whatever
+/// order its files are listed in. Duplicates within a single input keep
+/// their first element. See `kmerge_dedup_by` for the cost.
+///
+/// # Examples
+///
+/// ```rust
+/// // listed oldest first, with the generation of each input
+/// let generations = [3, 9, 5];
+/// let a = [(1, 'a'), (2, 'a')];
+/// let b = [(1, 'b'), (3, 'b')];
+/// let c = [(2, 'c'), (3, 'c')];
+/// let v = sortrs::kmerge_dedup_prioritized_by(
+///     &[&a[..], &b[..], &c[..]],
+///     |x, y| x.0 < y.0,
+///     |i, j| generations[i] > generations[j],
+/// );
+/// assert!(v == [(1, 'b'), (2, 'c'), (3, 'b')]);
+/// ```
+pub fn kmerge_dedup_prioritized_by<T, F, P>(inputs: &[&[T]], lt: F, wins: P) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+    P: Fn(usize, usize) -> bool,
+{
+    kmerge_dedup_impl(inputs, &lt, &wins)
+}
+
+/// Merges the sorted `inputs` into a single sorted `Vec` without duplicates.
+///
+/// When equal elements appear in several inputs the one from the earliest
+/// input is kept. See `kmerge_dedup_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = sortrs::kmerge_dedup(&[&[1, 3, 5][..], &[1, 2, 3, 3][..], &[6][..]]);
+/// assert!(v == [1, 2, 3, 5, 6]);
+/// ```
+pub fn kmerge_dedup<T: PartialOrd + Clone>(inputs: &[&[T]]) -> Vec<T> {
+    kmerge_dedup_by(inputs, |a, b| a.lt(b))
+}
@@ -0,0 +1,81 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Handing key sorts off to another device.
+//!
+//! An `Offload` sorts a buffer of `u64` keys and returns the permutation, so
+//! a GPU radix sort from another crate can do the heavy lifting while
+//! `sort_by_key` extracts the keys and moves the elements on the CPU.
+//! `CpuOffload` is the reference implementation.
+
+use super::radix::lsd_radix_sort;
+use super::{apply_permutation, record_sorted};
+
+/// A backend that sorts keys, usually on another device.
+pub trait Offload {
+    /// Returns the permutation that stably sorts `keys` in ascending order,
+    /// where element `i` of the result is the index in `keys` of the `i`th
+    /// smallest key.
+    ///
+    /// The result must be a permutation of `0..keys.len()`. Keys that are
+    /// equal must keep their relative order, as they do in `CpuOffload`.
+    fn sort_keys(&mut self, keys: &[u64]) -> Vec<usize>;
+}
+
+/// The reference `Offload`, which radix sorts keys on the calling thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuOffload;
+
+impl Offload for CpuOffload {
+    fn sort_keys(&mut self, keys: &[u64]) -> Vec<usize> {
+        let pairs = keys
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, key)| (key, i))
+            .collect::<Vec<(u64, usize)>>();
+        lsd_radix_sort(pairs).into_iter().map(|(_, i)| i).collect()
+    }
+}
+
+/// Sorts the slice, in place, by the `u64` key extracted with `key`, using
+/// `offload` to sort the keys.
+///
+/// `key` is called once per element to fill a key buffer, which is submitted
+/// to `offload`, and the permutation it returns is applied to the slice with
+/// `apply_permutation`. Only the keys and the permutation cross to the
+/// backend, the elements themselves never leave the slice. The sort is
+/// stable if the backend is.
+///
+/// # Panics
+///
+/// Panics if `offload` returns something other than a permutation of the
+/// slice's indices.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::offload::{sort_by_key, CpuOffload};
+///
+/// let mut v = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd')];
+/// sort_by_key(&mut v, |x| x.0 as u64, &mut CpuOffload);
+/// assert!(v == [(1, 'b'), (2, 'd'), (3, 'a'), (3, 'c')]);
+/// ```
+pub fn sort_by_key<T, F, O>(v: &mut [T], key: F, offload: &mut O)
+where
+    F: Fn(&T) -> u64,
+    O: Offload + ?Sized,
+{
+    record_sorted(v.len());
+    if v.len() < 2 {
+        return;
+    }
+    let keys = v.iter().map(key).collect::<Vec<u64>>();
+    let perm = offload.sort_keys(&keys);
+    apply_permutation(v, &perm);
+}
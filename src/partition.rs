@@ -0,0 +1,139 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Partitioning slices without fully sorting them.
+
+use super::partition_at_impl;
+
+/// Partitions the slice about the element at index `pivot`, using `lt` to
+/// compare elements, and returns `(mid, equal)`.
+///
+/// Afterwards `v[..mid]` holds the elements less than the pivot,
+/// `v[mid..mid + equal]` those equivalent to it, including the pivot itself,
+/// and `v[mid + equal..]` those greater. Every element of the middle run is
+/// in its sorted position, so `equal` is at least 1 and a quickselect can
+/// stop as soon as the index it wants falls within the run. The order within
+/// each part is unspecified.
+///
+/// This is the Hoare partition used by `introsort_by` followed by a pass over
+/// each side to gather the equivalent elements. It is `O(n)` and does not
+/// allocate.
+///
+/// # Panics
+///
+/// Panics if `pivot` is out of bounds.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(3, 'a'), (9, 'b'), (3, 'c'), (1, 'd'), (7, 'e')];
+/// let (mid, equal) = sortrs::partition_at_by(&mut v, 0, |a, b| a.0 < b.0);
+/// assert_eq!((mid, equal), (1, 2));
+/// assert!(v[0] == (1, 'd'));
+/// assert!(v[1..3].iter().all(|x| x.0 == 3));
+/// assert!(v[3..].iter().all(|x| x.0 > 3));
+/// ```
+pub fn partition_at_by<T, F>(v: &mut [T], pivot: usize, lt: F) -> (usize, usize)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(pivot < v.len(), "pivot index out of bounds");
+    partition_at_impl(v, pivot, &lt)
+}
+
+/// Partitions the slice about the element at index `pivot`, returning
+/// `(mid, equal)` such that `v[mid..mid + equal]` are the elements equal to
+/// the pivot, with smaller elements before them and larger ones after.
+///
+/// See `partition_at_by` for details.
+///
+/// # Panics
+///
+/// Panics if `pivot` is out of bounds.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [4, 8, 1, 4, 6, 4, 2];
+/// let (mid, equal) = sortrs::partition_at(&mut v, 3);
+/// assert!(v[mid..mid + equal] == [4, 4, 4]);
+/// assert!(v[..mid].iter().all(|&x| x < 4));
+/// assert!(v[mid + equal..].iter().all(|&x| x > 4));
+/// ```
+pub fn partition_at<T: PartialOrd>(v: &mut [T], pivot: usize) -> (usize, usize) {
+    partition_at_by(v, pivot, |a, b| a.lt(b))
+}
+
+/// The bucket an element is placed in by `partition3_stable`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Class {
+    /// Placed at the start of the slice.
+    Low,
+    /// Placed between the `Low` and `High` elements.
+    Mid,
+    /// Placed at the end of the slice.
+    High,
+}
+
+/// Partitions the slice into the `Low`, `Mid` and `High` elements as decided
+/// by `classify`, preserving the order of elements within each class.
+///
+/// Returns `(mid, high)`, the indices of the first `Mid` and first `High`
+/// element, so the classes are `v[..mid]`, `v[mid..high]` and `v[high..]`.
+///
+/// `classify` is called exactly once per element. This is `O(n)` time and
+/// allocates a class and a destination index per element.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Class;
+///
+/// let mut v = [5, 1, 9, 3, 7, 2];
+/// let split = sortrs::partition3_stable(&mut v, |&x| {
+///     if x < 3 {
+///         Class::Low
+///     } else if x < 6 {
+///         Class::Mid
+///     } else {
+///         Class::High
+///     }
+/// });
+/// assert_eq!(split, (2, 4));
+/// assert!(v == [1, 2, 5, 3, 9, 7]);
+/// ```
+pub fn partition3_stable<T, F>(v: &mut [T], classify: F) -> (usize, usize)
+where
+    F: Fn(&T) -> Class,
+{
+    // classify everything before moving anything
+    let classes = v.iter().map(classify).collect::<Vec<Class>>();
+    let mid = classes.iter().filter(|&&c| c == Class::Low).count();
+    let high = mid + classes.iter().filter(|&&c| c == Class::Mid).count();
+
+    // each element's destination, in order within its class
+    let mut next = [0, mid, high];
+    let mut dest = classes
+        .iter()
+        .map(|&c| {
+            let d = next[c as usize];
+            next[c as usize] += 1;
+            d
+        })
+        .collect::<Vec<usize>>();
+
+    // follow each cycle of the permutation, swapping elements into place
+    for i in 0..v.len() {
+        while dest[i] != i {
+            let d = dest[i];
+            v.swap(i, d);
+            dest.swap(i, d);
+        }
+    }
+    (mid, high)
+}
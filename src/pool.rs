@@ -0,0 +1,135 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A thread-local pool of scratch memory for the stable sorts.
+//!
+//! With the `pool` feature the merge buffer of `timsort`, and of `sort` and
+//! `sort_by` which use it, is taken from a block of memory kept by the
+//! calling thread instead of being allocated for each sort. The block grows
+//! to the largest buffer any sort on the thread has needed and is kept until
+//! `clear` is called or the thread exits, so sorting many similar sized
+//! slices allocates once.
+
+use std::alloc::{self, Layout};
+use std::cell::RefCell;
+use std::mem;
+use std::ptr;
+
+thread_local! {
+    static POOL: RefCell<Scratch> = RefCell::new(Scratch::empty());
+}
+
+/// A block of scratch memory owned by a sort while it runs. Dropping it
+/// gives it back to the thread's pool, unless the pool holds a larger block
+/// by then.
+pub(crate) struct Scratch {
+    ptr: *mut u8,
+    layout: Layout,
+}
+
+impl Scratch {
+    fn empty() -> Scratch {
+        Scratch {
+            ptr: ptr::null_mut(),
+            layout: Layout::new::<()>(),
+        }
+    }
+
+    /// Takes the thread's block out of the pool, leaving it empty, so a sort
+    /// started by a comparator while this one runs allocates its own.
+    pub(crate) fn take() -> Scratch {
+        POOL.with(|pool| mem::replace(&mut *pool.borrow_mut(), Scratch::empty()))
+    }
+
+    /// Returns a pointer to room for at least `len` values of `T`, growing
+    /// the block if it is too small or not aligned for `T`. The contents
+    /// are not kept when it grows.
+    pub(crate) fn reserve<T>(&mut self, len: usize) -> *mut T {
+        let size = mem::size_of::<T>()
+            .checked_mul(len)
+            .expect("capacity overflow");
+        let align = mem::align_of::<T>();
+        if size > self.layout.size() || align > self.layout.align() {
+            let layout = Layout::from_size_align(
+                size.max(self.layout.size()),
+                align.max(self.layout.align()),
+            )
+            .expect("capacity overflow");
+            self.release();
+            let ptr = unsafe { alloc::alloc(layout) };
+            if ptr.is_null() {
+                alloc::handle_alloc_error(layout);
+            }
+            self.ptr = ptr;
+            self.layout = layout;
+        }
+        self.ptr as *mut T
+    }
+
+    /// Frees the block, leaving this empty.
+    fn release(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { alloc::dealloc(self.ptr, self.layout) };
+        }
+        // assigning a new Scratch would drop this one, and its block with it
+        self.ptr = ptr::null_mut();
+        self.layout = Layout::new::<()>();
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        if self.ptr.is_null() {
+            return;
+        }
+        let mut block = mem::replace(self, Scratch::empty());
+        // the pool is gone once the thread is being torn down, in which case
+        // the block is simply freed
+        let _ = POOL.try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if block.layout.size() > pool.layout.size() {
+                mem::swap(&mut block, &mut *pool);
+            }
+        });
+        block.release();
+    }
+}
+
+/// Frees the calling thread's pooled scratch memory.
+///
+/// The next stable sort on the thread allocates again. Other threads' pools
+/// are not affected.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = (0..1000).rev().map(|x| x % 7).collect::<Vec<i32>>();
+/// sortrs::timsort(&mut v);
+/// sortrs::pool::clear();
+/// assert_eq!(sortrs::pool::capacity(), 0);
+/// ```
+pub fn clear() {
+    let mut block = Scratch::take();
+    block.release();
+}
+
+/// Returns the size in bytes of the calling thread's pooled scratch memory,
+/// which is the largest merge buffer a stable sort on the thread has needed
+/// since the pool was last cleared.
+///
+/// # Examples
+///
+/// ```rust
+/// sortrs::pool::clear();
+/// let mut v = (0..1000u32).map(|x| x.wrapping_mul(2654435761)).collect::<Vec<u32>>();
+/// sortrs::timsort(&mut v);
+/// assert!(sortrs::pool::capacity() > 0);
+/// ```
+pub fn capacity() -> usize {
+    POOL.with(|pool| pool.borrow().layout.size())
+}
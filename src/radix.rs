@@ -0,0 +1,138 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Radix sorts on integer keys.
+
+use std::mem;
+
+use super::{apply_permutation, record_sorted};
+
+/// Stably sorts `(key, index)` pairs by key, least significant byte first,
+/// skipping bytes that are the same for every key.
+pub(crate) fn lsd_radix_sort(mut pairs: Vec<(u64, usize)>) -> Vec<(u64, usize)> {
+    let mut scratch = pairs.clone();
+    for shift in (0..64).step_by(8) {
+        let mut counts = [0usize; 256];
+        for &(key, _) in &pairs {
+            counts[(key >> shift) as usize & 0xff] += 1;
+        }
+        // a digit shared by every key doesn't change the order
+        if counts.contains(&pairs.len()) {
+            continue;
+        }
+        // turn the counts into the starting offset of each digit
+        let mut offset = 0;
+        for count in counts.iter_mut() {
+            let n = *count;
+            *count = offset;
+            offset += n;
+        }
+        // scattering in input order keeps equal digits in order, so each
+        // pass is stable and the passes compose
+        for &pair in &pairs {
+            let digit = (pair.0 >> shift) as usize & 0xff;
+            scratch[counts[digit]] = pair;
+            counts[digit] += 1;
+        }
+        mem::swap(&mut pairs, &mut scratch);
+    }
+    pairs
+}
+
+/// Sorts the slice, in place, by the `u64` key extracted with `key`,
+/// preserving the order of elements with equal keys.
+///
+/// This is a least significant digit radix sort on bytes of the key. `key`
+/// is called exactly once per element, the `(key, index)` pairs are sorted
+/// and then the permutation is applied to the slice with
+/// `apply_permutation`, so each element is moved only once. Passes over
+/// bytes that are the same in every key are skipped, so narrow keys cost
+/// fewer passes. It takes `O(n)` time for each of up to 8 passes and
+/// allocates two `(u64, usize)` buffers of the slice's length.
+///
+/// Stability is guaranteed, which means radix sorts can be chained to sort by
+/// several keys, least significant key first. The `keys` module maps signed
+/// integers, floats and other types to unsigned keys in order.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (1, 'e')];
+/// sortrs::radix_sort_by_key(&mut v, |x| x.0 as u64);
+/// assert!(v == [(1, 'b'), (1, 'e'), (2, 'd'), (3, 'a'), (3, 'c')]);
+///
+/// // signed keys
+/// let mut v = [5i64, -3, 0, -10, 7];
+/// sortrs::radix_sort_by_key(&mut v, |&x| sortrs::keys::i64_key(x));
+/// assert!(v == [-10, -3, 0, 5, 7]);
+/// ```
+pub fn radix_sort_by_key<T, F>(v: &mut [T], key: F)
+where
+    F: Fn(&T) -> u64,
+{
+    record_sorted(v.len());
+    if v.len() < 2 {
+        return;
+    }
+    let pairs = v
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (key(x), i))
+        .collect::<Vec<(u64, usize)>>();
+    let perm = lsd_radix_sort(pairs)
+        .into_iter()
+        .map(|(_, i)| i)
+        .collect::<Vec<usize>>();
+    apply_permutation(v, &perm);
+}
+
+/// Sorts the slice, in place, by several `u64` keys, preserving the order of
+/// elements that are equal in every key.
+///
+/// `keys` are in order of significance, so elements are ordered by
+/// `keys[0]`, then by `keys[1]` where `keys[0]` is equal, and so on, like
+/// comparing tuples. One stable radix pass is made per key, from the least
+/// significant to the most, over an index permutation, and the slice is
+/// permuted once at the end, so elements are moved only once however many
+/// keys there are. Each key function is called once per element. See
+/// `radix_sort_by_key` for the cost of each pass.
+///
+/// # Examples
+///
+/// ```rust
+/// struct Event { tenant: u32, timestamp: u64, seq: u16 }
+///
+/// let mut events = vec![
+///     Event { tenant: 2, timestamp: 10, seq: 0 },
+///     Event { tenant: 1, timestamp: 20, seq: 1 },
+///     Event { tenant: 1, timestamp: 10, seq: 2 },
+///     Event { tenant: 1, timestamp: 10, seq: 1 },
+/// ];
+/// sortrs::radix_sort_multi_key(&mut events, &[
+///     |e: &Event| e.tenant as u64,
+///     |e: &Event| e.timestamp,
+///     |e: &Event| e.seq as u64,
+/// ]);
+/// let order = events.iter().map(|e| (e.tenant, e.timestamp, e.seq)).collect::<Vec<_>>();
+/// assert!(order == [(1, 10, 1), (1, 10, 2), (1, 20, 1), (2, 10, 0)]);
+/// ```
+pub fn radix_sort_multi_key<T>(v: &mut [T], keys: &[fn(&T) -> u64]) {
+    record_sorted(v.len());
+    if v.len() < 2 {
+        return;
+    }
+    let mut perm = (0..v.len()).collect::<Vec<usize>>();
+    for key in keys.iter().rev() {
+        let pairs = perm
+            .iter()
+            .map(|&i| (key(&v[i]), i))
+            .collect::<Vec<(u64, usize)>>();
+        perm = lsd_radix_sort(pairs).into_iter().map(|(_, i)| i).collect();
+    }
+    apply_permutation(v, &perm);
+}
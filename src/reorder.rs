@@ -0,0 +1,130 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Reassembling sequence numbered items that arrive out of order.
+
+use super::{shift_down, shift_up};
+
+/// Orders heap entries so the smallest sequence number is at the root.
+#[inline]
+fn later<T>(a: &(u64, T), b: &(u64, T)) -> bool {
+    b.0 < a.0
+}
+
+/// A bounded buffer that accepts items tagged with sequence numbers in any
+/// order and releases them in sequence order, once every earlier item has
+/// arrived.
+///
+/// Pending items are kept in a heap on their sequence numbers, so `insert`
+/// and `pop` are `O(log n)` for `n` pending items. At most `capacity` items
+/// are held, and only sequence numbers less than `capacity` ahead of the
+/// next one to be released are accepted, so a lost item can't make the
+/// buffer grow without bound.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut buffer = sortrs::ReorderBuffer::new(8);
+/// buffer.insert(1, 'b').unwrap();
+/// assert_eq!(buffer.pop(), None);
+/// buffer.insert(0, 'a').unwrap();
+/// buffer.insert(2, 'c').unwrap();
+/// let ready = std::iter::from_fn(|| buffer.pop()).collect::<String>();
+/// assert_eq!(ready, "abc");
+/// assert_eq!(buffer.next_seq(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReorderBuffer<T> {
+    // (sequence number, item) pairs in heap order
+    heap: Vec<(u64, T)>,
+    next: u64,
+    capacity: usize,
+}
+
+impl<T> ReorderBuffer<T> {
+    /// Creates an empty buffer holding up to `capacity` items, whose first
+    /// item to release has sequence number 0.
+    pub fn new(capacity: usize) -> ReorderBuffer<T> {
+        ReorderBuffer::starting_at(capacity, 0)
+    }
+
+    /// Creates an empty buffer holding up to `capacity` items, whose first
+    /// item to release has sequence number `seq`.
+    pub fn starting_at(capacity: usize, seq: u64) -> ReorderBuffer<T> {
+        ReorderBuffer {
+            heap: Vec::with_capacity(capacity),
+            next: seq,
+            capacity,
+        }
+    }
+
+    /// Adds `item` with sequence number `seq` to the buffer.
+    ///
+    /// Returns the item back if the buffer is full or `seq` is outside the
+    /// window the buffer accepts: already released, or `capacity` or more
+    /// ahead of `next_seq`. If the same sequence number is inserted more than
+    /// once, only one of its items is released and the rest are dropped.
+    pub fn insert(&mut self, seq: u64, item: T) -> Result<(), T> {
+        if seq < self.next
+            || seq - self.next >= self.capacity as u64
+            || self.heap.len() == self.capacity
+        {
+            return Err(item);
+        }
+        self.heap.push((seq, item));
+        let last = self.heap.len() as isize - 1;
+        shift_up(self.heap.as_mut_ptr(), last, &later);
+        Ok(())
+    }
+
+    /// Removes and returns the item with sequence number `next_seq`, or
+    /// `None` if it hasn't arrived yet.
+    pub fn pop(&mut self) -> Option<T> {
+        // drop any duplicates of sequence numbers already released
+        while self.heap.first().is_some_and(|&(seq, _)| seq < self.next) {
+            self.remove_root();
+        }
+        match self.heap.first() {
+            Some(&(seq, _)) if seq == self.next => {
+                self.next += 1;
+                Some(self.remove_root())
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes the item at the root of the heap.
+    fn remove_root(&mut self) -> T {
+        let (_, item) = self.heap.swap_remove(0);
+        let len = self.heap.len() as isize;
+        if len > 1 {
+            shift_down(self.heap.as_mut_ptr(), 0, len - 1, &later);
+        }
+        item
+    }
+
+    /// Returns the sequence number of the next item to be released.
+    pub fn next_seq(&self) -> u64 {
+        self.next
+    }
+
+    /// Returns the number of items waiting in the buffer.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if no items are waiting in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns the maximum number of items the buffer holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! In-place block moves.
+
+use std::ops::Range;
+
+/// Rotates `v` left by `k` using three reversals. Requires `k <= v.len()`.
+#[inline]
+fn rotate_impl<T>(v: &mut [T], k: usize) {
+    v[..k].reverse();
+    v[k..].reverse();
+    v.reverse();
+}
+
+/// Rotates the elements of `v` in `range` left by `k` places, leaving the
+/// rest of `v` untouched.
+///
+/// The rotation is done in place with three reversals, in `O(range.len())`
+/// swaps.
+///
+/// # Panics
+///
+/// Panics if `range` is out of bounds or `k` is greater than its length.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [0, 1, 2, 3, 4, 5];
+/// sortrs::rotate_range_left(&mut v, 1..5, 1);
+/// assert!(v == [0, 2, 3, 4, 1, 5]);
+/// ```
+pub fn rotate_range_left<T>(v: &mut [T], range: Range<usize>, k: usize) {
+    let s = &mut v[range];
+    assert!(k <= s.len(), "rotation is larger than the range");
+    rotate_impl(s, k);
+}
+
+/// Rotates the elements of `v` in `range` right by `k` places, leaving the
+/// rest of `v` untouched.
+///
+/// The rotation is done in place with three reversals, in `O(range.len())`
+/// swaps.
+///
+/// # Panics
+///
+/// Panics if `range` is out of bounds or `k` is greater than its length.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [0, 1, 2, 3, 4, 5];
+/// sortrs::rotate_range_right(&mut v, 1..5, 1);
+/// assert!(v == [0, 4, 1, 2, 3, 5]);
+/// ```
+pub fn rotate_range_right<T>(v: &mut [T], range: Range<usize>, k: usize) {
+    let s = &mut v[range];
+    assert!(k <= s.len(), "rotation is larger than the range");
+    let len = s.len();
+    rotate_impl(s, len - k);
+}
+
+/// Moves the elements of `v` in `from` so that they start at index `to`,
+/// shifting the elements in between to close the gap.
+///
+/// `to` is the position of the block after the move, so the result is the
+/// same as removing the block and reinserting it at `to`. The relative order
+/// of all other elements is preserved.
+///
+/// # Panics
+///
+/// Panics if `from` is out of bounds or the moved block would extend past the
+/// end of `v`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = ['a', 'b', 'c', 'd', 'e', 'f'];
+/// sortrs::move_range(&mut v, 1..3, 3);
+/// assert!(v == ['a', 'd', 'e', 'b', 'c', 'f']);
+/// sortrs::move_range(&mut v, 3..5, 0);
+/// assert!(v == ['b', 'c', 'a', 'd', 'e', 'f']);
+/// ```
+pub fn move_range<T>(v: &mut [T], from: Range<usize>, to: usize) {
+    assert!(
+        from.start <= from.end && from.end <= v.len(),
+        "range out of bounds"
+    );
+    let len = from.end - from.start;
+    assert!(to + len <= v.len(), "destination out of bounds");
+    if to < from.start {
+        rotate_impl(&mut v[to..from.end], from.start - to);
+    } else if to > from.start {
+        rotate_impl(&mut v[from.start..to + len], len);
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Detection and manipulation of already ordered runs.
+
+use std::ptr;
+
+use super::search::upper_bound;
+use super::{introsort_impl, record_sorted};
+
+/// Reverses every strictly descending run of two or more elements in place,
+/// using `lt` to compare elements, and returns the number of runs reversed.
+///
+/// Only strictly descending runs are reversed so that equal elements never
+/// change order, which keeps this safe to use before a stable sort.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [1, 5, 4, 3, 3, 2, 6];
+/// assert_eq!(sortrs::reverse_descending_runs_by(&mut v, |a, b| a.lt(b)), 2);
+/// assert!(v == [1, 3, 4, 5, 2, 3, 6]);
+/// ```
+pub fn reverse_descending_runs_by<T, F>(v: &mut [T], lt: F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let mut runs = 0;
+    let mut start = 0;
+    while start + 1 < v.len() {
+        let mut end = start + 1;
+        while end < v.len() && lt(&v[end], &v[end - 1]) {
+            end += 1;
+        }
+        if end - start > 1 {
+            v[start..end].reverse();
+            runs += 1;
+        }
+        start = end;
+    }
+    runs
+}
+
+/// Reverses every strictly descending run of two or more elements in place
+/// and returns the number of runs reversed.
+///
+/// This is equivalent to `reverse_descending_runs_by(v, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [9, 8, 7, 1, 2];
+/// assert_eq!(sortrs::reverse_descending_runs(&mut v), 1);
+/// assert!(v == [1, 7, 8, 9, 2]);
+/// ```
+pub fn reverse_descending_runs<T: PartialOrd>(v: &mut [T]) -> usize {
+    reverse_descending_runs_by(v, |a, b| a.lt(b))
+}
+
+/// The order of a slice as found by `detect_order`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Already sorted in ascending order, including empty and single element
+    /// slices.
+    Ascending,
+    /// Sorted in descending order, so reversing it sorts it.
+    Descending,
+    /// An ascending sequence rotated left so that it starts at index `k`,
+    /// such as a full circular buffer. `v.rotate_left(k)` sorts it.
+    Rotated(usize),
+    /// None of the above.
+    Unsorted,
+}
+
+/// Detects whether the slice is already sorted, reverse sorted or a
+/// rotation of a sorted sequence, using `lt` to compare elements.
+///
+/// This is a single `O(n)` pass over the slice. A slice that is both
+/// ascending and descending, because its elements are all equal, is
+/// `Ascending`. A descending slice of two elements is `Descending` rather
+/// than `Rotated(1)`. A slice is only `Rotated` if its last element is less
+/// than its first, so a rotation whose wrap point falls between equal
+/// elements is `Unsorted`.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Order;
+///
+/// assert_eq!(sortrs::detect_order_by(&[1, 2, 3], |a, b| b.lt(a)), Order::Descending);
+/// assert_eq!(sortrs::detect_order_by(&[2, 1, 6, 4], |a, b| b.lt(a)), Order::Rotated(2));
+/// ```
+pub fn detect_order_by<T, F>(v: &[T], lt: F) -> Order
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    // the length of the ascending prefix
+    let k = 1 + v.windows(2).take_while(|w| !lt(&w[1], &w[0])).count();
+    if k >= len {
+        Order::Ascending
+    } else if v.windows(2).all(|w| !lt(&w[0], &w[1])) {
+        Order::Descending
+    } else if v[k..].windows(2).all(|w| !lt(&w[1], &w[0])) && lt(&v[len - 1], &v[0]) {
+        // both halves ascend and the end wraps around to the start with a
+        // strict descent, so no element of one half is equal to one of the
+        // other
+        Order::Rotated(k)
+    } else {
+        Order::Unsorted
+    }
+}
+
+/// Detects whether the slice is already sorted, reverse sorted or a
+/// rotation of a sorted sequence.
+///
+/// This is equivalent to `detect_order_by(v, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Order;
+///
+/// assert_eq!(sortrs::detect_order(&[1, 2, 2, 3]), Order::Ascending);
+/// assert_eq!(sortrs::detect_order(&[3, 2, 2, 1]), Order::Descending);
+/// assert_eq!(sortrs::detect_order(&[7, 8, 9, 1, 2]), Order::Rotated(3));
+/// assert_eq!(sortrs::detect_order(&[1, 3, 2, 4]), Order::Unsorted);
+/// ```
+pub fn detect_order<T: PartialOrd>(v: &[T]) -> Order {
+    detect_order_by(v, |a, b| a.lt(b))
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements, fixing
+/// descending and rotated slices with a reversal or rotation instead of a
+/// sort. Returns the order that was detected.
+///
+/// `Descending` slices are reversed, which reverses the order of equal
+/// elements. `Rotated` slices are rotated, which is `O(n)` and keeps equal
+/// elements in order. `Unsorted` slices are sorted with `introsort_by`.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::Order;
+///
+/// let mut log = [(7, 'h'), (9, 'i'), (2, 'a'), (4, 'd')];
+/// let order = sortrs::sort_presorted_by(&mut log, |a, b| a.0 < b.0);
+/// assert_eq!(order, Order::Rotated(2));
+/// assert!(log == [(2, 'a'), (4, 'd'), (7, 'h'), (9, 'i')]);
+/// ```
+pub fn sort_presorted_by<T, F>(v: &mut [T], lt: F) -> Order
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    let order = detect_order_by(v, &lt);
+    match order {
+        Order::Ascending => {}
+        Order::Descending => v.reverse(),
+        Order::Rotated(k) => v.rotate_left(k),
+        Order::Unsorted => introsort_impl(v, lt),
+    }
+    order
+}
+
+/// Sorts the slice, in place, fixing descending and rotated slices with a
+/// reversal or rotation instead of a sort. Returns the order that was
+/// detected.
+///
+/// This is equivalent to `sort_presorted_by(v, |a, b| a.lt(b))`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [4, 5, 1, 2, 3];
+/// assert_eq!(sortrs::sort_presorted(&mut v), sortrs::Order::Rotated(2));
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn sort_presorted<T: PartialOrd>(v: &mut [T]) -> Order {
+    sort_presorted_by(v, |a, b| a.lt(b))
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements, assuming that
+/// no element is more than `k` positions from where it belongs.
+///
+/// The slice is sorted in overlapping windows of `2 * k` elements, stepping
+/// `k` elements at a time, which is `O(n log k)` rather than `O(n log n)`.
+/// After each window is sorted its first `k` elements are final, as nothing
+/// further on can belong before them. If the assumption holds the whole
+/// slice ends up sorted. If it doesn't, elements that are further out of
+/// place only move towards the front by up to `k` positions per window and
+/// the result is not sorted, no work is spent checking for this.
+///
+/// # Examples
+///
+/// ```rust
+/// // timestamps from sensors that can arrive up to 2 readings late
+/// let mut v = [1, 3, 2, 4, 7, 5, 6, 9, 8];
+/// sortrs::approx_sort_by(&mut v, 2, |a, b| a.lt(b));
+/// assert!(v == [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+/// ```
+pub fn approx_sort_by<T, F>(v: &mut [T], k: usize, lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if k == 0 {
+        return;
+    }
+    record_sorted(v.len());
+    let len = v.len();
+    let mut start = 0usize;
+    loop {
+        let end = start.saturating_add(k.saturating_mul(2)).min(len);
+        introsort_impl(&mut v[start..end], &lt);
+        if end == len {
+            break;
+        }
+        start += k;
+    }
+}
+
+/// Sorts the slice, in place, assuming that no element is more than `k`
+/// positions from where it belongs.
+///
+/// This is equivalent to `approx_sort_by(v, k, |a, b| a.lt(b))`, see
+/// `approx_sort_by` for what happens when the assumption doesn't hold.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [2, 1, 3, 5, 4];
+/// sortrs::approx_sort(&mut v, 1);
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn approx_sort<T: PartialOrd>(v: &mut [T], k: usize) {
+    approx_sort_by(v, k, |a, b| a.lt(b))
+}
+
+/// Sorts an almost sorted slice, in place, by taking out the few elements
+/// that are out of place and inserting them back where they belong, using
+/// `lt` to compare elements.
+///
+/// A single pass finds a longest ascending subsequence and sets aside the
+/// `k` elements outside it, the fewest that can be. They are sorted and
+/// their places in the rest are found by binary search, then each element
+/// is moved at most once to merge them back. This is `O(n + k log n)`
+/// comparisons, with `O(n)` extra memory, so after a handful of elements of
+/// a sorted slice are updated it is much cheaper than sorting again. The
+/// order of equal elements is not preserved.
+///
+/// Returns the number of elements that were out of place. If more than
+/// `max_displaced` are found the slice is not almost sorted after all, so
+/// it is sorted with `introsort_by` instead and `None` is returned.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [1, 2, 30, 4, 5, 6, 7, 0, 9];
+/// assert_eq!(sortrs::repair_sorted_by(&mut v, 4, |a, b| a.lt(b)), Some(2));
+/// assert!(v == [0, 1, 2, 4, 5, 6, 7, 9, 30]);
+/// ```
+pub fn repair_sorted_by<T, F>(v: &mut [T], max_displaced: usize, lt: F) -> Option<usize>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    let len = v.len();
+    // the fewest elements to set aside are those outside a longest
+    // ascending subsequence, found by patience sorting: tails[l] is the
+    // index of the least last element of an ascending subsequence of length
+    // l + 1 and prev links each element to the one before it in its own
+    const NONE: usize = usize::MAX;
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![NONE; len];
+    for i in 0..len {
+        match tails.last() {
+            Some(&last) if lt(&v[i], &v[last]) => {
+                // replace the first tail greater than v[i]
+                let (mut lo, mut hi) = (0, tails.len() - 1);
+                while lo < hi {
+                    let mid = lo + (hi - lo) / 2;
+                    if lt(&v[i], &v[tails[mid]]) {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1;
+                    }
+                }
+                if lo > 0 {
+                    prev[i] = tails[lo - 1];
+                }
+                tails[lo] = i;
+            }
+            last => {
+                if let Some(&last) = last {
+                    prev[i] = last;
+                }
+                tails.push(i);
+            }
+        }
+        // each element extends the longest subsequence by at most one, so
+        // the number set aside can't go down again
+        if i + 1 - tails.len() > max_displaced {
+            introsort_impl(v, &lt);
+            return None;
+        }
+    }
+
+    // move the subsequence to the front, in order, and the rest behind it
+    let kept = tails.len();
+    let mut j = tails.last().cloned().unwrap_or(NONE);
+    let mut keep = Vec::with_capacity(kept);
+    while j != NONE {
+        keep.push(j);
+        j = prev[j];
+    }
+    for (k, &j) in keep.iter().rev().enumerate() {
+        v.swap(k, j);
+    }
+
+    let displaced = len - kept;
+    if displaced == 0 {
+        return Some(0);
+    }
+    introsort_impl(&mut v[kept..], &lt);
+    // find every insertion point before moving anything, so a panic in `lt`
+    // can't leave the slice with elements duplicated or missing
+    let mut places = (kept..len)
+        .map(|j| upper_bound(&v[..kept], &v[j], &lt))
+        .collect::<Vec<usize>>();
+    // the displaced elements are sorted so the places never go down, unless
+    // `lt` is inconsistent, and the merge relies on it
+    for j in 1..places.len() {
+        places[j] = places[j].max(places[j - 1]);
+    }
+    unsafe {
+        let ptr = v.as_mut_ptr();
+        let mut tmp: Vec<T> = Vec::with_capacity(displaced);
+        ptr::copy_nonoverlapping(ptr.add(kept), tmp.as_mut_ptr(), displaced);
+        // merge from the back, shifting each stretch of kept elements up by
+        // the number of displaced elements that belong before it
+        let mut end = kept;
+        for (j, &place) in places.iter().enumerate().rev() {
+            ptr::copy(ptr.add(place), ptr.add(place + j + 1), end - place);
+            ptr::copy_nonoverlapping(tmp.as_ptr().add(j), ptr.add(place + j), 1);
+            end = place;
+        }
+        // tmp's length is still 0, so dropping it only frees its memory
+    }
+    Some(displaced)
+}
+
+/// Sorts an almost sorted slice, in place, by taking out the few elements
+/// that are out of place and inserting them back where they belong.
+///
+/// This is equivalent to `repair_sorted_by(v, max_displaced, |a, b|
+/// a.lt(b))`, see `repair_sorted_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut prices = (0..1000).map(|x| x * 10).collect::<Vec<u32>>();
+/// // update a couple of records in place
+/// prices[10] = 5005;
+/// prices[900] = 15;
+/// assert_eq!(sortrs::repair_sorted(&mut prices, 10), Some(2));
+/// assert!(prices.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn repair_sorted<T: PartialOrd>(v: &mut [T], max_displaced: usize) -> Option<usize> {
+    repair_sorted_by(v, max_displaced, |a, b| a.lt(b))
+}
+
+/// Returns the boundaries of up to `target_chunks` pieces of `v` of roughly
+/// equal length, cutting where an ascending run ends where possible, using
+/// `lt` to compare elements.
+///
+/// The returned vector starts with 0 and ends with `v.len()`, and piece `i`
+/// is `v[b[i]..b[i + 1]]`. Each cut is moved from its evenly spaced position
+/// to the nearest run boundary, an index `i` with `v[i] < v[i - 1]`, within
+/// a quarter of the target piece length. If there is no boundary that close
+/// the cut stays put. Cutting between runs rather than through them leaves
+/// each piece with fewer, longer runs, and pieces that are already sorted
+/// need no work at all, so the pieces can be sorted independently, in
+/// parallel say, with a stable sort and merged back in order without
+/// losing stability.
+///
+/// This takes `O(n)` comparisons at most and typically far fewer, as only
+/// the neighbourhood of each cut is examined. Pieces are never empty, so
+/// there are fewer than `target_chunks` if `v` is shorter than that.
+///
+/// # Panics
+///
+/// Panics if `target_chunks` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4];
+/// // an even split would cut at 6, the run boundary at 7 is close enough
+/// assert!(sortrs::split_into_sorted_chunks_by(&v, 2, |a, b| a.lt(b)) == [0, 7, 12]);
+/// ```
+pub fn split_into_sorted_chunks_by<T, F>(v: &[T], target_chunks: usize, lt: F) -> Vec<usize>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(target_chunks != 0, "there must be at least one chunk");
+    let len = v.len();
+    let pieces = target_chunks.min(len.max(1));
+    let reach = len / pieces / 4;
+    let is_boundary = |i: usize| lt(&v[i], &v[i - 1]);
+
+    let mut bounds = vec![0];
+    for k in 1..pieces {
+        let ideal = k * len / pieces;
+        // search outwards from the ideal cut, nearest first
+        let cut = (0..reach + 1)
+            .flat_map(|d| [ideal + d, ideal.wrapping_sub(d)])
+            .find(|&i| i > 0 && i < len && is_boundary(i))
+            .unwrap_or(ideal);
+        // a cut pulled back behind the previous one would leave a piece empty
+        if cut > *bounds.last().unwrap() {
+            bounds.push(cut);
+        }
+    }
+    if len > 0 {
+        bounds.push(len);
+    }
+    bounds
+}
+
+/// Returns the boundaries of up to `target_chunks` pieces of `v` of roughly
+/// equal length, cutting where an ascending run ends where possible.
+///
+/// This is equivalent to `split_into_sorted_chunks_by(v, target_chunks, |a,
+/// b| a.lt(b))`, see `split_into_sorted_chunks_by` for details.
+///
+/// # Panics
+///
+/// Panics if `target_chunks` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// // three sorted runs of uneven length
+/// let v = [(0..90).collect::<Vec<u32>>(), (0..110).collect(), (0..100).collect()].concat();
+/// let bounds = sortrs::split_into_sorted_chunks(&v, 3);
+/// assert!(bounds == [0, 90, 200, 300]);
+/// // every piece is a single run, so is already sorted
+/// assert!(bounds
+///     .windows(2)
+///     .all(|w| sortrs::detect_order(&v[w[0]..w[1]]) == sortrs::Order::Ascending));
+/// ```
+pub fn split_into_sorted_chunks<T: PartialOrd>(v: &[T], target_chunks: usize) -> Vec<usize> {
+    split_into_sorted_chunks_by(v, target_chunks, |a, b| a.lt(b))
+}
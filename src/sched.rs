@@ -0,0 +1,45 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Running the independent tasks of the parallel algorithms.
+//!
+//! The parallel algorithms split their work into tasks on disjoint parts of
+//! the data and hand them to a `Scheduler`, which must run them all before
+//! returning. `ThreadScheduler` runs each on its own scoped thread. Tests can
+//! supply a scheduler that runs them in a chosen order, or one at a time, to
+//! explore interleavings deterministically.
+
+use std::thread;
+
+/// A unit of work handed to a `Scheduler`.
+pub type Task<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Runs the tasks of a parallel algorithm.
+pub trait Scheduler {
+    /// Runs every task in `tasks` exactly once, in any order and on any
+    /// threads, and returns once all of them have finished.
+    ///
+    /// The tasks work on disjoint data, so they may run concurrently. If a
+    /// task panics the panic should be propagated once the others finish, as
+    /// `ThreadScheduler` does.
+    fn join_all<'a>(&self, tasks: Vec<Task<'a>>);
+}
+
+/// The default `Scheduler`, which runs each task on its own scoped thread.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThreadScheduler;
+
+impl Scheduler for ThreadScheduler {
+    fn join_all<'a>(&self, tasks: Vec<Task<'a>>) {
+        thread::scope(|scope| {
+            for task in tasks {
+                scope.spawn(task);
+            }
+        });
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Binary searches over sorted slices.
+
+use std::ops::{Bound, RangeBounds};
+
+use super::timsort::timsort_impl;
+
+/// Index of the first element of `v` that is not less than `key`.
+#[inline]
+pub(crate) fn lower_bound<T, F>(v: &[T], key: &T, lt: &F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    v.partition_point(|x| lt(x, key))
+}
+
+/// Index of the first element of `v` that is greater than `key`.
+#[inline]
+pub(crate) fn upper_bound<T, F>(v: &[T], key: &T, lt: &F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    v.partition_point(|x| !lt(key, x))
+}
+
+/// Index of the first element of `v` that is not less than `key`, found by
+/// probing exponentially further from the front before binary searching the
+/// last step. This is `O(log i)` for a result of `i`, so it beats
+/// `lower_bound` when the result is expected near the front.
+pub(crate) fn gallop_lower_bound<T, F>(v: &[T], key: &T, lt: &F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    // probe v[0], v[1], v[3], v[7], ... until one is not less than key
+    let mut bound = 1;
+    while bound <= v.len() && lt(&v[bound - 1], key) {
+        bound *= 2;
+    }
+    // the previous probe, v[bound / 2 - 1], was less than key
+    let start = bound / 2;
+    let end = bound.min(v.len());
+    start + lower_bound(&v[start..end], key, lt)
+}
+
+/// Calls `f` with each pair of equivalent elements of the sorted `small` and
+/// `large`, pairing each element at most once.
+fn for_each_match<T, F, G>(small: &[T], large: &[T], lt: &F, mut f: G)
+where
+    F: Fn(&T, &T) -> bool,
+    G: FnMut(&T, &T),
+{
+    let mut rest = large;
+    for x in small {
+        // gallop from the last match, which costs O(1) while the inputs are
+        // dense and O(log gap) across long stretches of `large`
+        rest = &rest[gallop_lower_bound(rest, x, lt)..];
+        match rest.split_first() {
+            Some((y, tail)) => {
+                if !lt(x, y) {
+                    f(x, y);
+                    rest = tail;
+                }
+            }
+            None => break,
+        }
+    }
+}
+
+/// Returns the elements of the sorted `a` that have an equivalent element in
+/// the sorted `b`, using `lt` to compare elements.
+///
+/// Both inputs must be sorted with respect to `lt`. Duplicates are matched
+/// one to one, so an element appearing `m` times in `a` and `n` times in `b`
+/// appears `min(m, n)` times in the result. The returned elements are
+/// clones of those in `a`.
+///
+/// The smaller input is walked while the larger is searched by galloping
+/// forward from the previous match. This is `O(m log(n / m))` for inputs of
+/// sizes `m <= n`, so intersecting a short list with a huge one only touches
+/// a small part of the huge one, while similar sized inputs are merged in
+/// linear time.
+///
+/// # Examples
+///
+/// ```rust
+/// let a = [(1, 'a'), (4, 'b'), (9, 'c')];
+/// let b = [(0, 'x'), (1, 'y'), (2, 'y'), (9, 'z')];
+/// let v = sortrs::intersect_sorted_by(&a, &b, |x, y| x.0 < y.0);
+/// assert!(v == [(1, 'a'), (9, 'c')]);
+/// ```
+pub fn intersect_sorted_by<T, F>(a: &[T], b: &[T], lt: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut matched = Vec::new();
+    if a.len() <= b.len() {
+        for_each_match(a, b, &lt, |x, _| matched.push(x.clone()));
+    } else {
+        for_each_match(b, a, &lt, |_, x| matched.push(x.clone()));
+    }
+    matched
+}
+
+/// Returns the elements of the sorted `a` that are also in the sorted `b`.
+///
+/// Galloping search makes this fast when one input is much smaller than the
+/// other. See `intersect_sorted_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let small = [3, 500, 999];
+/// let large = (0..1000).collect::<Vec<u32>>();
+/// assert!(sortrs::intersect_sorted(&small, &large) == [3, 500, 999]);
+/// assert!(sortrs::intersect_sorted(&[1, 1, 2], &[1, 1, 1]) == [1, 1]);
+/// ```
+pub fn intersect_sorted<T: PartialOrd + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    intersect_sorted_by(a, b, |x, y| x.lt(y))
+}
+
+/// Splits the sorted slice `v` into the elements less than, equal to and
+/// greater than `key`, using `lt` to compare elements.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [5, 4, 4, 2, 1];
+/// let (greater, equal, less) = sortrs::split_sorted_by(&v, &4, |a, b| b.lt(a));
+/// assert!(greater == [5] && equal == [4, 4] && less == [2, 1]);
+/// ```
+pub fn split_sorted_by<'a, T, F>(v: &'a [T], key: &T, lt: F) -> (&'a [T], &'a [T], &'a [T])
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let lower = lower_bound(v, key, &lt);
+    let upper = lower + upper_bound(&v[lower..], key, &lt);
+    (&v[..lower], &v[lower..upper], &v[upper..])
+}
+
+/// Splits the sorted slice `v` into the elements less than, equal to and
+/// greater than `key`.
+///
+/// Each boundary is found with a binary search, so this is `O(log n)`.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [1, 2, 4, 4, 5];
+/// let (less, equal, greater) = sortrs::split_sorted(&v, &4);
+/// assert!(less == [1, 2] && equal == [4, 4] && greater == [5]);
+/// ```
+pub fn split_sorted<'a, T: PartialOrd>(v: &'a [T], key: &T) -> (&'a [T], &'a [T], &'a [T]) {
+    split_sorted_by(v, key, |a, b| a.lt(b))
+}
+
+/// Returns the `n`th smallest element, counting from zero, of the sorted
+/// slices in `parts` taken together, without merging them, using `lt` to
+/// compare elements. Returns `None` if `n` is not less than their total
+/// length.
+///
+/// Each part must be sorted by `lt` in ascending order. Among equivalent
+/// elements any one may be returned. If `lt` is not a strict weak order the
+/// result is unspecified, but it is still returned.
+///
+/// # Examples
+///
+/// ```rust
+/// let a = [9, 7, 3];
+/// let b = [8, 5, 2, 1];
+/// let parts: [&[i32]; 2] = [&a, &b];
+/// assert_eq!(sortrs::select_nth_from_sorted_by(&parts, 2, |x, y| y.lt(x)), Some(&7));
+/// ```
+pub fn select_nth_from_sorted_by<'a, T, F>(parts: &[&'a [T]], n: usize, lt: F) -> Option<&'a T>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let total: usize = parts.iter().map(|p| p.len()).sum();
+    if n >= total {
+        return None;
+    }
+    // the answer is the nth smallest of the elements left in these ranges
+    let mut ranges = parts.iter().map(|p| (0, p.len())).collect::<Vec<_>>();
+    let mut n = n;
+    loop {
+        let mut mids = ranges
+            .iter()
+            .zip(parts)
+            .filter(|&(r, _)| r.0 < r.1)
+            .map(|(r, p)| (&p[r.0 + (r.1 - r.0) / 2], r.1 - r.0))
+            .collect::<Vec<(&'a T, usize)>>();
+        timsort_impl(&mut mids, &|a: &(&T, usize), b: &(&T, usize)| lt(a.0, b.0));
+        // the middle of the parts weighted by their remaining length, so at
+        // least half of the remaining elements are in parts whose middle is
+        // on each side of it
+        let remaining: usize = mids.iter().map(|m| m.1).sum();
+        let mut seen = 0;
+        let pivot = mids
+            .iter()
+            .find(|m| {
+                seen += m.1;
+                2 * seen >= remaining
+            })
+            .unwrap()
+            .0;
+
+        let (mut less, mut not_greater) = (0, 0);
+        let bounds = ranges
+            .iter()
+            .zip(parts)
+            .map(|(r, p)| {
+                let (lower, equal, _) = split_sorted_by(&p[r.0..r.1], pivot, &lt);
+                less += lower.len();
+                not_greater += lower.len() + equal.len();
+                (r.0 + lower.len(), r.0 + lower.len() + equal.len())
+            })
+            .collect::<Vec<_>>();
+        let before = ranges.clone();
+        if n < less {
+            for (r, b) in ranges.iter_mut().zip(&bounds) {
+                r.1 = b.0;
+            }
+        } else if n < not_greater {
+            return Some(pivot);
+        } else {
+            n -= not_greater;
+            for (r, b) in ranges.iter_mut().zip(&bounds) {
+                r.0 = b.1;
+            }
+        }
+        // the pivot is always discarded under a strict weak order, so a round
+        // that discards nothing means `lt` is inconsistent and another would
+        // never finish
+        if ranges == before {
+            return Some(pivot);
+        }
+    }
+}
+
+/// Returns the `n`th smallest element, counting from zero, of the sorted
+/// slices in `parts` taken together, without merging them. Returns `None` if
+/// `n` is not less than their total length.
+///
+/// This is equivalent to `select_nth_from_sorted_by(parts, n, |a, b|
+/// a.lt(b))`. Each round binary searches every part for the weighted median
+/// of their middle elements and discards at least a quarter of the elements
+/// left, so for `k` parts of at most `m` elements this is `O(k log m)`
+/// comparisons per round and `O(log(k m))` rounds, with `O(k)` extra memory.
+///
+/// # Examples
+///
+/// ```rust
+/// let shards: Vec<Vec<u32>> = vec![vec![1, 4, 9], vec![2, 3], vec![5, 6, 7, 8]];
+/// let parts = shards.iter().map(|s| &s[..]).collect::<Vec<&[u32]>>();
+/// // the median of all nine
+/// assert_eq!(sortrs::select_nth_from_sorted(&parts, 4), Some(&5));
+/// assert_eq!(sortrs::select_nth_from_sorted(&parts, 9), None);
+/// ```
+pub fn select_nth_from_sorted<'a, T: PartialOrd>(parts: &[&'a [T]], n: usize) -> Option<&'a T> {
+    select_nth_from_sorted_by(parts, n, |a, b| a.lt(b))
+}
+
+/// Returns the contiguous subslice of the sorted `v` whose keys, extracted
+/// with `key`, fall within `bounds`.
+///
+/// `v` must be sorted by `key` in ascending order. Both ends of the range are
+/// found with a binary search, so this is `O(log n)`.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [(1, 'a'), (3, 'b'), (4, 'c'), (4, 'd'), (7, 'e')];
+/// assert!(sortrs::range_sorted(&v, 3..5, |x| x.0) == [(3, 'b'), (4, 'c'), (4, 'd')]);
+/// assert!(sortrs::range_sorted(&v, ..=3, |x| x.0) == [(1, 'a'), (3, 'b')]);
+/// assert!(sortrs::range_sorted(&v, 8.., |x| x.0).is_empty());
+/// ```
+pub fn range_sorted<T, K, R, F>(v: &[T], bounds: R, key: F) -> &[T]
+where
+    K: PartialOrd,
+    R: RangeBounds<K>,
+    F: Fn(&T) -> K,
+{
+    let start = match bounds.start_bound() {
+        Bound::Included(s) => v.partition_point(|x| key(x).lt(s)),
+        Bound::Excluded(s) => v.partition_point(|x| !s.lt(&key(x))),
+        Bound::Unbounded => 0,
+    };
+    let end = match bounds.end_bound() {
+        Bound::Included(e) => v.partition_point(|x| !e.lt(&key(x))),
+        Bound::Excluded(e) => v.partition_point(|x| key(x).lt(e)),
+        Bound::Unbounded => v.len(),
+    };
+    // an inverted range selects nothing
+    &v[start..end.max(start)]
+}
+
+/// Returns the index of the first element of the sorted `v` whose key,
+/// extracted with `f`, equals `key`, or `None` if there isn't one.
+///
+/// `v` must be sorted by `f` in ascending order. The first matching element
+/// is found with a binary search, so this is `O(log n)` calls to `f`, and
+/// unlike `binary_search_by_key` the result is always the first of several
+/// matches.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [(1, 'a'), (3, 'b'), (3, 'c'), (7, 'd')];
+/// assert_eq!(sortrs::position_sorted_by_key(&v, &3, |x| x.0), Some(1));
+/// assert_eq!(sortrs::position_sorted_by_key(&v, &4, |x| x.0), None);
+/// ```
+pub fn position_sorted_by_key<T, K, F>(v: &[T], key: &K, f: F) -> Option<usize>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    let i = v.partition_point(|x| f(x).lt(key));
+    match v.get(i) {
+        Some(x) if !key.lt(&f(x)) => Some(i),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the sorted `v` has an element whose key, extracted with
+/// `f`, equals `key`.
+///
+/// `v` must be sorted by `f` in ascending order. This is a binary search,
+/// see `position_sorted_by_key`.
+///
+/// # Examples
+///
+/// ```rust
+/// let users = [(101, "ann"), (205, "bob"), (317, "cy")];
+/// assert!(sortrs::contains_sorted_by_key(&users, &205, |u| u.0));
+/// assert!(!sortrs::contains_sorted_by_key(&users, &206, |u| u.0));
+/// ```
+pub fn contains_sorted_by_key<T, K, F>(v: &[T], key: &K, f: F) -> bool
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    position_sorted_by_key(v, key, f).is_some()
+}
+
+/// Returns a mutable reference to the first element of the sorted `v` whose
+/// key, extracted with `f`, equals `key`, or `None` if there isn't one.
+///
+/// `v` must be sorted by `f` in ascending order, and the element must not be
+/// changed in a way that changes its key if `v` is to stay sorted. This is
+/// a binary search, see `position_sorted_by_key`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut stock = [("apple", 3), ("kiwi", 0), ("pear", 8)];
+/// if let Some(item) = sortrs::find_sorted_mut(&mut stock, &"kiwi", |x| x.0) {
+///     item.1 += 5;
+/// }
+/// assert!(stock == [("apple", 3), ("kiwi", 5), ("pear", 8)]);
+/// ```
+pub fn find_sorted_mut<'a, T, K, F>(v: &'a mut [T], key: &K, f: F) -> Option<&'a mut T>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    position_sorted_by_key(v, key, f).map(move |i| &mut v[i])
+}
@@ -0,0 +1,770 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Order statistics.
+
+use std::collections::VecDeque;
+
+use super::search::{lower_bound, upper_bound};
+use super::{
+    insertsort_impl, introsort_impl, partial_sort_rest, partition, partition_at_impl,
+    partition_pivot, ptr_distance, sortable_len,
+};
+
+/// Reorders `v` so that the element at index `n` is the one that would be
+/// there if `v` were sorted, with no greater elements before it and no
+/// smaller elements after it. Requires `n < v.len()`.
+///
+/// This is introselect: quickselect using the introsort partition, switching
+/// to median of medians pivots for the remaining range once the ranges
+/// partitioned add up to `4n`, so it is `O(n)` on average and in the worst
+/// case.
+pub(crate) fn select_impl<T, F>(v: &mut [T], n: usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    // Threshold at which the remaining range is insertion sorted
+    const THRESHOLD: usize = 16;
+
+    if sortable_len(v).is_none() {
+        return;
+    }
+    let mut lo = 0;
+    let mut hi = v.len();
+    // quickselect partitions about 3n elements in all on average, if it has
+    // partitioned many more than that it is going quadratic
+    let mut budget = 4 * v.len();
+    loop {
+        let len = hi - lo;
+        let ptr = v[lo..hi].as_mut_ptr();
+        if len <= THRESHOLD {
+            insertsort_impl(ptr, len as isize, lt);
+            return;
+        }
+        let fallback = budget < len;
+        budget = budget.saturating_sub(len);
+        // the pivot is left at the front of the range, swap it to the end of
+        // the smaller elements which is its sorted position
+        let upper = if fallback {
+            // pick a pivot that is sure to cut off at least 3/10 of the range
+            let pivot = median_of_medians(&mut v[lo..hi], lt);
+            v.swap(lo, lo + pivot);
+            let ptr = v[lo..hi].as_mut_ptr();
+            unsafe { partition(ptr.add(1), ptr.add(len), ptr, lt) }
+        } else {
+            partition_pivot(ptr, len as isize, lt)
+        };
+        let mid = lo + ptr_distance(upper, ptr) as usize - 1;
+        v.swap(lo, mid);
+        if n < mid {
+            hi = mid;
+        } else if n > mid {
+            lo = mid + 1;
+        } else {
+            return;
+        }
+    }
+}
+
+/// Returns the index of the median of the medians of each group of 5
+/// elements of the `len > 16` elements of `v`, moving the medians to the
+/// front of `v`.
+fn median_of_medians<T, F>(v: &mut [T], lt: &F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let groups = v.len() / 5;
+    for g in 0..groups {
+        insertsort_impl(v[5 * g..].as_mut_ptr(), 5, lt);
+        v.swap(g, 5 * g + 2);
+    }
+    select_impl(&mut v[..groups], groups / 2, lt);
+    groups / 2
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position,
+/// using `lt` to compare elements, and returns the elements before it, the
+/// element and the elements after it.
+///
+/// No element before `index` is greater than the element at `index` and no
+/// element after it is less. This is introselect, quickselect using the
+/// introsort partition, switching to median of medians pivots if the
+/// partitions are poor, so it is `O(n)` even in the worst case. The order of
+/// the elements either side is unspecified.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// let (_, second_largest, larger) = sortrs::select_nth_unstable_by(&mut v, 1, |a, b| b.lt(a));
+/// assert_eq!(*second_largest, 4);
+/// assert!(larger.iter().all(|&x| x < 4));
+/// ```
+pub fn select_nth_unstable_by<T, F>(
+    v: &mut [T],
+    index: usize,
+    lt: F,
+) -> (&mut [T], &mut T, &mut [T])
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    assert!(
+        index < len,
+        "partition_at_index index {} greater than length of slice {}",
+        index,
+        len
+    );
+    select_impl(v, index, &lt);
+    let (left, rest) = v.split_at_mut(index);
+    let (nth, right) = rest.split_first_mut().unwrap();
+    (left, nth, right)
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position
+/// and returns the elements before it, the element and the elements after
+/// it.
+///
+/// This is equivalent to `select_nth_unstable_by(v, index, |a, b| a.lt(b))`,
+/// see `select_nth_unstable_by` for details.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [9, 1, 8, 2, 7, 3, 6];
+/// let (_, median, _) = sortrs::select_nth_unstable(&mut v, 3);
+/// assert_eq!(*median, 6);
+/// ```
+pub fn select_nth_unstable<T: PartialOrd>(
+    v: &mut [T],
+    index: usize,
+) -> (&mut [T], &mut T, &mut [T]) {
+    select_nth_unstable_by(v, index, |a, b| a.lt(b))
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position by
+/// the key extracted with `key`, and returns the elements before it, the
+/// element and the elements after it.
+///
+/// `key` is called twice per comparison, see `select_nth_unstable_by` for
+/// details.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [("c", 3), ("a", 1), ("b", 2)];
+/// let (_, nth, _) = sortrs::select_nth_unstable_by_key(&mut v, 0, |x| x.1);
+/// assert!(*nth == ("a", 1));
+/// ```
+pub fn select_nth_unstable_by_key<T, K, F>(
+    v: &mut [T],
+    index: usize,
+    key: F,
+) -> (&mut [T], &mut T, &mut [T])
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    select_nth_unstable_by(v, index, |a, b| key(a).lt(&key(b)))
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position,
+/// using `lt` to compare elements, and gathers the elements equivalent to it
+/// around it. Returns `(mid, equal)` where `v[..mid]` is less than the
+/// element, `v[mid..mid + equal]` is equivalent to it and
+/// `v[mid + equal..]` is greater, with `mid <= index < mid + equal`.
+///
+/// This is `select_nth_unstable_by` followed by a three way partition about
+/// the selected element, so the run of ties is found in the same `O(n)`
+/// time. Every element of the run is in its sorted position.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [3, 1, 2, 2, 5, 2];
+/// let (mid, equal) = sortrs::select_nth_unstable_equal_by(&mut v, 2, |a, b| a.lt(b));
+/// assert_eq!((mid, equal), (1, 3));
+/// assert_eq!(v[1..4], [2, 2, 2]);
+/// ```
+pub fn select_nth_unstable_equal_by<T, F>(v: &mut [T], index: usize, lt: F) -> (usize, usize)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    assert!(
+        index < len,
+        "partition_at_index index {} greater than length of slice {}",
+        index,
+        len
+    );
+    select_impl(v, index, &lt);
+    partition_at_impl(v, index, &lt)
+}
+
+/// Reorders the slice so the element at `index` is in its sorted position
+/// with the elements equal to it around it, and returns `(mid, equal)` where
+/// `v[mid..mid + equal]` are the elements equal to it.
+///
+/// This is equivalent to
+/// `select_nth_unstable_equal_by(v, index, |a, b| a.lt(b))`, see
+/// `select_nth_unstable_equal_by` for details.
+///
+/// # Panics
+///
+/// Panics when `index >= v.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [4, 9, 4, 1, 4, 7];
+/// let (mid, equal) = sortrs::select_nth_unstable_equal(&mut v, 3);
+/// assert_eq!((mid, equal), (1, 3));
+/// assert!(v[mid..mid + equal].iter().all(|&x| x == 4));
+/// ```
+pub fn select_nth_unstable_equal<T: PartialOrd>(v: &mut [T], index: usize) -> (usize, usize) {
+    select_nth_unstable_equal_by(v, index, |a, b| a.lt(b))
+}
+
+#[inline]
+fn f64_lt(a: &f64, b: &f64) -> bool {
+    a.lt(b)
+}
+
+/// Returns the `p` quantile of the non-empty `v`, interpolating linearly
+/// between the two closest ranks. Reorders `v`.
+fn quantile_impl(v: &mut [f64], p: f64) -> f64 {
+    let rank = p * (v.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let fraction = rank - lower as f64;
+    select_impl(v, lower, &f64_lt);
+    let lower_value = v[lower];
+    if fraction > 0.0 {
+        // everything after the selected element is at least as large, so the
+        // next rank is the smallest of them
+        let upper_value = v[lower + 1..]
+            .iter()
+            .fold(f64::INFINITY, |min, &x| min.min(x));
+        lower_value + (upper_value - lower_value) * fraction
+    } else {
+        lower_value
+    }
+}
+
+/// Returns the mean of `v` after discarding the smallest and largest
+/// `fraction` of its values, or `None` if `v` is empty.
+///
+/// `floor(fraction * v.len())` values are discarded from each end. They are
+/// found with in-place selection rather than a sort, so this is `O(n)` on
+/// average, and **reorders the slice**. The result is unspecified if `v`
+/// contains NaN.
+///
+/// # Panics
+///
+/// Panics if `fraction` is not in the range `[0, 0.5)`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [1.0, 100.0, 2.0, 3.0, -50.0];
+/// assert_eq!(sortrs::trimmed_mean(&mut v, 0.2), Some(2.0));
+/// ```
+pub fn trimmed_mean(v: &mut [f64], fraction: f64) -> Option<f64> {
+    assert!(
+        (0.0..0.5).contains(&fraction),
+        "trim fraction must be in [0, 0.5)"
+    );
+    let len = v.len();
+    if len == 0 {
+        return None;
+    }
+    let trim = (fraction * len as f64).floor() as usize;
+    if trim > 0 {
+        // partition off the smallest and then the largest values
+        select_impl(v, trim, &f64_lt);
+        select_impl(&mut v[trim..], len - 2 * trim - 1, &f64_lt);
+    }
+    let kept = &v[trim..len - trim];
+    Some(kept.iter().sum::<f64>() / kept.len() as f64)
+}
+
+/// Returns the interquartile range of `v`, the difference between its 75th
+/// and 25th percentiles, or `None` if `v` is empty.
+///
+/// Percentiles interpolate linearly between the two closest ranks, the same
+/// as the default method of most statistics packages. They are found with
+/// in-place selection rather than a sort, so this is `O(n)` on average, and
+/// **reorders the slice**. The result is unspecified if `v` contains NaN.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [7.0, 1.0, 3.0, 5.0, 9.0];
+/// assert_eq!(sortrs::interquartile_range(&mut v), Some(4.0));
+/// ```
+pub fn interquartile_range(v: &mut [f64]) -> Option<f64> {
+    if v.is_empty() {
+        return None;
+    }
+    let q1 = quantile_impl(v, 0.25);
+    let q3 = quantile_impl(v, 0.75);
+    Some(q3 - q1)
+}
+
+/// Returns the lower median of `v`, using `lt` to compare elements, or
+/// `None` if `v` is empty.
+///
+/// See `median` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [("b", 3), ("a", 9), ("c", 1), ("d", 4)];
+/// assert_eq!(sortrs::median_by(&mut v, |x, y| x.1 < y.1), Some(&("b", 3)));
+/// ```
+pub fn median_by<T, F>(v: &mut [T], lt: F) -> Option<&T>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if v.is_empty() {
+        return None;
+    }
+    let mid = (v.len() - 1) / 2;
+    select_impl(v, mid, &lt);
+    Some(&v[mid])
+}
+
+/// Returns the lower median of `v`, or `None` if `v` is empty.
+///
+/// This is equivalent to `median_by(v, |a, b| a.lt(b))`. The median is the
+/// element that would be at index `(v.len() - 1) / 2` if `v` were sorted, so
+/// for an even length it is the lower of the two middle elements. It is not
+/// interpolated, as the elements need not be numbers. It is found with
+/// in-place selection rather than a sort, so this is `O(n)` and **reorders
+/// the slice**.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [7, 1, 3, 5, 9, 2];
+/// assert_eq!(sortrs::median(&mut v), Some(&3));
+/// ```
+pub fn median<T: PartialOrd>(v: &mut [T]) -> Option<&T> {
+    median_by(v, |a, b| a.lt(b))
+}
+
+/// Moves the elements at each of the sorted `ranks` into place, splitting
+/// `v` at the middle rank and recursing into the two sides.
+fn select_ranks<T, F>(v: &mut [T], ranks: &[usize], offset: usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if ranks.is_empty() {
+        return;
+    }
+    let mid = ranks.len() / 2;
+    let n = ranks[mid] - offset;
+    select_impl(v, n, lt);
+    let (left, right) = v.split_at_mut(n);
+    select_ranks(left, &ranks[..mid], offset, lt);
+    select_ranks(&mut right[1..], &ranks[mid + 1..], offset + n + 1, lt);
+}
+
+/// Returns the quantiles of `v` at each of the probabilities `ps`, using `lt`
+/// to compare elements, or `None` if `v` is empty.
+///
+/// See `quantiles` for details.
+///
+/// # Panics
+///
+/// Panics if any of `ps` is not in the range `[0, 1]`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 1, 4, 2, 3];
+/// let q = sortrs::quantiles_by(&mut v, &[0.0, 0.5], |a, b| b.lt(a)).unwrap();
+/// assert!(q == [&5, &3]);
+/// ```
+pub fn quantiles_by<'a, T, F>(v: &'a mut [T], ps: &[f64], lt: F) -> Option<Vec<&'a T>>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(
+        ps.iter().all(|p| (0.0..=1.0).contains(p)),
+        "quantiles must be in [0, 1]"
+    );
+    if v.is_empty() {
+        return None;
+    }
+    let last = (v.len() - 1) as f64;
+    let positions = ps
+        .iter()
+        .map(|p| (p * last).floor() as usize)
+        .collect::<Vec<usize>>();
+    let mut ranks = positions.clone();
+    introsort_impl(&mut ranks, |a, b| a.lt(b));
+    ranks.dedup();
+    select_ranks(v, &ranks, 0, &lt);
+    let v = &*v;
+    Some(positions.into_iter().map(|i| &v[i]).collect())
+}
+
+/// Returns the quantiles of `v` at each of the probabilities `ps`, in the
+/// order given, or `None` if `v` is empty.
+///
+/// This is equivalent to `quantiles_by(v, ps, |a, b| a.lt(b))`. The
+/// quantile at `p` is the element that would be at index
+/// `floor(p * (v.len() - 1))` if `v` were sorted, the lower of the two
+/// closest ranks. So `0.0` is the least element, `1.0` the greatest and
+/// `0.5` the lower median of `median`. Every result is an element of `v`:
+/// nothing is interpolated, as the elements need not be numbers. Equal
+/// elements are interchangeable and which of them is returned is
+/// unspecified.
+///
+/// The requested ranks are found with in-place selection, splitting the
+/// slice at each in turn, rather than a sort. This is `O(n log q)` on
+/// average for `q` distinct ranks, and **reorders the slice**.
+///
+/// # Panics
+///
+/// Panics if any of `ps` is not in the range `[0, 1]`.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut latencies = (1..=100).rev().collect::<Vec<u32>>();
+/// let q = sortrs::quantiles(&mut latencies, &[0.5, 0.95, 0.99]).unwrap();
+/// assert!(q == [&50, &95, &99]);
+/// ```
+pub fn quantiles<'a, T: PartialOrd>(v: &'a mut [T], ps: &[f64]) -> Option<Vec<&'a T>> {
+    quantiles_by(v, ps, |a, b| a.lt(b))
+}
+
+/// Returns the least element of `iter` using `lt` to compare elements, or
+/// `None` if it is empty. If several elements are least the first is
+/// returned.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [(2, 'a'), (1, 'b'), (1, 'c')];
+/// assert_eq!(sortrs::min_by_lt(&v, |a, b| a.0 < b.0), Some(&(1, 'b')));
+/// ```
+pub fn min_by_lt<I, F>(iter: I, lt: F) -> Option<I::Item>
+where
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    iter.into_iter().fold(None, |min, x| match min {
+        Some(min) if !lt(&x, &min) => Some(min),
+        _ => Some(x),
+    })
+}
+
+/// Returns the greatest element of `iter` using `lt` to compare elements,
+/// or `None` if it is empty. If several elements are greatest the last is
+/// returned.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [(2, 'a'), (2, 'b'), (1, 'c')];
+/// assert_eq!(sortrs::max_by_lt(&v, |a, b| a.0 < b.0), Some(&(2, 'b')));
+/// ```
+pub fn max_by_lt<I, F>(iter: I, lt: F) -> Option<I::Item>
+where
+    I: IntoIterator,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    iter.into_iter().fold(None, |max, x| match max {
+        Some(max) if lt(&x, &max) => Some(max),
+        _ => Some(x),
+    })
+}
+
+/// Returns the least and greatest elements of `iter` in a single pass using
+/// `lt` to compare elements, or `None` if it is empty.
+///
+/// The result is the same as `min_by_lt` and `max_by_lt`, the first least
+/// and the last greatest element, but elements are taken in pairs and only
+/// the lesser of each pair is compared with the least so far and the
+/// greater with the greatest, `3n / 2` comparisons rather than `2n`. A
+/// single element is both the least and the greatest.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [3, 1, 4, 1, 5, 9, 2, 6];
+/// assert_eq!(sortrs::minmax_by_lt(&v, |a, b| a < b), Some((&1, &9)));
+/// ```
+pub fn minmax_by_lt<I, F>(iter: I, lt: F) -> Option<(I::Item, I::Item)>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    let mut iter = iter.into_iter();
+    let first = iter.next()?;
+    let (mut min, mut max) = (first.clone(), first);
+    loop {
+        let a = match iter.next() {
+            Some(a) => a,
+            None => return Some((min, max)),
+        };
+        let (small, large) = match iter.next() {
+            // equal elements keep their order, so the first can be the
+            // least and the second the greatest
+            Some(b) => {
+                if lt(&b, &a) {
+                    (b, a)
+                } else {
+                    (a, b)
+                }
+            }
+            None => {
+                if lt(&a, &min) {
+                    min = a;
+                } else if !lt(&a, &max) {
+                    max = a;
+                }
+                return Some((min, max));
+            }
+        };
+        if lt(&small, &min) {
+            min = small;
+        }
+        if !lt(&large, &max) {
+            max = large;
+        }
+    }
+}
+
+/// Reorders the slice to find its `k` least and `k` greatest elements, using
+/// `lt` to compare elements, and returns them, each in ascending order.
+///
+/// The least elements are moved to the front of the slice and the greatest
+/// to the back with in-place selection, then each end is sorted, which is
+/// `O(n + k log k)` on average. If the slice holds fewer than `2k` elements
+/// it is sorted and the two parts overlap, and if it holds fewer than `k`
+/// each part is the whole slice.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [5, 9, 1, 7, 3, 8, 2];
+/// let (least, greatest) = sortrs::extremes_k_by(&mut v, 2, |a, b| a < b);
+/// assert!(least == [1, 2]);
+/// assert!(greatest == [8, 9]);
+/// ```
+pub fn extremes_k_by<T, F>(v: &mut [T], k: usize, lt: F) -> (&[T], &[T])
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    let k = k.min(len);
+    if k == 0 {
+        return (&[], &[]);
+    }
+    if 2 * k >= len {
+        introsort_impl(v, &lt);
+    } else {
+        // the k least before index k, then the k greatest after the rest
+        select_impl(v, k, &lt);
+        select_impl(&mut v[k..], len - 2 * k - 1, &lt);
+        introsort_impl(&mut v[..k], &lt);
+        introsort_impl(&mut v[len - k..], &lt);
+    }
+    (&v[..k], &v[len - k..])
+}
+
+/// Reorders the slice to find its `k` least and `k` greatest elements, and
+/// returns them, each in ascending order.
+///
+/// This is equivalent to `extremes_k_by(v, k, |a, b| a.lt(b))`, see
+/// `extremes_k_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut latencies = [12, 250, 8, 15, 9, 900, 11];
+/// let (fastest, slowest) = sortrs::extremes_k(&mut latencies, 2);
+/// assert!(fastest == [8, 9]);
+/// assert!(slowest == [250, 900]);
+/// ```
+pub fn extremes_k<T: PartialOrd>(v: &mut [T], k: usize) -> (&[T], &[T]) {
+    extremes_k_by(v, k, |a, b| a.lt(b))
+}
+
+/// Returns the `k` greatest elements of `v`, using `lt` to compare elements,
+/// greatest first.
+///
+/// Pass a reversed comparator to get the `k` least elements, least first.
+/// `v` is not modified; see `top_k` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let scores = [("a", 7), ("b", 9), ("c", 2), ("d", 9)];
+/// let best = sortrs::top_k_by(&scores, 2, |x, y| x.1 < y.1);
+/// assert!(best == [("b", 9), ("d", 9)]);
+/// let worst = sortrs::top_k_by(&scores, 1, |x, y| y.1 < x.1);
+/// assert!(worst == [("c", 2)]);
+/// ```
+pub fn top_k_by<T, F>(v: &[T], k: usize, lt: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let k = k.min(v.len());
+    let mut top = v[..k].to_vec();
+    partial_sort_rest(&mut top, &v[k..], &|a: &T, b: &T| lt(b, a));
+    top
+}
+
+/// Returns the `k` greatest elements of `v`, greatest first.
+///
+/// This is equivalent to `top_k_by(v, k, |a, b| a.lt(b))`. A heap of the
+/// greatest `k` elements seen so far is kept in the returned `Vec`, so this
+/// is `O(n log k)`, leaves `v` untouched and clones only elements that enter
+/// the heap. If `v` holds fewer than `k` elements all of them are returned.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = [5, 1, 4, 2, 3];
+/// assert!(sortrs::top_k(&v, 3) == [5, 4, 3]);
+/// assert!(sortrs::top_k(&v, 10) == [5, 4, 3, 2, 1]);
+/// ```
+pub fn top_k<T: PartialOrd + Clone>(v: &[T], k: usize) -> Vec<T> {
+    top_k_by(v, k, |a, b| a.lt(b))
+}
+
+/// The median of a sliding window over a stream of values.
+///
+/// Values are pushed onto the back of the window and popped off the front.
+/// A sorted copy of the window is kept alongside the values in arrival order,
+/// so `median` is `O(1)` and `push` and `pop` are a binary search plus an
+/// `O(w)` shift for a window of `w` elements. The crate's heaps aren't used
+/// as `pop` has to remove an arbitrary value, which a heap can't do without
+/// a search of its own.
+///
+/// Each value is tagged with its arrival, so `pop` removes the copy of the
+/// value it returns even when values are incomparable, such as NaN. The
+/// median of such a window is only as meaningful as the order they were
+/// given.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut window = sortrs::SlidingMedian::new();
+/// for &x in &[5, 1, 4] {
+///     window.push(x);
+/// }
+/// assert_eq!(window.median(), Some(&4));
+/// window.pop();
+/// assert_eq!(window.median(), Some(&1));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SlidingMedian<T> {
+    window: VecDeque<T>,
+    // each value with the number of values pushed before it
+    sorted: Vec<(T, u64)>,
+    pushed: u64,
+}
+
+impl<T: PartialOrd + Clone> SlidingMedian<T> {
+    /// Creates an empty window.
+    pub fn new() -> SlidingMedian<T> {
+        SlidingMedian {
+            window: VecDeque::new(),
+            sorted: Vec::new(),
+            pushed: 0,
+        }
+    }
+
+    /// Creates an empty window with space for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> SlidingMedian<T> {
+        SlidingMedian {
+            window: VecDeque::with_capacity(capacity),
+            sorted: Vec::with_capacity(capacity),
+            pushed: 0,
+        }
+    }
+
+    /// Adds `x` to the back of the window.
+    pub fn push(&mut self, x: T) {
+        let lt = |a: &(T, u64), b: &(T, u64)| a.0.lt(&b.0);
+        let entry = (x.clone(), self.pushed);
+        let index = upper_bound(&self.sorted, &entry, &lt);
+        self.sorted.insert(index, entry);
+        self.window.push_back(x);
+        self.pushed += 1;
+    }
+
+    /// Removes and returns the oldest value in the window, or `None` if it is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let seq = self.pushed - self.window.len() as u64;
+        let entry = (self.window.pop_front()?, seq);
+        let lt = |a: &(T, u64), b: &(T, u64)| a.0.lt(&b.0);
+        // equal values are in arrival order, so the oldest is the first of
+        // them, unless the value is incomparable and the search can't find it
+        let index = lower_bound(&self.sorted, &entry, &lt);
+        let index = match self.sorted.get(index) {
+            Some(e) if e.1 == seq => index,
+            _ => self.sorted.iter().position(|e| e.1 == seq).unwrap(),
+        };
+        self.sorted.remove(index);
+        Some(entry.0)
+    }
+
+    /// Returns the median of the window, or `None` if it is empty.
+    ///
+    /// For an even number of values this is the lower of the two middle
+    /// values, see `upper_median` for the other.
+    pub fn median(&self) -> Option<&T> {
+        if self.sorted.is_empty() {
+            None
+        } else {
+            Some(&self.sorted[(self.sorted.len() - 1) / 2].0)
+        }
+    }
+
+    /// Returns the upper median of the window, or `None` if it is empty.
+    ///
+    /// This is the same as `median` for an odd number of values.
+    pub fn upper_median(&self) -> Option<&T> {
+        self.sorted.get(self.sorted.len() / 2).map(|e| &e.0)
+    }
+
+    /// Returns the number of values in the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Returns `true` if the window holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+}
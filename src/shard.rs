@@ -0,0 +1,352 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Splitting data into ordered ranges, the building blocks of a distributed
+//! sort.
+//!
+//! `sample_splitters` picks keys that divide data into ranges of similar
+//! size and `range_partition` groups a slice by those ranges. Each range can
+//! then be sorted on its own, on another machine even, and the sorted ranges
+//! concatenated or merged with `kmerge_dedup` or `merge`. `shard_for_key`
+//! finds the range of a single key, for routing lookups to the right shard.
+//! `equi_depth_splitters` picks exact splitters from data that is already
+//! sorted in shards, for rebalancing them.
+
+use super::introsort_impl;
+use super::search::{select_nth_from_sorted_by, upper_bound};
+
+/// Sampled elements per bucket, so that bucket sizes vary by only a few
+/// percent.
+const OVERSAMPLE: usize = 32;
+
+/// Picks `k - 1` splitters that divide the elements of `iter` into `k`
+/// ranges of roughly equal size, using `lt` to compare elements.
+///
+/// A fixed size sample of `32 * k` elements is drawn uniformly from `iter`
+/// with reservoir sampling, so the iterator is consumed once and only the
+/// sample is kept in memory. The splitters are then chosen from the sample
+/// with `splitters_from_sample_by`. The sampling is seeded deterministically, so the same
+/// input always gives the same splitters.
+///
+/// The splitters are sorted with respect to `lt`. If there are fewer than
+/// `k - 1` elements or many equal elements some splitters may be equal,
+/// which leaves the ranges between them empty.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let splitters = sortrs::sample_splitters_by((0..1000).rev(), 4, |a, b| b.lt(a));
+/// assert!(splitters.len() == 3);
+/// assert!(splitters[0] > splitters[1] && splitters[1] > splitters[2]);
+/// ```
+pub fn sample_splitters_by<I, F>(iter: I, k: usize, lt: F) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+    F: Fn(&I::Item, &I::Item) -> bool,
+{
+    assert!(k != 0, "there must be at least one bucket");
+    let capacity = k.saturating_mul(OVERSAMPLE);
+    let mut sample = Vec::new();
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    for (i, x) in iter.into_iter().enumerate() {
+        if i < capacity {
+            sample.push(x);
+            continue;
+        }
+        // keep the i-th element with probability capacity / (i + 1)
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = (state % (i as u64 + 1)) as usize;
+        if j < capacity {
+            sample[j] = x;
+        }
+    }
+    splitters_from_sample_by(&mut sample, k, lt)
+}
+
+/// Picks `k - 1` splitters that divide the elements of `iter` into `k`
+/// ranges of roughly equal size.
+///
+/// This is equivalent to `sample_splitters_by(iter, k, |a, b| a.lt(b))`,
+/// see `sample_splitters_by` for details.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let v = (0..10000u32).map(|x| x.wrapping_mul(2654435761) % 1000).collect::<Vec<_>>();
+/// let splitters = sortrs::sample_splitters(v.iter().cloned(), 4);
+/// assert!(splitters.len() == 3);
+/// assert!(splitters.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn sample_splitters<I>(iter: I, k: usize) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + Clone,
+{
+    sample_splitters_by(iter, k, |a, b| a.lt(b))
+}
+
+/// Sorts `sample`, using `lt` to compare elements, and picks `k - 1`
+/// evenly spaced elements of it as splitters for `k` ranges.
+///
+/// This is the second half of `sample_splitters_by`, for when the sample is
+/// gathered some other way, such as by combining samples taken on several
+/// machines. Every machine that picks splitters from the same sample gets
+/// the same splitters. An empty sample gives no splitters.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut sample = [7, 3, 9, 1, 5, 11, 13, 15];
+/// let splitters = sortrs::splitters_from_sample_by(&mut sample, 4, |a, b| b.lt(a));
+/// assert!(splitters == [11, 7, 3]);
+/// ```
+pub fn splitters_from_sample_by<T, F>(sample: &mut [T], k: usize, lt: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(k != 0, "there must be at least one bucket");
+    if sample.is_empty() {
+        return Vec::new();
+    }
+    introsort_impl(sample, &lt);
+    let len = sample.len();
+    (1..k).map(|i| sample[i * len / k].clone()).collect()
+}
+
+/// Sorts `sample` and picks `k - 1` evenly spaced elements of it as
+/// splitters for `k` ranges.
+///
+/// This is equivalent to `splitters_from_sample_by(sample, k, |a, b|
+/// a.lt(b))`, see `splitters_from_sample_by` for details.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// // samples gathered from two machines
+/// let mut sample = vec![40, 10, 70, 20];
+/// sample.extend_from_slice(&[50, 80, 30, 60]);
+/// assert!(sortrs::splitters_from_sample(&mut sample, 2) == [50]);
+/// ```
+pub fn splitters_from_sample<T: PartialOrd + Clone>(sample: &mut [T], k: usize) -> Vec<T> {
+    splitters_from_sample_by(sample, k, |a, b| a.lt(b))
+}
+
+/// Picks `k - 1` splitters that divide the elements of the sorted slices in
+/// `parts`, taken together, into `k` ranges of equal size, using `lt` to
+/// compare elements.
+///
+/// Each part must be sorted by `lt` in ascending order, as the shards of
+/// range-partitioned data usually are. Unlike `sample_splitters_by` the
+/// splitters are exact: splitter `i` is the element with `i * n / k`
+/// elements before it in the combined order, found with
+/// `select_nth_from_sorted_by` without merging the parts. So with distinct
+/// elements range `i` of `range_partition_by` holds exactly the elements
+/// from `i * n / k` up to `(i + 1) * n / k`. Many equal elements can leave
+/// some splitters equal and the ranges between them empty. No parts, or
+/// only empty ones, give no splitters.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// let a = [12, 9, 5, 1];
+/// let b = [11, 10, 8, 2];
+/// let parts: [&[i32]; 2] = [&a, &b];
+/// let splitters = sortrs::equi_depth_splitters_by(&parts, 4, |x, y| y.lt(x));
+/// assert!(splitters == [10, 8, 2]);
+/// ```
+pub fn equi_depth_splitters_by<T, F>(parts: &[&[T]], k: usize, lt: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(k != 0, "there must be at least one bucket");
+    let len: usize = parts.iter().map(|p| p.len()).sum();
+    if len == 0 {
+        return Vec::new();
+    }
+    (1..k)
+        .map(|i| {
+            select_nth_from_sorted_by(parts, i * len / k, &lt)
+                .unwrap()
+                .clone()
+        })
+        .collect()
+}
+
+/// Picks `k - 1` splitters that divide the elements of the sorted slices in
+/// `parts`, taken together, into `k` ranges of equal size.
+///
+/// This is equivalent to `equi_depth_splitters_by(parts, k, |a, b|
+/// a.lt(b))`, see `equi_depth_splitters_by` for details.
+///
+/// # Panics
+///
+/// Panics if `k` is 0.
+///
+/// # Examples
+///
+/// ```rust
+/// // three shards, sorted on their own machines
+/// let shards = vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8], vec![9, 10, 11, 12]];
+/// let parts = shards.iter().map(|s| &s[..]).collect::<Vec<&[i32]>>();
+/// assert!(sortrs::equi_depth_splitters(&parts, 3) == [5, 9]);
+/// ```
+pub fn equi_depth_splitters<T: PartialOrd + Clone>(parts: &[&[T]], k: usize) -> Vec<T> {
+    equi_depth_splitters_by(parts, k, |a, b| a.lt(b))
+}
+
+/// Returns the index of the range of `splitters` that `key` falls in, using
+/// `lt` to compare elements.
+///
+/// The ranges are those of `range_partition_by`: range 0 is below
+/// `splitters[0]` and elements equal to a splitter fall in the range above
+/// it, so data partitioned with `range_partition_by` and later looked up
+/// with `shard_for_key_by` always agree on where each key lives. The range
+/// is found with a binary search, in `O(log k)` comparisons.
+///
+/// # Examples
+///
+/// ```rust
+/// let splitters = [30, 20, 10];
+/// let shard = |key| sortrs::shard_for_key_by(&key, &splitters, |a, b| b.lt(a));
+/// assert!(shard(35) == 0 && shard(30) == 1 && shard(15) == 2 && shard(0) == 3);
+/// ```
+pub fn shard_for_key_by<T, F>(key: &T, splitters: &[T], lt: F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    upper_bound(splitters, key, &lt)
+}
+
+/// Returns the index of the range of `splitters` that `key` falls in.
+///
+/// This is equivalent to `shard_for_key_by(key, splitters, |a, b|
+/// a.lt(b))`, see `shard_for_key_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = (0..1000u32).map(|x| x.wrapping_mul(2654435761) % 1000).collect::<Vec<_>>();
+/// let splitters = sortrs::sample_splitters(v.iter().cloned(), 4);
+/// let bounds = sortrs::range_partition(&mut v, &splitters);
+/// for shard in 0..4 {
+///     for key in &v[bounds[shard]..bounds[shard + 1]] {
+///         assert_eq!(sortrs::shard_for_key(key, &splitters), shard);
+///     }
+/// }
+/// ```
+pub fn shard_for_key<T: PartialOrd>(key: &T, splitters: &[T]) -> usize {
+    shard_for_key_by(key, splitters, |a, b| a.lt(b))
+}
+
+/// Groups the elements of `v` by the range of `splitters` they fall in,
+/// using `lt` to compare elements, and returns the boundaries of the groups.
+///
+/// `splitters` must be sorted with respect to `lt`. The `splitters.len() +
+/// 1` ranges are those below `splitters[0]`, between each pair of adjacent
+/// splitters and from the last splitter up, with elements equal to a
+/// splitter in the range above it. The returned vector has one more entry
+/// than there are ranges: range `i` is `v[b[i]..b[i + 1]]`, `b[0]` is 0 and
+/// the last entry is `v.len()`.
+///
+/// Each element's range is found with a binary search over the splitters
+/// and elements are then swapped into place, so this is `O(n log k)` time
+/// with `O(n)` extra space for the range indices. The elements within a
+/// range are left in no particular order. If `v` is already sorted nothing
+/// is moved.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [9, 1, 5, 3, 7, 2];
+/// let b = sortrs::range_partition_by(&mut v, &[8, 4], |a, b| b.lt(a));
+/// assert!(b == [0, 1, 3, 6]);
+/// assert!(v[b[0]..b[1]] == [9]);
+/// ```
+pub fn range_partition_by<T, F>(v: &mut [T], splitters: &[T], lt: F) -> Vec<usize>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let mut ranges = v
+        .iter()
+        .map(|x| upper_bound(splitters, x, &lt))
+        .collect::<Vec<usize>>();
+    // starts[r] is the index range r begins at, with v.len() at the end
+    let mut starts = vec![0; splitters.len() + 2];
+    for &r in &ranges {
+        starts[r + 1] += 1;
+    }
+    for i in 1..starts.len() {
+        starts[i] += starts[i - 1];
+    }
+
+    // swap each element into the next unfilled slot of its range until every
+    // slot of every range holds one of its own elements
+    let mut next = starts.clone();
+    for r in 0..splitters.len() + 1 {
+        while next[r] < starts[r + 1] {
+            let i = next[r];
+            let target = ranges[i];
+            if target != r {
+                let j = next[target];
+                v.swap(i, j);
+                ranges.swap(i, j);
+                next[target] += 1;
+            } else {
+                next[r] += 1;
+            }
+        }
+    }
+    starts
+}
+
+/// Groups the elements of `v` by the range of `splitters` they fall in and
+/// returns the boundaries of the groups.
+///
+/// This is equivalent to `range_partition_by(v, splitters, |a, b|
+/// a.lt(b))`, see `range_partition_by` for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = (0..100u32).rev().collect::<Vec<u32>>();
+/// let splitters = sortrs::sample_splitters(v.iter().cloned(), 4);
+/// let b = sortrs::range_partition(&mut v, &splitters);
+/// assert!(b.len() == 5 && b[0] == 0 && b[4] == 100);
+/// for (i, w) in b.windows(2).enumerate() {
+///     // every element of a range is below the next range's splitter
+///     assert!(v[w[0]..w[1]].iter().all(|x| i == 3 || *x < splitters[i]));
+/// }
+/// ```
+pub fn range_partition<T: PartialOrd>(v: &mut [T], splitters: &[T]) -> Vec<usize> {
+    range_partition_by(v, splitters, |a, b| a.lt(b))
+}
@@ -0,0 +1,196 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Bulk maintenance of sorted `Vec`s.
+
+use std::ptr;
+
+use super::introsort_impl;
+
+/// Removes every element of the sorted `vec` that is equal to an element of
+/// the sorted `to_remove_sorted`, returning the number of elements removed.
+///
+/// Both inputs must be sorted in ascending order. All occurrences of a
+/// matching value are removed and the remaining elements keep their order.
+/// This is a single `O(n + m)` pass rather than one `remove` per item.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = vec![1, 2, 2, 3, 5, 8];
+/// assert_eq!(sortrs::remove_sorted_items(&mut v, &[2, 5, 7]), 3);
+/// assert!(v == [1, 3, 8]);
+/// ```
+pub fn remove_sorted_items<T: PartialOrd>(vec: &mut Vec<T>, to_remove_sorted: &[T]) -> usize {
+    let len = vec.len();
+    let mut j = 0;
+    vec.retain(|x| {
+        // skip removal candidates smaller than the current element
+        while j < to_remove_sorted.len() && to_remove_sorted[j].lt(x) {
+            j += 1;
+        }
+        !(j < to_remove_sorted.len() && to_remove_sorted[j] == *x)
+    });
+    len - vec.len()
+}
+
+/// Tracks the gap between the initialized prefix of a `Vec` and its
+/// initialized tail, either the unmerged prefix and merged tail of a
+/// backward merge or the kept prefix and unvisited tail of a compaction. On
+/// drop the tail is shifted down to close the gap so the `Vec` is left fully
+/// initialized, even if a comparison, clone or predicate panics part way
+/// through.
+struct MergeHole<'a, T: 'a> {
+    vec: &'a mut Vec<T>,
+    // [0, old) is initialized
+    old: usize,
+    // [dest, end) is initialized
+    dest: usize,
+    end: usize,
+}
+
+impl<'a, T> Drop for MergeHole<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let ptr = self.vec.as_mut_ptr();
+            let tail = self.end - self.dest;
+            ptr::copy(ptr.add(self.dest), ptr.add(self.old), tail);
+            self.vec.set_len(self.old + tail);
+        }
+    }
+}
+
+/// Sorts `new_items` and merges them into the sorted `vec`.
+///
+/// The merge is done in a single backward pass directly into `vec`'s spare
+/// capacity, so apart from growing `vec` no memory is allocated. New items
+/// are placed after any existing elements that compare equal to them.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = vec![1, 4, 9];
+/// sortrs::merge_insert(&mut v, &mut [8, 0, 4]);
+/// assert!(v == [0, 1, 4, 4, 8, 9]);
+/// ```
+pub fn merge_insert<T: PartialOrd + Clone>(vec: &mut Vec<T>, new_items: &mut [T]) {
+    introsort_impl(new_items, |a, b| a.lt(b));
+
+    let old = vec.len();
+    let end = old + new_items.len();
+    vec.reserve(new_items.len());
+    let mut hole = MergeHole {
+        vec,
+        old,
+        dest: end,
+        end,
+    };
+
+    // fill the merged tail from the back with whichever input is largest
+    let mut j = new_items.len();
+    while j > 0 {
+        let ptr = hole.vec.as_mut_ptr();
+        unsafe {
+            if hole.old > 0 && new_items[j - 1].lt(&*ptr.add(hole.old - 1)) {
+                hole.old -= 1;
+                hole.dest -= 1;
+                ptr::copy_nonoverlapping(ptr.add(hole.old), ptr.add(hole.dest), 1);
+            } else {
+                let x = new_items[j - 1].clone();
+                hole.dest -= 1;
+                ptr::write(ptr.add(hole.dest), x);
+                j -= 1;
+            }
+        }
+    }
+    // dropping the hole sets the final length
+}
+
+/// Removes all but the first of each run of consecutive elements in the
+/// sorted `vec` that have equal keys, returning the number of elements
+/// removed.
+///
+/// The first occurrence of each key is always the one kept, and the kept
+/// elements stay in their original order, so a `vec` that was stably sorted
+/// by key keeps the earliest inserted element for every key. `key` is called
+/// at least once per element. This is a single `O(n)` pass.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = vec![(1, 'a'), (1, 'b'), (2, 'c'), (3, 'd'), (3, 'e')];
+/// assert_eq!(sortrs::dedup_sorted_by_key(&mut v, |x| x.0), 2);
+/// assert!(v == [(1, 'a'), (2, 'c'), (3, 'd')]);
+/// ```
+pub fn dedup_sorted_by_key<T, K, F>(vec: &mut Vec<T>, mut key: F) -> usize
+where
+    K: PartialEq,
+    F: FnMut(&T) -> K,
+{
+    let len = vec.len();
+    // dedup_by passes the later element first and keeps the earlier one
+    vec.dedup_by(|later, kept| key(later) == key(kept));
+    len - vec.len()
+}
+
+/// Removes the elements of the sorted `vec` for which `pred` returns true
+/// and returns them, in their original order.
+///
+/// Both the retained and the extracted elements keep their relative order,
+/// so if `vec` was sorted both are still sorted and a stably sorted `vec`
+/// stays stably sorted. `pred` is called exactly once per element, in
+/// order, and each element is moved at most once, so this is a single
+/// `O(n)` pass rather than one `remove` per match.
+///
+/// # Examples
+///
+/// ```rust
+/// // a queue of (deadline, job) sorted by deadline
+/// let mut queue = vec![(1, "a"), (2, "cancelled"), (3, "b"), (3, "cancelled"), (5, "c")];
+/// let cancelled = sortrs::extract_if_sorted(&mut queue, |x| x.1 == "cancelled");
+/// assert!(queue == [(1, "a"), (3, "b"), (5, "c")]);
+/// assert!(cancelled == [(2, "cancelled"), (3, "cancelled")]);
+/// ```
+pub fn extract_if_sorted<T, F>(vec: &mut Vec<T>, mut pred: F) -> Vec<T>
+where
+    F: FnMut(&T) -> bool,
+{
+    let len = vec.len();
+    let mut extracted = Vec::new();
+    // nothing is visible through vec until the hole is dropped
+    unsafe {
+        vec.set_len(0);
+    }
+    let mut hole = MergeHole {
+        vec,
+        old: 0,
+        dest: 0,
+        end: len,
+    };
+
+    // move each kept element down to the end of the kept prefix
+    while hole.dest < hole.end {
+        let ptr = hole.vec.as_mut_ptr();
+        unsafe {
+            let x = ptr.add(hole.dest);
+            if pred(&*x) {
+                hole.dest += 1;
+                extracted.push(ptr::read(x));
+            } else {
+                if hole.old != hole.dest {
+                    ptr::copy_nonoverlapping(x, ptr.add(hole.old), 1);
+                }
+                hole.old += 1;
+                hole.dest += 1;
+            }
+        }
+    }
+    // dropping the hole sets the final length
+    drop(hole);
+    extracted
+}
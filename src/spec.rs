@@ -0,0 +1,143 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorting records by several keys.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use super::{sort_unstable_by, timsort_by, Compare};
+
+/// A sort order over several keys, each ascending or descending, built up
+/// one key at a time.
+///
+/// Elements are compared by the first key, ties by the second and so on.
+/// Each key added wraps the comparator built so far in a new closure type,
+/// so the finished spec is a single comparator the compiler can inline, with
+/// no boxing or dispatch per key. Keys that are incomparable, such as NaN,
+/// count as equal and fall through to the next key.
+///
+/// `apply` is stable, so elements equal in every key keep their input order.
+/// A spec also implements `Compare`, for the `_with` sorts.
+///
+/// The key closures need the element type, so it is usually named up front
+/// as in `SortSpec::<Row>::new()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use sortrs::SortSpec;
+///
+/// struct Row {
+///     last_name: String,
+///     age: u32,
+/// }
+///
+/// let mut rows = vec![
+///     Row { last_name: "Smith".to_string(), age: 30 },
+///     Row { last_name: "Jones".to_string(), age: 25 },
+///     Row { last_name: "Smith".to_string(), age: 41 },
+/// ];
+/// SortSpec::<Row>::new()
+///     .asc_by_key(|r| r.last_name.clone())
+///     .desc_by_key(|r| r.age)
+///     .apply(&mut rows);
+/// let ages = rows.iter().map(|r| r.age).collect::<Vec<u32>>();
+/// assert!(ages == [25, 41, 30]);
+/// ```
+pub struct SortSpec<T, C = fn(&T, &T) -> Ordering> {
+    cmp: C,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<T> SortSpec<T> {
+    /// Returns a spec with no keys, under which all elements are equal.
+    pub fn new() -> SortSpec<T> {
+        SortSpec {
+            cmp: |_, _| Ordering::Equal,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for SortSpec<T> {
+    fn default() -> SortSpec<T> {
+        SortSpec::new()
+    }
+}
+
+impl<T, C> SortSpec<T, C>
+where
+    C: Fn(&T, &T) -> Ordering,
+{
+    /// Adds a key that orders elements in ascending order of `key`, for
+    /// elements equal in the keys added before it.
+    pub fn asc_by_key<K, F>(self, key: F) -> SortSpec<T, impl Fn(&T, &T) -> Ordering>
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        let cmp = self.cmp;
+        SortSpec {
+            cmp: move |a: &T, b: &T| cmp(a, b).then_with(|| key_order(&key(a), &key(b))),
+            marker: PhantomData,
+        }
+    }
+
+    /// Adds a key that orders elements in descending order of `key`, for
+    /// elements equal in the keys added before it.
+    pub fn desc_by_key<K, F>(self, key: F) -> SortSpec<T, impl Fn(&T, &T) -> Ordering>
+    where
+        K: PartialOrd,
+        F: Fn(&T) -> K,
+    {
+        let cmp = self.cmp;
+        SortSpec {
+            cmp: move |a: &T, b: &T| cmp(a, b).then_with(|| key_order(&key(b), &key(a))),
+            marker: PhantomData,
+        }
+    }
+
+    /// Compares `a` and `b` by each key in turn.
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.cmp)(a, b)
+    }
+
+    /// Sorts the slice, in place, by the spec with `timsort_by`, keeping
+    /// elements that are equal in every key in their input order.
+    pub fn apply(&self, v: &mut [T]) {
+        timsort_by(v, |a, b| self.compare(a, b) == Ordering::Less);
+    }
+
+    /// Sorts the slice, in place, by the spec with `introsort_by`, without
+    /// preserving the order of elements that are equal in every key.
+    pub fn apply_unstable(&self, v: &mut [T]) {
+        sort_unstable_by(v, |a, b| self.compare(a, b) == Ordering::Less);
+    }
+}
+
+impl<T, C> Compare<T> for SortSpec<T, C>
+where
+    C: Fn(&T, &T) -> Ordering,
+{
+    #[inline]
+    fn lt(&self, a: &T, b: &T) -> bool {
+        self.compare(a, b) == Ordering::Less
+    }
+}
+
+/// Orders two keys, counting incomparable keys as equal.
+fn key_order<K: PartialOrd>(a: &K, b: &K) -> Ordering {
+    if a.lt(b) {
+        Ordering::Less
+    } else if b.lt(a) {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorting one channel of interleaved data.
+
+use super::record_sorted;
+
+/// Repair the heap of channel elements whose root is the `root`th element of
+/// the channel, where the heap holds the first `end` elements.
+fn shift_down_strided<T, F>(
+    v: &mut [T],
+    stride: usize,
+    channel: usize,
+    root: usize,
+    end: usize,
+    lt: &F,
+) where
+    F: Fn(&T, &T) -> bool,
+{
+    let at = |i: usize| channel + i * stride;
+    let mut root = root;
+    loop {
+        let left_child = 2 * root + 1;
+        if left_child >= end {
+            return;
+        }
+        let mut swap = root;
+        if lt(&v[at(swap)], &v[at(left_child)]) {
+            swap = left_child;
+        }
+        let right_child = left_child + 1;
+        if right_child < end && lt(&v[at(swap)], &v[at(right_child)]) {
+            swap = right_child;
+        }
+        if swap == root {
+            return;
+        }
+        v.swap(at(root), at(swap));
+        root = swap;
+    }
+}
+
+/// Sorts the elements `v[channel]`, `v[channel + stride]`,
+/// `v[channel + 2 * stride]`, ... in place, using `lt` to compare elements,
+/// leaving the rest of the slice alone.
+///
+/// See `sort_strided` for details.
+///
+/// # Panics
+///
+/// Panics if `stride` is 0 or `channel` is not less than `stride`.
+///
+/// # Examples
+///
+/// ```rust
+/// // (reading, sensor) pairs from two sensors, interleaved
+/// let mut v = [(3, 'a'), (9, 'b'), (1, 'a'), (8, 'b'), (2, 'a'), (7, 'b')];
+/// sortrs::sort_strided_by(&mut v, 2, 1, |x, y| x.0 < y.0);
+/// assert!(v == [(3, 'a'), (7, 'b'), (1, 'a'), (8, 'b'), (2, 'a'), (9, 'b')]);
+/// ```
+pub fn sort_strided_by<T, F>(v: &mut [T], stride: usize, channel: usize, lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    assert!(stride != 0, "stride must be at least 1");
+    assert!(
+        channel < stride,
+        "channel {} is not less than stride {}",
+        channel,
+        stride
+    );
+    // the number of elements in the channel
+    if channel >= v.len() {
+        return;
+    }
+    let len = (v.len() - channel - 1) / stride + 1;
+    record_sorted(len);
+    // a heapsort over the channel's elements, which needs no buffer
+    for start in (0..len / 2).rev() {
+        shift_down_strided(v, stride, channel, start, len, &lt);
+    }
+    for end in (1..len).rev() {
+        // move the largest to the end and restore the heap before it
+        v.swap(channel, channel + end * stride);
+        shift_down_strided(v, stride, channel, 0, end, &lt);
+    }
+}
+
+/// Sorts every `stride`th element of the slice, starting at `channel`, in
+/// place.
+///
+/// This is equivalent to `sort_strided_by(v, stride, channel, |a, b|
+/// a.lt(b))`. Interleaved data, such as the samples of a multichannel audio
+/// buffer, holds channel `c` at indices `c`, `c + stride`, `c + 2 * stride`
+/// and so on. This sorts that channel without copying it out and back: it is
+/// a heapsort over the channel's elements, so it is `O(n log n)` in the
+/// length of the channel, allocates nothing and is not stable. The other
+/// channels are not touched.
+///
+/// # Panics
+///
+/// Panics if `stride` is 0 or `channel` is not less than `stride`.
+///
+/// # Examples
+///
+/// ```rust
+/// // left and right samples of a stereo buffer
+/// let mut v = [4, 40, 2, 20, 3, 30, 1, 10];
+/// sortrs::sort_strided(&mut v, 2, 1);
+/// assert!(v == [4, 10, 2, 20, 3, 30, 1, 40]);
+/// ```
+pub fn sort_strided<T: PartialOrd>(v: &mut [T], stride: usize, channel: usize) {
+    sort_strided_by(v, stride, channel, |a, b| a.lt(b));
+}
@@ -0,0 +1,196 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorts specialized for strings.
+
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{apply_permutation, introsort_impl, record_sorted};
+
+/// A string's position in the key arena, with its first bytes inline so most
+/// comparisons don't touch the arena at all.
+struct ArenaKey {
+    // the first 8 bytes, big endian and zero padded, so integer order is byte
+    // order
+    prefix: u64,
+    start: usize,
+    end: usize,
+    index: usize,
+}
+
+/// Builds a key for every string, writing the bytes of each string, as
+/// transformed by `push`, into one contiguous arena.
+fn arena_keys<'a, I, P>(strings: I, arena: &mut Vec<u8>, push: P) -> Vec<ArenaKey>
+where
+    I: Iterator<Item = &'a str>,
+    P: Fn(&str, &mut Vec<u8>),
+{
+    strings
+        .enumerate()
+        .map(|(index, s)| {
+            let start = arena.len();
+            push(s, arena);
+            let bytes = &arena[start..];
+            let mut prefix = [0u8; 8];
+            let n = bytes.len().min(8);
+            prefix[..n].copy_from_slice(&bytes[..n]);
+            ArenaKey {
+                prefix: u64::from_be_bytes(prefix),
+                start,
+                end: arena.len(),
+                index,
+            }
+        })
+        .collect()
+}
+
+/// Sorts `keys` by their bytes in `arena`, breaking ties between equal keys
+/// with `tie_lt` on their indices, and returns the permutation that applies
+/// the order.
+fn sort_arena_keys<F>(mut keys: Vec<ArenaKey>, arena: &[u8], tie_lt: F) -> Vec<usize>
+where
+    F: Fn(usize, usize) -> bool,
+{
+    introsort_impl(&mut keys, |a, b| {
+        // equal prefixes can still differ, including by zero padding
+        if a.prefix != b.prefix {
+            return a.prefix < b.prefix;
+        }
+        let (x, y) = (&arena[a.start..a.end], &arena[b.start..b.end]);
+        x < y || (x == y && tie_lt(a.index, b.index))
+    });
+    keys.into_iter().map(|key| key.index).collect()
+}
+
+/// Appends the case folded UTF-8 of `s` to `out`. ASCII is folded a byte at
+/// a time, anything else a `char` at a time with `char::to_lowercase`.
+fn push_folded(s: &str, out: &mut Vec<u8>) {
+    let bytes = s.as_bytes();
+    let ascii = bytes.iter().take_while(|b| b.is_ascii()).count();
+    out.extend(bytes[..ascii].iter().map(|b| b.to_ascii_lowercase()));
+    let mut buf = [0u8; 4];
+    for c in s[ascii..].chars().flat_map(char::to_lowercase) {
+        out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    }
+}
+
+/// Sorts the strings, in place, in byte order, which is the same as the `Ord`
+/// order of `str`.
+///
+/// Comparing `String`s directly chases a pointer to a separate heap
+/// allocation for every comparison. Instead the bytes of every string are
+/// copied into one contiguous arena, and small keys holding each string's
+/// arena offsets and first 8 bytes are sorted. Most comparisons are settled
+/// by the inline prefix alone. The slice is then permuted once, moving each
+/// string exactly once. This allocates the arena, the size of all the
+/// strings together, plus a key per string.
+///
+/// Equal strings are indistinguishable, so the sort being unstable doesn't
+/// matter.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = vec!["pear".to_string(), "apple".to_string(), "fig".to_string()];
+/// sortrs::sort_strings(&mut v);
+/// assert!(v == ["apple", "fig", "pear"]);
+/// ```
+pub fn sort_strings<S: AsRef<str>>(v: &mut [S]) {
+    record_sorted(v.len());
+    if v.len() < 2 {
+        return;
+    }
+    let mut arena = Vec::with_capacity(v.iter().map(|s| s.as_ref().len()).sum());
+    let keys = arena_keys(v.iter().map(|s| s.as_ref()), &mut arena, |s, arena| {
+        arena.extend_from_slice(s.as_bytes())
+    });
+    let perm = sort_arena_keys(keys, &arena, |_, _| false);
+    apply_permutation(v, &perm);
+}
+
+/// Sorts the strings, in place, ignoring case.
+///
+/// Strings are compared by their lowercase form, and strings that differ
+/// only in case are ordered by their bytes so the order is total and the
+/// result doesn't depend on the input order. ASCII is folded a byte at a
+/// time. Other characters are folded with `char::to_lowercase`, the Unicode
+/// lowercase mapping, which agrees with Unicode simple case folding for
+/// nearly all characters. Locale specific rules, such as Turkish dotted and
+/// dotless i, are not applied.
+///
+/// The folded strings are built once into an arena, as in `sort_strings`,
+/// rather than being folded again on every comparison.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = vec!["banana", "Apple", "apple", "Éclair", "cherry"];
+/// sortrs::sort_strings_ci(&mut v);
+/// assert!(v == ["Apple", "apple", "banana", "cherry", "Éclair"]);
+/// ```
+pub fn sort_strings_ci<S: AsRef<str>>(v: &mut [S]) {
+    record_sorted(v.len());
+    if v.len() < 2 {
+        return;
+    }
+    let mut arena = Vec::with_capacity(v.iter().map(|s| s.as_ref().len()).sum());
+    let keys = arena_keys(v.iter().map(|s| s.as_ref()), &mut arena, push_folded);
+    let perm = {
+        let v = &*v;
+        sort_arena_keys(keys, &arena, |a, b| v[a].as_ref() < v[b].as_ref())
+    };
+    apply_permutation(v, &perm);
+}
+
+/// Returns the length of `s` in bytes, for use as a sort key.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = ["ccc", "é", "bb"];
+/// sortrs::sort_by(&mut v, |a, b| sortrs::byte_len(a) < sortrs::byte_len(b));
+/// assert!(v == ["é", "bb", "ccc"]);
+/// ```
+pub fn byte_len(s: &str) -> usize {
+    s.len()
+}
+
+/// Returns the number of `char`s, or Unicode scalar values, in `s`, for use
+/// as a sort key.
+///
+/// # Examples
+///
+/// ```rust
+/// // shortest first, then alphabetically
+/// let mut v = ["pear", "fig", "kiwi", "éa"];
+/// sortrs::sort_by(&mut v, |a, b| {
+///     (sortrs::char_count(a), a) < (sortrs::char_count(b), b)
+/// });
+/// assert!(v == ["éa", "fig", "kiwi", "pear"]);
+/// ```
+pub fn char_count(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Returns the number of extended grapheme clusters, or user perceived
+/// characters, in `s`, for use as a sort key.
+///
+/// Requires the `unicode-segmentation` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// // "e" followed by a combining acute accent is one grapheme
+/// assert_eq!(sortrs::grapheme_count("e\u{301}te\u{301}"), 3);
+/// assert_eq!(sortrs::char_count("e\u{301}te\u{301}"), 5);
+/// ```
+#[cfg(feature = "unicode-segmentation")]
+pub fn grapheme_count(s: &str) -> usize {
+    s.graphemes(true).count()
+}
@@ -0,0 +1,575 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Timsort, a stable adaptive merge sort.
+
+#[cfg(feature = "pool")]
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::slice;
+
+#[cfg(feature = "pool")]
+use super::pool::Scratch;
+use super::{ptr_distance, record_sorted};
+
+/// Slices shorter than this are sorted with binary insertion sort alone.
+const MIN_MERGE: usize = 64;
+
+/// Consecutive wins by one run after which a merge starts galloping.
+const MIN_GALLOP: usize = 7;
+
+/// Scratch space for the shorter run of a merge, drawn from the thread's
+/// pool with the `pool` feature.
+struct MergeBuf<T> {
+    #[cfg(not(feature = "pool"))]
+    vec: Vec<T>,
+    #[cfg(feature = "pool")]
+    scratch: Scratch,
+    #[cfg(feature = "pool")]
+    marker: PhantomData<T>,
+}
+
+impl<T> MergeBuf<T> {
+    #[cfg(not(feature = "pool"))]
+    fn new() -> MergeBuf<T> {
+        MergeBuf { vec: Vec::new() }
+    }
+
+    #[cfg(feature = "pool")]
+    fn new() -> MergeBuf<T> {
+        MergeBuf {
+            scratch: Scratch::take(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a pointer to room for at least `len` elements.
+    #[cfg(not(feature = "pool"))]
+    fn reserve(&mut self, len: usize) -> *mut T {
+        if self.vec.capacity() < len {
+            self.vec.reserve(len);
+        }
+        self.vec.as_mut_ptr()
+    }
+
+    /// Returns a pointer to room for at least `len` elements.
+    #[cfg(feature = "pool")]
+    fn reserve(&mut self, len: usize) -> *mut T {
+        self.scratch.reserve(len)
+    }
+}
+
+/// With the `tiled-merge` feature, merges of more than this many bytes are
+/// split into merges of at most this size, so the runs and the buffer for
+/// the shorter of them fit in a 256 KiB L2 cache.
+#[cfg(feature = "tiled-merge")]
+const TILE_BYTES: usize = 128 * 1024;
+
+/// With the `tiled-merge` feature on x86_64, copies from the merge buffer
+/// of at least this many bytes use non-temporal stores.
+#[cfg(all(feature = "tiled-merge", target_arch = "x86_64"))]
+const STREAM_BYTES: usize = 1024;
+
+/// A sorted run of the slice being sorted.
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+/// Returns the minimum run length for a slice of `len` elements, chosen so
+/// that `len / min_run` is a power of two or just below one, which keeps the
+/// final merges balanced.
+fn min_run_length(mut len: usize) -> usize {
+    let mut odd = 0;
+    while len >= MIN_MERGE {
+        odd |= len & 1;
+        len >>= 1;
+    }
+    len + odd
+}
+
+/// Returns the length of the run at the start of `v`, reversing it in place
+/// first if it is strictly descending. Only strictly descending runs are
+/// reversed so equal elements never change order.
+fn count_run<T, F>(v: &mut [T], lt: &F) -> usize
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    if len < 2 {
+        return len;
+    }
+    let mut end = 2;
+    if lt(&v[1], &v[0]) {
+        while end < len && lt(&v[end], &v[end - 1]) {
+            end += 1;
+        }
+        v[..end].reverse();
+    } else {
+        while end < len && !lt(&v[end], &v[end - 1]) {
+            end += 1;
+        }
+    }
+    end
+}
+
+/// Sorts `v` by inserting each element after the sorted prefix of length
+/// `sorted` at the position found by binary search, after any equal
+/// elements.
+fn binary_insertsort<T, F>(v: &mut [T], sorted: usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    for i in sorted.max(1)..v.len() {
+        let pos = {
+            let (before, rest) = v.split_at(i);
+            before.partition_point(|x| !lt(&rest[0], x))
+        };
+        v[pos..i + 1].rotate_right(1);
+    }
+}
+
+/// Returns the partition point of `v` for `pred`, which must hold for a
+/// prefix of `v`, probing exponentially further from the front before
+/// binary searching the last step.
+fn gallop_front<T, P>(v: &[T], pred: P) -> usize
+where
+    P: Fn(&T) -> bool,
+{
+    let mut bound = 1;
+    while bound <= v.len() && pred(&v[bound - 1]) {
+        bound *= 2;
+    }
+    let start = bound / 2;
+    let end = bound.min(v.len());
+    start + v[start..end].partition_point(pred)
+}
+
+/// Returns the partition point of `v` for `pred`, which must hold for a
+/// prefix of `v`, probing exponentially further from the back before
+/// binary searching the last step.
+fn gallop_back<T, P>(v: &[T], pred: P) -> usize
+where
+    P: Fn(&T) -> bool,
+{
+    let len = v.len();
+    let mut bound = 1;
+    while bound <= len && !pred(&v[len - bound]) {
+        bound *= 2;
+    }
+    let start = len - bound.min(len);
+    let end = len - bound / 2;
+    start + v[start..end].partition_point(pred)
+}
+
+/// Copies `count` elements from `src` to the non-overlapping `dst`.
+///
+/// With the `tiled-merge` feature on x86_64, long copies are written with
+/// non-temporal stores, which go around the cache rather than evicting data
+/// the merge still needs for output it won't read again soon. Elsewhere this
+/// is `ptr::copy_nonoverlapping`.
+#[inline]
+unsafe fn bulk_copy<T>(src: *const T, dst: *mut T, count: usize) {
+    #[cfg(all(feature = "tiled-merge", target_arch = "x86_64"))]
+    {
+        let bytes = count * mem::size_of::<T>();
+        if bytes >= STREAM_BYTES {
+            stream_copy(src as *const u8, dst as *mut u8, bytes);
+            return;
+        }
+    }
+    ptr::copy_nonoverlapping(src, dst, count);
+}
+
+/// Copies `bytes` bytes from `src` to the non-overlapping `dst`, using
+/// non-temporal stores for the 16 byte aligned blocks of `dst`.
+#[cfg(all(feature = "tiled-merge", target_arch = "x86_64"))]
+unsafe fn stream_copy(src: *const u8, dst: *mut u8, bytes: usize) {
+    use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_sfence, _mm_stream_si128};
+
+    let head = dst.align_offset(16).min(bytes);
+    ptr::copy_nonoverlapping(src, dst, head);
+    let mut i = head;
+    while i + 16 <= bytes {
+        let block = _mm_loadu_si128(src.add(i) as *const __m128i);
+        _mm_stream_si128(dst.add(i) as *mut __m128i, block);
+        i += 16;
+    }
+    ptr::copy_nonoverlapping(src.add(i), dst.add(i), bytes - i);
+    // non-temporal stores aren't ordered with later stores, so fence them
+    // before anything else can publish the slice to another thread
+    _mm_sfence();
+}
+
+/// The elements of the merge buffer in `[start, end)` that haven't been
+/// merged yet, and where they belong in the slice. On drop they are copied
+/// there, so the slice holds every element exactly once even if a
+/// comparison panics part way through a merge.
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let len = ptr_distance(self.end, self.start) as usize;
+            bulk_copy(self.start, self.dest, len);
+        }
+    }
+}
+
+/// Merges the sorted `v[..mid]` and `v[mid..]`, where the first is the
+/// shorter and `buf` has room for it, working from the front.
+unsafe fn merge_lo<T, F>(v: &mut [T], mid: usize, buf: *mut T, min_gallop: &mut usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    let ptr = v.as_mut_ptr();
+    ptr::copy_nonoverlapping(ptr, buf, mid);
+    // the first run is in the hole, the second is [right, right_end) and the
+    // merged output is written to hole.dest, just below right
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: ptr,
+    };
+    let mut right = ptr.add(mid);
+    let right_end = ptr.add(len);
+
+    while hole.start < hole.end && right < right_end {
+        // take one element at a time until one run keeps winning
+        let mut left_wins = 0;
+        let mut right_wins = 0;
+        while hole.start < hole.end && right < right_end {
+            if lt(&*right, &*hole.start) {
+                ptr::copy(right, hole.dest, 1);
+                right = right.add(1);
+                right_wins += 1;
+                left_wins = 0;
+            } else {
+                ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                hole.start = hole.start.add(1);
+                left_wins += 1;
+                right_wins = 0;
+            }
+            hole.dest = hole.dest.add(1);
+            if left_wins >= *min_gallop || right_wins >= *min_gallop {
+                break;
+            }
+        }
+
+        // then gallop, copying whole stretches of a run at once, until
+        // neither run wins by much
+        while hole.start < hole.end && right < right_end {
+            let left =
+                slice::from_raw_parts(hole.start, ptr_distance(hole.end, hole.start) as usize);
+            let taken_left = gallop_front(left, |x| !lt(&*right, x));
+            bulk_copy(hole.start, hole.dest, taken_left);
+            hole.start = hole.start.add(taken_left);
+            hole.dest = hole.dest.add(taken_left);
+            if hole.start == hole.end {
+                break;
+            }
+
+            let rest = slice::from_raw_parts(right, ptr_distance(right_end, right) as usize);
+            let taken_right = gallop_front(rest, |y| lt(y, &*hole.start));
+            ptr::copy(right, hole.dest, taken_right);
+            right = right.add(taken_right);
+            hole.dest = hole.dest.add(taken_right);
+
+            if taken_left < MIN_GALLOP && taken_right < MIN_GALLOP {
+                // galloping isn't paying off, make it harder to start again
+                *min_gallop += 2;
+                break;
+            }
+            *min_gallop = (*min_gallop - 1).max(1);
+        }
+    }
+    // dropping the hole copies what is left of the first run into place,
+    // what is left of the second run is already there
+}
+
+/// Merges the sorted `v[..mid]` and `v[mid..]`, where the second is the
+/// shorter and `buf` has room for it, working from the back.
+unsafe fn merge_hi<T, F>(v: &mut [T], mid: usize, buf: *mut T, min_gallop: &mut usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    let ptr = v.as_mut_ptr();
+    ptr::copy_nonoverlapping(ptr.add(mid), buf, len - mid);
+    // the first run is [ptr, hole.dest), the second is in the hole and the
+    // merged output is written downwards from out, just above the hole
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(len - mid),
+        dest: ptr.add(mid),
+    };
+    let mut out = ptr.add(len);
+
+    while ptr < hole.dest && hole.start < hole.end {
+        let mut left_wins = 0;
+        let mut right_wins = 0;
+        while ptr < hole.dest && hole.start < hole.end {
+            out = out.sub(1);
+            // ties go to the second run, which belongs after the first
+            if lt(&*hole.end.sub(1), &*hole.dest.sub(1)) {
+                hole.dest = hole.dest.sub(1);
+                ptr::copy(hole.dest, out, 1);
+                left_wins += 1;
+                right_wins = 0;
+            } else {
+                hole.end = hole.end.sub(1);
+                ptr::copy_nonoverlapping(hole.end, out, 1);
+                right_wins += 1;
+                left_wins = 0;
+            }
+            if left_wins >= *min_gallop || right_wins >= *min_gallop {
+                break;
+            }
+        }
+
+        while ptr < hole.dest && hole.start < hole.end {
+            let left = slice::from_raw_parts(ptr, ptr_distance(hole.dest, ptr) as usize);
+            let last = &*hole.end.sub(1);
+            let taken_left = left.len() - gallop_back(left, |x| !lt(last, x));
+            hole.dest = hole.dest.sub(taken_left);
+            out = out.sub(taken_left);
+            ptr::copy(hole.dest, out, taken_left);
+            if hole.dest == ptr {
+                break;
+            }
+
+            let right =
+                slice::from_raw_parts(hole.start, ptr_distance(hole.end, hole.start) as usize);
+            let last = &*hole.dest.sub(1);
+            let taken_right = right.len() - gallop_back(right, |y| lt(y, last));
+            hole.end = hole.end.sub(taken_right);
+            out = out.sub(taken_right);
+            bulk_copy(hole.end, out, taken_right);
+
+            if taken_left < MIN_GALLOP && taken_right < MIN_GALLOP {
+                *min_gallop += 2;
+                break;
+            }
+            *min_gallop = (*min_gallop - 1).max(1);
+        }
+    }
+    // dropping the hole copies what is left of the second run into place,
+    // what is left of the first run is already there
+}
+
+/// Splits the merge of the sorted `v[..mid]` and `v[mid..]` into two
+/// independent merges. The longer run is cut in half, its middle element's
+/// place in the other run is found by binary search and the elements
+/// between the two cuts are rotated so everything before the split is not
+/// greater than everything after it. Returns the split and the boundaries
+/// of the runs either side of it.
+#[cfg(feature = "tiled-merge")]
+fn split_merge<T, F>(v: &mut [T], mid: usize, lt: &F) -> (usize, usize, usize)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    let (left_cut, right_cut) = if mid >= len - mid {
+        let left_cut = mid / 2;
+        let right_cut = mid + v[mid..].partition_point(|y| lt(y, &v[left_cut]));
+        (left_cut, right_cut)
+    } else {
+        let right_cut = mid + (len - mid) / 2;
+        let left_cut = v[..mid].partition_point(|x| !lt(&v[right_cut], x));
+        (left_cut, right_cut)
+    };
+    // equal elements of the two runs never cross the split, so this is
+    // stable
+    v[left_cut..right_cut].rotate_left(mid - left_cut);
+    let split = left_cut + (right_cut - mid);
+    (split, left_cut, right_cut - split)
+}
+
+/// Merges the adjacent sorted runs `v[..mid]` and `v[mid..]`, using `buf` as
+/// scratch space for the shorter of them.
+///
+/// With the `tiled-merge` feature, merges bigger than `TILE_BYTES` are
+/// split with `split_merge` until each piece fits in the cache.
+fn merge<T, F>(v: &mut [T], mid: usize, buf: &mut MergeBuf<T>, min_gallop: &mut usize, lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    if mid == 0 || mid == v.len() {
+        return;
+    }
+    // elements of the first run not greater than the start of the second,
+    // and of the second run not less than the end of the first, are already
+    // in place
+    let start = gallop_front(&v[..mid], |x| !lt(&v[mid], x));
+    if start == mid {
+        return;
+    }
+    let end = mid + gallop_back(&v[mid..], |y| lt(y, &v[mid - 1]));
+    let v = &mut v[start..end];
+    let mid = mid - start;
+
+    #[cfg(feature = "tiled-merge")]
+    {
+        if mem::size_of_val(v) > TILE_BYTES {
+            let (split, left_mid, right_mid) = split_merge(v, mid, lt);
+            let (left, right) = v.split_at_mut(split);
+            merge(left, left_mid, buf, min_gallop, lt);
+            merge(right, right_mid, buf, min_gallop, lt);
+            return;
+        }
+    }
+
+    let buf = buf.reserve(mid.min(v.len() - mid));
+    unsafe {
+        if mid <= v.len() - mid {
+            merge_lo(v, mid, buf, min_gallop, lt);
+        } else {
+            merge_hi(v, mid, buf, min_gallop, lt);
+        }
+    }
+}
+
+/// Merges the runs at `i` and `i + 1` of the stack.
+fn merge_at<T, F>(
+    v: &mut [T],
+    runs: &mut Vec<Run>,
+    i: usize,
+    buf: &mut MergeBuf<T>,
+    min_gallop: &mut usize,
+    lt: &F,
+) where
+    F: Fn(&T, &T) -> bool,
+{
+    let a = runs[i];
+    let b = runs.remove(i + 1);
+    runs[i].len += b.len;
+    merge(&mut v[a.start..b.start + b.len], a.len, buf, min_gallop, lt);
+}
+
+/// Internal timsort implementation.
+pub(crate) fn timsort_impl<T, F>(v: &mut [T], lt: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let len = v.len();
+    // zero-sized elements are indistinguishable, so any order is sorted
+    if mem::size_of::<T>() == 0 || len < 2 {
+        return;
+    }
+    if len < MIN_MERGE {
+        let run = count_run(v, lt);
+        binary_insertsort(v, run, lt);
+        return;
+    }
+
+    let min_run = min_run_length(len);
+    let mut runs: Vec<Run> = Vec::new();
+    let mut buf = MergeBuf::new();
+    let mut min_gallop = MIN_GALLOP;
+    let mut start = 0;
+    while start < len {
+        // find the next run, extending it to min_run if it is short
+        let mut run = count_run(&mut v[start..], lt);
+        if run < min_run {
+            let forced = min_run.min(len - start);
+            binary_insertsort(&mut v[start..start + forced], run, lt);
+            run = forced;
+        }
+        runs.push(Run { start, len: run });
+        start += run;
+
+        // merge until the run lengths on the stack shrink faster than the
+        // Fibonacci numbers, which bounds the stack and keeps merges
+        // balanced
+        loop {
+            let n = runs.len();
+            if n < 2 {
+                break;
+            }
+            let mut i = n - 2;
+            if (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+                || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len)
+            {
+                if runs[n - 3].len < runs[n - 1].len {
+                    i = n - 3;
+                }
+            } else if runs[n - 2].len > runs[n - 1].len {
+                break;
+            }
+            merge_at(v, &mut runs, i, &mut buf, &mut min_gallop, lt);
+        }
+    }
+
+    // merge whatever is left on the stack
+    while runs.len() > 1 {
+        let n = runs.len();
+        let mut i = n - 2;
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            i = n - 3;
+        }
+        merge_at(v, &mut runs, i, &mut buf, &mut min_gallop, lt);
+    }
+}
+
+/// Sorts the slice, in place, using `lt` to compare elements, preserving the
+/// order of equal elements.
+///
+/// This is timsort, a merge sort that finds the runs already in the slice,
+/// ascending or strictly descending, and extends short ones with binary
+/// insertion sort before merging them pairwise. When one run keeps winning
+/// during a merge it switches to galloping, copying whole stretches of that
+/// run found by exponential search. It is `O(n log n)` worst-case and `O(n)`
+/// on data made of a few sorted runs, and allocates a merge buffer of at
+/// most half the slice's length. With the `pool` feature the buffer is
+/// reused across sorts on the same thread, see the `pool` module. With the
+/// `tiled-merge` feature merges of runs too big for the cache are split into
+/// cache-sized pieces, and long copies are written around the cache on
+/// x86_64.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+/// sortrs::timsort_by(&mut v, |a, b| a.0 < b.0);
+/// assert!(v == [(1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+///
+/// // reverse sorting
+/// sortrs::timsort_by(&mut v, |a, b| b.0 < a.0);
+/// assert!(v == [(2, 'a'), (2, 'c'), (1, 'b'), (1, 'd')]);
+/// ```
+pub fn timsort_by<T, F>(v: &mut [T], lt: F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    record_sorted(v.len());
+    timsort_impl(v, &lt);
+}
+
+/// Sorts the slice, in place, preserving the order of equal elements.
+///
+/// This is equivalent to `timsort_by(v, |a, b| a.lt(b))`, see `timsort_by`
+/// for details.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut v = [-5, 4, 1, -3, 2];
+///
+/// sortrs::timsort(&mut v);
+/// assert!(v == [-5, -3, 1, 2, 4]);
+/// ```
+pub fn timsort<T: PartialOrd>(v: &mut [T]) {
+    timsort_by(v, |a, b| a.lt(b));
+}
@@ -0,0 +1,365 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Sorting buffers that are only partly initialized.
+
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+use std::slice;
+
+use super::{heapsort_impl, insertsort_impl, introsort_impl, record_sorted, sortable_len};
+
+/// Sorts the first `init` elements of `buf`, in place, using `lt` to compare
+/// elements, and returns them as an initialized slice.
+///
+/// This sorts a buffer filled by a decoder or other producer without first
+/// initializing the rest of it. The sort is `introsort_by`. The returned
+/// slice borrows `buf`; dropping the elements is still the caller's
+/// responsibility, as for any `MaybeUninit`.
+///
+/// # Safety
+///
+/// The first `init` elements of `buf` must be initialized.
+///
+/// # Panics
+///
+/// Panics if `init` is greater than `buf.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<u32>::uninit(); 8];
+/// for (slot, x) in buf.iter_mut().zip(&[5, 1, 4]) {
+///     slot.write(*x);
+/// }
+/// let sorted = unsafe { sortrs::introsort_uninit_by(&mut buf, 3, |a, b| a.lt(b)) };
+/// assert!(sorted == [1, 4, 5]);
+/// ```
+pub unsafe fn introsort_uninit_by<T, F>(buf: &mut [MaybeUninit<T>], init: usize, lt: F) -> &mut [T]
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let v = init_prefix(buf, init);
+    record_sorted(init);
+    introsort_impl(v, lt);
+    v
+}
+
+/// Sorts the first `init` elements of `buf`, in place, and returns them as
+/// an initialized slice.
+///
+/// This is equivalent to `introsort_uninit_by(buf, init, |a, b| a.lt(b))`,
+/// see `introsort_uninit_by` for details.
+///
+/// # Safety
+///
+/// The first `init` elements of `buf` must be initialized.
+///
+/// # Panics
+///
+/// Panics if `init` is greater than `buf.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<u32>::uninit(); 8];
+/// for (slot, x) in buf.iter_mut().zip(&[5, 1, 4]) {
+///     slot.write(*x);
+/// }
+/// let sorted = unsafe { sortrs::introsort_uninit(&mut buf, 3) };
+/// assert!(sorted == [1, 4, 5]);
+/// ```
+pub unsafe fn introsort_uninit<T: PartialOrd>(buf: &mut [MaybeUninit<T>], init: usize) -> &mut [T] {
+    introsort_uninit_by(buf, init, |a, b| a.lt(b))
+}
+
+/// Sorts the first `init` elements of `buf`, in place, using `lt` to compare
+/// elements, and returns them as an initialized slice.
+///
+/// This is `introsort_uninit_by` with `heapsort_by` as the sort, for callers
+/// that want its guaranteed `O(n log n)` without the quicksort phase.
+///
+/// # Safety
+///
+/// The first `init` elements of `buf` must be initialized.
+///
+/// # Panics
+///
+/// Panics if `init` is greater than `buf.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<u32>::uninit(); 8];
+/// for (slot, x) in buf.iter_mut().zip(&[5, 1, 4]) {
+///     slot.write(*x);
+/// }
+/// let sorted = unsafe { sortrs::heapsort_uninit_by(&mut buf, 3, |a, b| b.lt(a)) };
+/// assert!(sorted == [5, 4, 1]);
+/// ```
+pub unsafe fn heapsort_uninit_by<T, F>(buf: &mut [MaybeUninit<T>], init: usize, lt: F) -> &mut [T]
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let v = init_prefix(buf, init);
+    record_sorted(init);
+    if let Some(len) = sortable_len(v) {
+        heapsort_impl(v.as_mut_ptr(), len, &lt);
+    }
+    v
+}
+
+/// Sorts the first `init` elements of `buf`, in place, with heapsort and
+/// returns them as an initialized slice.
+///
+/// This is equivalent to `heapsort_uninit_by(buf, init, |a, b| a.lt(b))`.
+///
+/// # Safety
+///
+/// The first `init` elements of `buf` must be initialized.
+///
+/// # Panics
+///
+/// Panics if `init` is greater than `buf.len()`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<i32>::uninit(); 6];
+/// for (slot, x) in buf.iter_mut().zip(&[3, -2, 8, 0]) {
+///     slot.write(*x);
+/// }
+/// let sorted = unsafe { sortrs::heapsort_uninit(&mut buf, 4) };
+/// assert!(sorted == [-2, 0, 3, 8]);
+/// ```
+pub unsafe fn heapsort_uninit<T: PartialOrd>(buf: &mut [MaybeUninit<T>], init: usize) -> &mut [T] {
+    heapsort_uninit_by(buf, init, |a, b| a.lt(b))
+}
+
+/// Returns the first `init` elements of `buf` as an initialized slice.
+///
+/// The caller must guarantee they are initialized.
+unsafe fn init_prefix<T>(buf: &mut [MaybeUninit<T>], init: usize) -> &mut [T] {
+    assert!(init <= buf.len(), "initialized count exceeds the buffer");
+    // MaybeUninit<T> has the same layout as T
+    slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut T, init)
+}
+
+/// Writes a sorted copy of `src` to the start of `dst`, using `lt` to
+/// compare elements, and returns the copy as an initialized slice.
+///
+/// Rather than copying and then sorting, the copy is the sort's first
+/// partitioning pass. A median of 3 pivot is chosen from `src` and each
+/// element is written to the front or back of the destination depending on
+/// which side of the pivot it falls, then both sides are sorted in place
+/// with `introsort_by`. This reads and writes every element once less than a
+/// clone followed by a sort, and leaves `src` untouched.
+///
+/// If cloning an element panics the elements already written are leaked,
+/// not dropped, as for any `MaybeUninit`.
+///
+/// # Panics
+///
+/// Panics if `dst` is shorter than `src`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let src = [5, 1, 4, 2, 3];
+/// let mut dst = [MaybeUninit::uninit(); 5];
+/// let sorted = sortrs::sort_copy_into_uninit_by(&src, &mut dst, |a, b| b.lt(a));
+/// assert!(sorted == [5, 4, 3, 2, 1]);
+/// assert!(src == [5, 1, 4, 2, 3]);
+/// ```
+pub fn sort_copy_into_uninit_by<'a, T, F>(
+    src: &[T],
+    dst: &'a mut [MaybeUninit<T>],
+    lt: F,
+) -> &'a mut [T]
+where
+    T: Clone,
+    F: Fn(&T, &T) -> bool,
+{
+    let len = src.len();
+    assert!(dst.len() >= len, "destination is shorter than the source");
+    record_sorted(len);
+    let p = dst.as_mut_ptr() as *mut T;
+    if len < 3 {
+        for (slot, x) in dst.iter_mut().zip(src) {
+            slot.write(x.clone());
+        }
+        let v = unsafe { slice::from_raw_parts_mut(p, len) };
+        insertsort_impl(p, len as isize, &lt);
+        return v;
+    }
+
+    // pick the median of 3 as the pivot, it is only read from src
+    let (a, b, c) = (&src[0], &src[len / 2], &src[len - 1]);
+    let pivot = if lt(a, b) == lt(b, c) {
+        b
+    } else if lt(a, b) == lt(a, c) {
+        c
+    } else {
+        a
+    };
+
+    // partition while copying, smaller elements fill from the front and the
+    // rest from the back
+    let mut lower = 0;
+    let mut upper = len;
+    for x in src {
+        let x = x.clone();
+        if lt(&x, pivot) {
+            dst[lower].write(x);
+            lower += 1;
+        } else {
+            upper -= 1;
+            dst[upper].write(x);
+        }
+    }
+
+    let v = unsafe { slice::from_raw_parts_mut(p, len) };
+    {
+        let (less, rest) = v.split_at_mut(lower);
+        introsort_impl(less, &lt);
+        introsort_impl(rest, &lt);
+    }
+    v
+}
+
+/// Writes a sorted copy of `src` to the start of `dst` and returns the copy
+/// as an initialized slice.
+///
+/// This is equivalent to `sort_copy_into_uninit_by(src, dst, |a, b|
+/// a.lt(b))`, see `sort_copy_into_uninit_by` for details.
+///
+/// # Panics
+///
+/// Panics if `dst` is shorter than `src`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let src = vec!["pear".to_string(), "fig".to_string()];
+/// let mut dst = vec![MaybeUninit::uninit(), MaybeUninit::uninit()];
+/// let sorted = sortrs::sort_copy_into_uninit(&src, &mut dst);
+/// assert!(sorted == ["fig", "pear"]);
+/// # for s in sorted.iter_mut() { unsafe { std::ptr::drop_in_place(s) } }
+/// ```
+pub fn sort_copy_into_uninit<'a, T>(src: &[T], dst: &'a mut [MaybeUninit<T>]) -> &'a mut [T]
+where
+    T: PartialOrd + Clone,
+{
+    sort_copy_into_uninit_by(src, dst, |a, b| a.lt(b))
+}
+
+/// A sorted sequence built in a caller provided, uninitialized buffer.
+///
+/// Each pushed element is inserted at its sorted position, after any equal
+/// elements, so the initialized prefix of the buffer is always sorted and
+/// elements never need to be initialized in advance. Finding the position is
+/// a binary search, but making room shifts the elements after it, so each
+/// push is `O(n)`; this suits small buffers or mostly ascending input, for
+/// which the shift is short.
+///
+/// Dropping a `SortedBuffer` drops the elements pushed into it. Use
+/// `into_slice` to keep them in the buffer instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+///
+/// let mut storage = [MaybeUninit::<u32>::uninit(); 4];
+/// let mut buf = sortrs::SortedBuffer::new(&mut storage);
+/// for &x in &[30, 10, 20] {
+///     buf.push(x).unwrap();
+/// }
+/// assert!(buf.as_slice() == [10, 20, 30]);
+/// buf.push(0).unwrap();
+/// assert_eq!(buf.push(40), Err(40));
+/// assert!(buf.into_slice() == [0, 10, 20, 30]);
+/// ```
+pub struct SortedBuffer<'a, T: 'a> {
+    buf: &'a mut [MaybeUninit<T>],
+    len: usize,
+}
+
+impl<'a, T: PartialOrd> SortedBuffer<'a, T> {
+    /// Creates an empty `SortedBuffer` using `buf` as storage.
+    pub fn new(buf: &'a mut [MaybeUninit<T>]) -> SortedBuffer<'a, T> {
+        SortedBuffer { buf, len: 0 }
+    }
+
+    /// Inserts `x` at its sorted position, after any equal elements, or
+    /// returns it if the buffer is full.
+    pub fn push(&mut self, x: T) -> Result<(), T> {
+        if self.len == self.buf.len() {
+            return Err(x);
+        }
+        let pos = self.as_slice().partition_point(|y| !x.lt(y));
+        unsafe {
+            let p = self.buf.as_mut_ptr() as *mut T;
+            ptr::copy(p.add(pos), p.add(pos + 1), self.len - pos);
+            ptr::write(p.add(pos), x);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the number of elements pushed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no elements have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Returns the sorted elements.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+
+    /// Returns the sorted elements, leaving them in the buffer. They won't be
+    /// dropped, as for any `MaybeUninit`.
+    pub fn into_slice(self) -> &'a mut [T] {
+        let len = self.len;
+        let p = self.buf.as_mut_ptr() as *mut T;
+        // the elements now belong to the returned slice
+        mem::forget(self);
+        unsafe { slice::from_raw_parts_mut(p, len) }
+    }
+}
+
+impl<'a, T> Drop for SortedBuffer<'a, T> {
+    fn drop(&mut self) {
+        unsafe {
+            let p = self.buf.as_mut_ptr() as *mut T;
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(p, self.len));
+        }
+    }
+}
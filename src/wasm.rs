@@ -0,0 +1,62 @@
+// Copyright 2015 Cameron Hart
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `wasm-bindgen` bindings for sorting JavaScript typed arrays and strings.
+//!
+//! Typed arrays passed to the `&mut` slice functions are copied into wasm
+//! memory, sorted with introsort and copied back into the caller's array.
+
+use wasm_bindgen::prelude::*;
+
+use super::sort_unstable_by;
+
+/// Sorts a `Float64Array` in place in ascending order.
+///
+/// NaNs are placed after every other value, matching
+/// `Float64Array.prototype.sort`.
+#[wasm_bindgen(js_name = sortFloat64Array)]
+pub fn sort_f64(v: &mut [f64]) {
+    sort_unstable_by(v, |a, b| a < b || (b.is_nan() && !a.is_nan()));
+}
+
+/// Sorts a `Float32Array` in place in ascending order.
+///
+/// NaNs are placed after every other value, matching
+/// `Float32Array.prototype.sort`.
+#[wasm_bindgen(js_name = sortFloat32Array)]
+pub fn sort_f32(v: &mut [f32]) {
+    sort_unstable_by(v, |a, b| a < b || (b.is_nan() && !a.is_nan()));
+}
+
+/// Sorts an `Int32Array` in place in ascending order.
+#[wasm_bindgen(js_name = sortInt32Array)]
+pub fn sort_i32(v: &mut [i32]) {
+    sort_unstable_by(v, |a, b| a.lt(b));
+}
+
+/// Sorts a `Uint32Array` in place in ascending order.
+#[wasm_bindgen(js_name = sortUint32Array)]
+pub fn sort_u32(v: &mut [u32]) {
+    sort_unstable_by(v, |a, b| a.lt(b));
+}
+
+/// Returns a new array with the strings of `v` sorted by UTF-8 byte order.
+///
+/// Throws if any element of `v` is not a string.
+#[wasm_bindgen(js_name = sortStrings)]
+pub fn sort_strings(v: Vec<JsValue>) -> Result<Vec<JsValue>, JsValue> {
+    let mut strings = Vec::with_capacity(v.len());
+    for x in v.iter() {
+        match x.as_string() {
+            Some(s) => strings.push(s),
+            None => return Err(JsValue::from_str("sortStrings expects an array of strings")),
+        }
+    }
+    sort_unstable_by(&mut strings, |a, b| a.lt(b));
+    Ok(strings.into_iter().map(JsValue::from).collect())
+}
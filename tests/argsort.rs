@@ -0,0 +1,185 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    apply_permutation, argsort_nullable, introsort_tiebreak, introsort_tiebreak_by, lexsort,
+    sort_by, sort_by_key_slice, NullPlacement, SortColumn, ValidityColumn,
+};
+
+#[test]
+fn test_lexsort() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let a = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 4)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let b = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| (x % 4) as f32)
+                .take(len)
+                .collect::<Vec<f32>>();
+            let c = &a[..];
+
+            let order = lexsort(&[&a, &b]);
+            let mut seen = vec![false; len];
+            for &i in &order {
+                assert!(!seen[i]);
+                seen[i] = true;
+            }
+            assert!(order.windows(2).all(|w| {
+                let (i, j) = (w[0], w[1]);
+                (a[i], b[i] as u8, i) < (a[j], b[j] as u8, j)
+            }));
+
+            // a single column argsort is stable
+            let order = lexsort(&[&c]);
+            assert!(order.windows(2).all(|w| (a[w[0]], w[0]) < (a[w[1]], w[1])));
+        }
+    }
+
+    assert!(lexsort(&[]).is_empty());
+    let column = [3, 1, 2];
+    assert_eq!(column.len(), 3);
+    assert!(!SortColumn::is_empty(&column));
+    assert!(lexsort(&[&column]) == [1, 2, 0]);
+}
+
+#[test]
+#[should_panic]
+fn test_lexsort_length_mismatch() {
+    lexsort(&[&vec![1, 2], &vec![1]]);
+}
+
+#[test]
+fn test_argsort_nullable() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let values = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 4)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let validity = thread_rng()
+                .gen_iter::<u8>()
+                .take(len.div_ceil(8))
+                .collect::<Vec<u8>>();
+            let column = ValidityColumn::new(&values, &validity);
+            let valid = |i: usize| validity[i / 8] & (1 << (i % 8)) != 0;
+
+            // nulls rank as -1 or 4 around the valid values 0..4
+            for &(nulls, null_rank) in &[(NullPlacement::First, -1), (NullPlacement::Last, 4)] {
+                let rank = |i: usize| {
+                    if valid(i) {
+                        values[i] as i32
+                    } else {
+                        null_rank
+                    }
+                };
+                let order = argsort_nullable(&column, nulls);
+                assert_eq!(order.len(), len);
+                assert!(order
+                    .windows(2)
+                    .all(|w| (rank(w[0]), w[0]) < (rank(w[1]), w[1])));
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_validity_column_too_short() {
+    ValidityColumn::new(&[0u8; 9], &[0xff]);
+}
+
+#[test]
+fn test_apply_permutation() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let v = thread_rng()
+                .gen_iter::<u32>()
+                .take(len)
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>();
+            let mut perm = (0..len).collect::<Vec<usize>>();
+            thread_rng().shuffle(&mut perm);
+
+            let mut v1 = v.clone();
+            apply_permutation(&mut v1, &perm);
+            for i in 0..len {
+                assert!(v1[i] == v[perm[i]]);
+            }
+
+            // applying the argsort of a column sorts it
+            let mut v1 = v.clone();
+            let order = lexsort(&[&v]);
+            apply_permutation(&mut v1, &order);
+            assert!(v1.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_apply_permutation_duplicate_index() {
+    apply_permutation(&mut [1, 2, 3], &[0, 1, 1]);
+}
+
+#[test]
+#[should_panic]
+fn test_apply_permutation_out_of_range() {
+    apply_permutation(&mut [1, 2, 3], &[0, 1, 3]);
+}
+
+#[test]
+fn test_introsort_tiebreak() {
+    for len in 0usize..100 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 8)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .take(len)
+            .collect::<Vec<(u8, usize)>>();
+
+        // sorting by key alone must match the stable sort
+        let mut expected = v.clone();
+        sort_by(&mut expected, |a, b| a.0 < b.0);
+        let mut v1 = v.clone();
+        introsort_tiebreak_by(&mut v1, |a, b| a.0 < b.0);
+        assert!(v1 == expected);
+
+        let mut keys = v.iter().map(|x| x.0).collect::<Vec<u8>>();
+        introsort_tiebreak(&mut keys);
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
+
+#[test]
+fn test_sort_by_key_slice() {
+    for len in 0usize..100 {
+        let mut keys = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 8)
+            .take(len)
+            .collect::<Vec<u8>>();
+        let mut values = (0..len).collect::<Vec<usize>>();
+        let mut expected = keys
+            .iter()
+            .cloned()
+            .zip(0..len)
+            .collect::<Vec<(u8, usize)>>();
+        sort_by(&mut expected, |a, b| a.0 < b.0);
+
+        sort_by_key_slice(&mut values, &mut keys);
+        assert!(keys.iter().cloned().zip(values).collect::<Vec<_>>() == expected);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_sort_by_key_slice_length_mismatch() {
+    sort_by_key_slice(&mut [1, 2, 3], &mut [1, 2]);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "bench")]
+
+extern crate sortrs;
+
+use sortrs::bench::{tune, BenchRunner, Pattern};
+use sortrs::{heapsort, insertsort_by, introsort, introsort_with_config_by, is_permutation_of};
+
+#[test]
+fn test_bench_input() {
+    let runner = BenchRunner::new(|x| x);
+    for pattern in Pattern::all() {
+        for &len in &[0usize, 1, 5, 100, 1000] {
+            let v = runner.input(pattern, len);
+            assert_eq!(v.len(), len);
+            // the corpus is deterministic for a given seed
+            assert!(v == runner.input(pattern, len));
+            match pattern {
+                Pattern::Sorted => assert!(v.windows(2).all(|w| w[0] < w[1])),
+                Pattern::Reversed => assert!(v.windows(2).all(|w| w[0] > w[1])),
+                Pattern::NearlySorted => {
+                    let sorted = (0..len as u64).collect::<Vec<u64>>();
+                    assert!(is_permutation_of(&v, &sorted));
+                }
+                Pattern::FewUnique => assert!(v.iter().all(|&x| x < 16)),
+                Pattern::Random => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bench_run() {
+    let mut runner = BenchRunner::new(|x| x as u32);
+    runner.lens = vec![10, 200];
+    runner.iterations = 3;
+    for sortfn in &[introsort::<u32>, heapsort::<u32>] {
+        let results = runner.run(sortfn);
+        assert_eq!(results.len(), Pattern::all().len() * 2);
+        for result in &results {
+            assert!(result.min <= result.mean);
+            assert!(result.comparisons.is_none());
+        }
+    }
+}
+
+#[test]
+fn test_bench_run_counting() {
+    let mut runner = BenchRunner::new(|x| x);
+    runner.lens = vec![50];
+    runner.patterns = vec![Pattern::Sorted, Pattern::Reversed];
+    let results = runner.run_counting(|v, lt| insertsort_by(v, |a, b| lt(a, b)));
+    // insertion sort makes n - 1 comparisons on sorted input and n(n - 1)/2
+    // on reversed input
+    assert_eq!(results[0].comparisons, Some(49));
+    assert_eq!(results[1].comparisons, Some(49 * 50 / 2));
+}
+
+#[test]
+fn test_tune() {
+    let config = tune(|x| (x, x));
+    assert!(config.insertion_threshold > 0);
+    let runner = BenchRunner::new(|x| (x % 100, x));
+    for pattern in Pattern::all() {
+        let mut v = runner.input(pattern, 1000);
+        introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+        assert!(v.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
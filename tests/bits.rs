@@ -0,0 +1,106 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{sort_bits, Bitmap, Bits};
+
+#[test]
+fn test_sort_bits() {
+    for len in 0usize..200 {
+        let mut v = thread_rng()
+            .gen_iter::<bool>()
+            .take(len)
+            .collect::<Vec<bool>>();
+        let mut expected = v.clone();
+        expected.sort();
+        sort_bits(&mut v[..]);
+        assert!(v == expected);
+
+        let mut words = thread_rng()
+            .gen_iter::<u64>()
+            .map(|w| if len % 3 == 0 { w & w >> 1 } else { w })
+            .take(len / 8)
+            .collect::<Vec<u64>>();
+        let ones = words[..].count_ones();
+        sort_bits(&mut words[..]);
+        let bits = (0..words.len() * 64)
+            .map(|i| words[i / 64] >> (i % 64) & 1 == 1)
+            .collect::<Vec<bool>>();
+        assert_eq!(bits.iter().filter(|&&b| b).count(), ones);
+        assert!(bits.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
+
+#[test]
+fn test_set_range() {
+    for start in 0usize..130 {
+        for end in start..130 {
+            let mut words = [0u64; 3];
+            words[..].set_range(start..end, true);
+            assert_eq!(words[..].count_ones(), end - start);
+            let mut words = [!0u64; 3];
+            words[..].set_range(start..end, false);
+            assert_eq!(words[..].count_ones(), 192 - (end - start));
+        }
+    }
+}
+
+fn random_ids(len: usize, modulus: u32) -> Vec<u32> {
+    let mut ids = thread_rng()
+        .gen_iter::<u32>()
+        .map(|x| x % modulus)
+        .take(len)
+        .collect::<Vec<u32>>();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_bitmap() {
+    for &len in &[0usize, 1, 10, 1000] {
+        for &modulus in &[1u32, 100, 100_000, u32::MAX] {
+            let a = random_ids(len, modulus);
+            let b = random_ids(len / 2 + 1, modulus);
+            let mut a_set = a.clone();
+            a_set.dedup();
+            let mut b_set = b.clone();
+            b_set.dedup();
+
+            let x = Bitmap::from_sorted_ids(&a);
+            let y = Bitmap::from_sorted_ids(&b);
+            assert!(x.to_sorted_ids() == a_set);
+            assert_eq!(x.len(), a_set.len());
+            assert_eq!(x.is_empty(), a_set.is_empty());
+            for &id in &b_set {
+                assert_eq!(x.contains(id), a_set.binary_search(&id).is_ok());
+            }
+
+            let mut union = a_set.clone();
+            union.extend_from_slice(&b_set);
+            union.sort();
+            union.dedup();
+            let intersection = a_set
+                .iter()
+                .cloned()
+                .filter(|id| b_set.binary_search(id).is_ok())
+                .collect::<Vec<u32>>();
+            let difference = a_set
+                .iter()
+                .cloned()
+                .filter(|id| b_set.binary_search(id).is_err())
+                .collect::<Vec<u32>>();
+            assert!(x.union(&y).to_sorted_ids() == union);
+            assert!(x.intersection(&y).to_sorted_ids() == intersection);
+            assert!(x.difference(&y).to_sorted_ids() == difference);
+            // empty words are dropped, so equal sets are equal bitmaps
+            assert!(x.intersection(&y) == Bitmap::from_sorted_ids(&intersection));
+            assert!(x.difference(&x) == Bitmap::new());
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_bitmap_unsorted() {
+    Bitmap::from_sorted_ids(&[2, 1]);
+}
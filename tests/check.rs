@@ -0,0 +1,44 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{heapsort_by, introsort_by, CheckedCmp};
+
+#[test]
+fn test_checked_cmp() {
+    let checked = CheckedCmp::new(|a: &u32, b: &u32| a < b);
+    for len in 0usize..100 {
+        let mut v = thread_rng()
+            .gen_iter::<u32>()
+            .take(len)
+            .collect::<Vec<u32>>();
+        checked.reset();
+        introsort_by(&mut v, |a, b| checked.lt(a, b));
+        assert!(v.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(checked.reflexivity_violations(), 0);
+        // heapsort makes at most about 2 n lg n comparisons
+        checked.reset();
+        heapsort_by(&mut v, |a, b| checked.lt(a, b));
+        assert!(checked.calls() <= 2 * (len as u64 + 1) * 8);
+    }
+    assert!(checked.calls() > 0);
+}
+
+#[test]
+fn test_checked_cmp_reflexivity() {
+    let checked = CheckedCmp::new(|a: &u32, b: &u32| a <= b);
+    assert!(checked.lt(&1, &2));
+    assert_eq!(checked.calls(), 1);
+    assert_eq!(checked.reflexivity_violations(), 1);
+    checked.reset();
+    assert_eq!(checked.calls(), 0);
+    assert_eq!(checked.reflexivity_violations(), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_checked_cmp_budget() {
+    let checked = CheckedCmp::with_budget(|a: &u32, b: &u32| a < b, 10);
+    let mut v = (0..100u32).rev().collect::<Vec<u32>>();
+    introsort_by(&mut v, |a, b| checked.lt(a, b));
+}
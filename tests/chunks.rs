@@ -0,0 +1,57 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, sort_chunks, sort_chunks_by};
+
+fn check_chunks<const N: usize>() {
+    // by the 0-1 principle a network sorts everything if it sorts every
+    // sequence of zeros and ones
+    if N <= 8 {
+        for bits in 0u32..1 << N {
+            let mut v = (0..N).map(|i| (bits >> i) & 1).collect::<Vec<u32>>();
+            sort_chunks::<N, _>(&mut v);
+            assert!(v.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    for len in 0usize..4 * N + 3 {
+        let original = thread_rng().gen_iter::<u8>().take(len).collect::<Vec<u8>>();
+        let mut expected = original.clone();
+        for chunk in expected.chunks_mut(N) {
+            introsort(chunk);
+        }
+
+        let mut v = original.clone();
+        sort_chunks::<N, _>(&mut v);
+        assert!(v == expected);
+
+        for chunk in expected.chunks_mut(N) {
+            chunk.reverse();
+        }
+        let mut v = original.clone();
+        sort_chunks_by::<N, _, _>(&mut v, |a, b| b.lt(a));
+        assert!(v == expected);
+    }
+}
+
+#[test]
+fn test_sort_chunks() {
+    check_chunks::<1>();
+    check_chunks::<2>();
+    check_chunks::<3>();
+    check_chunks::<4>();
+    check_chunks::<5>();
+    check_chunks::<6>();
+    check_chunks::<7>();
+    check_chunks::<8>();
+    check_chunks::<9>();
+    check_chunks::<16>();
+    check_chunks::<100>();
+}
+
+#[test]
+#[should_panic]
+fn test_sort_chunks_zero() {
+    sort_chunks::<0, _>(&mut [2, 1]);
+}
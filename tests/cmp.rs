@@ -0,0 +1,156 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::cmp::Ordering;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    heapsort_by_cmp, heapsort_with, insertsort_by_cmp, insertsort_with, introsort,
+    introsort_by_cmp, introsort_with, sort_by_cmp, sort_unstable_by_cmp, sort_unstable_with,
+    sort_with, timsort_by_cmp, timsort_with, CheckedCmp, Compare,
+};
+
+type Pair = (u8, usize);
+type Sort = fn(&mut [Pair], fn(&Pair, &Pair) -> Ordering);
+
+#[test]
+fn test_sort_by_cmp() {
+    let sorts: [Sort; 6] = [
+        |v, cmp| insertsort_by_cmp(v, cmp),
+        |v, cmp| heapsort_by_cmp(v, cmp),
+        |v, cmp| introsort_by_cmp(v, cmp),
+        |v, cmp| timsort_by_cmp(v, cmp),
+        |v, cmp| sort_by_cmp(v, cmp),
+        |v, cmp| sort_unstable_by_cmp(v, cmp),
+    ];
+    for len in 0usize..100 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 16)
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u8, usize)>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+
+        for sort in &sorts {
+            let mut v1 = v.clone();
+            sort(&mut v1, Ord::cmp);
+            assert!(v1 == expected);
+            sort(&mut v1, |a, b| b.cmp(a));
+            assert!(v1.windows(2).all(|w| w[0] > w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_sort_by_cmp_stable() {
+    for len in 0usize..100 {
+        let mut v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 4)
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u8, usize)>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+        let mut v1 = v.clone();
+
+        timsort_by_cmp(&mut v, |a, b| a.0.cmp(&b.0));
+        assert!(v == expected);
+        sort_by_cmp(&mut v1, |a, b| a.0.cmp(&b.0));
+        assert!(v1 == expected);
+    }
+}
+
+#[test]
+fn test_sort_by_cmp_total_cmp() {
+    let mut v = thread_rng()
+        .gen_iter::<f64>()
+        .map(|x| x - 0.5)
+        .take(100)
+        .collect::<Vec<f64>>();
+    v.extend_from_slice(&[0.0, -0.0, f64::NAN, f64::INFINITY]);
+    introsort_by_cmp(&mut v, f64::total_cmp);
+    assert!(v
+        .windows(2)
+        .all(|w| w[0].total_cmp(&w[1]) != Ordering::Greater));
+    assert!(v.last().unwrap().is_nan());
+}
+
+#[test]
+fn test_sort_by_cmp_fn_mut() {
+    // a comparator that counts its calls, as can be passed to slice::sort_by
+    let v = thread_rng()
+        .gen_iter::<u32>()
+        .take(100)
+        .collect::<Vec<u32>>();
+    let mut expected = v.clone();
+    introsort(&mut expected);
+    let mut calls = 0;
+    let mut v1 = v.clone();
+    introsort_by_cmp(&mut v1, |a, b| {
+        calls += 1;
+        a.cmp(b)
+    });
+    assert!(v1 == expected);
+    assert!(calls > 0);
+    let mut v1 = v.clone();
+    sort_by_cmp(&mut v1, |a, b| {
+        calls += 1;
+        a.cmp(b)
+    });
+    assert!(v1 == expected);
+}
+
+/// Orders indices by the weight stored for them.
+struct ByWeight<'a>(&'a [u32]);
+
+impl<'a> Compare<usize> for ByWeight<'a> {
+    fn lt(&self, a: &usize, b: &usize) -> bool {
+        self.0[*a] < self.0[*b]
+    }
+}
+
+#[test]
+fn test_sort_with() {
+    let sorts: [fn(&mut [usize], &ByWeight); 6] = [
+        |v, cmp| insertsort_with(v, cmp),
+        |v, cmp| heapsort_with(v, cmp),
+        |v, cmp| introsort_with(v, cmp),
+        |v, cmp| timsort_with(v, cmp),
+        |v, cmp| sort_with(v, cmp),
+        |v, cmp| sort_unstable_with(v, cmp),
+    ];
+    for len in 0usize..100 {
+        let weights = thread_rng()
+            .gen_iter::<u32>()
+            .map(|x| x % 16)
+            .take(len)
+            .collect::<Vec<u32>>();
+        let mut expected = weights.clone();
+        introsort(&mut expected);
+        for sort in &sorts {
+            let mut v = (0..len).collect::<Vec<usize>>();
+            sort(&mut v, &ByWeight(&weights));
+            assert!(v.iter().map(|&i| weights[i]).collect::<Vec<u32>>() == expected);
+        }
+
+        // equal weights keep their index order in the stable sorts
+        let mut v = (0..len).collect::<Vec<usize>>();
+        sort_with(&mut v, &ByWeight(&weights));
+        assert!(v
+            .windows(2)
+            .all(|w| weights[w[0]] < weights[w[1]] || w[0] < w[1]));
+
+        // closures and CheckedCmp are comparators too
+        let checked = CheckedCmp::new(|a: &usize, b: &usize| b < a);
+        sort_unstable_with(&mut v, &checked);
+        assert!(v.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(checked.reflexivity_violations(), 0);
+        introsort_with(&mut v, &|a: &usize, b: &usize| a < b);
+        assert!(v == (0..len).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "compat")]
+
+extern crate rand;
+extern crate sortrs;
+
+use std::cmp::Reverse;
+
+use rand::{Rng, thread_rng};
+use sortrs::compat;
+
+#[test]
+fn test_compat_sorts_match_std() {
+    for len in 0usize..100 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 16)
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u8, usize)>>();
+
+        let mut expected = v.clone();
+        expected.sort();
+        let mut v1 = v.clone();
+        compat::sort(&mut v1);
+        assert!(v1 == expected);
+        let mut v1 = v.clone();
+        compat::sort_unstable(&mut v1);
+        assert!(v1 == expected);
+
+        // stable sorts by key keep the input order of equal keys
+        let mut expected = v.clone();
+        expected.sort_by_key(|x| x.0);
+        let mut v1 = v.clone();
+        compat::sort_by_key(&mut v1, |x| x.0);
+        assert!(v1 == expected);
+        let mut v1 = v.clone();
+        compat::sort_by_cached_key(&mut v1, |x| x.0.to_string().len() * 100 + x.0 as usize);
+        assert!(v1 == expected);
+        let mut v1 = v.clone();
+        compat::sort_by(&mut v1, |a, b| a.0.cmp(&b.0));
+        assert!(v1 == expected);
+
+        let mut v1 = v.clone();
+        compat::sort_unstable_by(&mut v1, |a, b| b.cmp(a));
+        assert!(v1.windows(2).all(|w| w[0] > w[1]));
+        compat::sort_unstable_by_key(&mut v1, |x| Reverse(x.1));
+        assert!(v1.windows(2).all(|w| w[0].1 > w[1].1));
+    }
+}
+
+#[test]
+fn test_compat_fnmut() {
+    // std accepts comparators that mutate their environment
+    let mut v = (0..100u32).rev().collect::<Vec<u32>>();
+    let mut calls = 0;
+    compat::sort_by(&mut v, |a, b| {
+        calls += 1;
+        a.cmp(b)
+    });
+    assert!(calls > 0);
+    assert!(v == (0..100).collect::<Vec<u32>>());
+
+    let mut keys = 0;
+    compat::sort_by_cached_key(&mut v, |&x| {
+        keys += 1;
+        Reverse(x)
+    });
+    assert_eq!(keys, 100);
+    assert!(v == (0..100).rev().collect::<Vec<u32>>());
+}
+
+#[test]
+fn test_compat_select_nth_unstable() {
+    for len in 1usize..60 {
+        for _ in 0..10 {
+            let v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut sorted = v.clone();
+            sorted.sort();
+            let index = thread_rng().gen_range(0, len);
+
+            let mut v1 = v.clone();
+            let (left, nth, right) = compat::select_nth_unstable(&mut v1, index);
+            assert_eq!(*nth, sorted[index]);
+            assert_eq!(left.len(), index);
+            assert!(left.iter().all(|x| x <= nth));
+            assert!(right.iter().all(|x| x >= nth));
+
+            let mut v1 = v.clone();
+            let (_, nth, _) = compat::select_nth_unstable_by(&mut v1, index, |a, b| b.cmp(a));
+            assert_eq!(*nth, sorted[len - 1 - index]);
+
+            let mut v1 = v.clone();
+            let (_, nth, right) = compat::select_nth_unstable_by_key(&mut v1, index, |&x| x);
+            assert_eq!(*nth, sorted[index]);
+            assert_eq!(right.len(), len - 1 - index);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_compat_select_nth_unstable_out_of_bounds() {
+    let mut v = [1, 2, 3];
+    compat::select_nth_unstable(&mut v, 3);
+}
@@ -0,0 +1,181 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{Class, Fallback, Insertion, IntrosortConfig, Order, SliceSortExt};
+
+type Sort = fn(&mut Vec<(u32, usize)>);
+
+#[test]
+fn test_slice_sort_ext() {
+    for &len in &[0usize, 1, 2, 10, 100, 1000] {
+        let original = thread_rng()
+            .gen_iter::<u32>()
+            .map(|x| x % 64)
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u32, usize)>>();
+        let mut sorted = original.clone();
+        sorted.sort();
+        let mut stable = original.clone();
+        stable.sort_by_key(|x| x.0);
+        let mut descending = sorted.clone();
+        descending.reverse();
+
+        let sorts: [Sort; 10] = [
+            |v| v.insertsort(),
+            |v| v.heapsort(),
+            |v| v.introsort(),
+            |v| v.introsort_tiebreak(),
+            |v| v.timsort(),
+            |v| v.sort_chunks::<3>(),
+            |v| {
+                v.sort_presorted();
+            },
+            |v| v.approx_sort(1000),
+            |v| {
+                v.repair_sorted(0);
+            },
+            |v| {
+                let config = IntrosortConfig {
+                    fallback: Fallback::Heapsort,
+                    insertion: Insertion::PerLeaf,
+                    depth_multiplier: 0,
+                    insertion_threshold: 4,
+                };
+                v.introsort_with_config_by(&config, |a, b| a.lt(b));
+            },
+        ];
+        for (i, sort) in sorts.iter().enumerate() {
+            let mut v = original.clone();
+            sort(&mut v);
+            // sort_chunks only sorts each chunk
+            if i == 5 {
+                assert!(v.chunks(3).all(|c| c.windows(2).all(|w| w[0] <= w[1])));
+            } else {
+                assert!(v == sorted);
+            }
+        }
+
+        let by: [Sort; 9] = [
+            |v| v.insertsort_by(|a, b| b.lt(a)),
+            |v| v.heapsort_by(|a, b| b.lt(a)),
+            |v| v.introsort_by(|a, b| b.lt(a)),
+            |v| v.introsort_tiebreak_by(|a, b| b.lt(a)),
+            |v| v.timsort_by(|a, b| b.lt(a)),
+            |v| {
+                v.sort_presorted_by(|a, b| b.lt(a));
+            },
+            |v| v.approx_sort_by(1000, |a, b| b.lt(a)),
+            |v| {
+                v.repair_sorted_by(0, |a, b| b.lt(a));
+            },
+            |v| v.heap_pop_k_by(1000, |a, b| b.lt(a)),
+        ];
+        for sort in &by {
+            let mut v = original.clone();
+            sort(&mut v);
+            assert!(v == descending);
+        }
+
+        // the key sorts are stable for this input as each key is in order
+        let by_key: [Sort; 5] = [
+            |v| v.insertsort_by_key(|x| x.0),
+            |v| v.heapsort_by_key(|x| (x.0, x.1)),
+            |v| v.introsort_by_key(|x| (x.0, x.1)),
+            |v| v.introsort_by_cached_key(|x| x.0),
+            |v| v.radix_sort_by_key(|x| u64::from(x.0)),
+        ];
+        for sort in &by_key {
+            let mut v = original.clone();
+            sort(&mut v);
+            assert!(v == stable);
+        }
+    }
+
+    let mut v = [5, 1, 4, 2, 3];
+    v.heap_pop_k(2);
+    assert!(v[3..] == [4, 5]);
+    let mut v = [4, 5, 1, 2, 3];
+    assert_eq!(v.sort_presorted(), Order::Rotated(2));
+    assert!(v == [1, 2, 3, 4, 5]);
+    assert_eq!(v.repair_sorted(0), Some(0));
+    let mut v = [3, 2, 1];
+    v.sort_chunks_by::<2, _>(|a, b| b.lt(a));
+    assert!(v == [3, 2, 1]);
+}
+
+#[test]
+fn test_slice_sort_ext_cmp_and_partition() {
+    let original = thread_rng()
+        .gen_iter::<u32>()
+        .map(|x| x % 64)
+        .take(200)
+        .enumerate()
+        .map(|(i, x)| (x, i))
+        .collect::<Vec<(u32, usize)>>();
+    let mut sorted = original.clone();
+    sorted.sort();
+    let mut stable = original.clone();
+    stable.sort_by_key(|x| x.0);
+
+    let sorts: [Sort; 14] = [
+        |v| v.insertsort_by_cmp(Ord::cmp),
+        |v| v.heapsort_by_cmp(Ord::cmp),
+        |v| v.introsort_by_cmp(Ord::cmp),
+        |v| v.timsort_by_cmp(Ord::cmp),
+        |v| v.sort_by_cmp(Ord::cmp),
+        |v| v.sort_unstable_by_cmp(Ord::cmp),
+        |v| v.insertsort_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.heapsort_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.introsort_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.timsort_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.sort_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.sort_unstable_with(&|a: &(u32, usize), b: &(u32, usize)| a < b),
+        |v| v.sort_ord(),
+        |v| v.sort_unstable_ord(),
+    ];
+    for sort in &sorts {
+        let mut v = original.clone();
+        sort(&mut v);
+        assert!(v == sorted);
+    }
+
+    let mut v = original.clone();
+    v.radix_sort_multi_key(&[|x| u64::from(x.0)]);
+    assert!(v == stable);
+
+    let mut v = original.clone();
+    let (mid, equal) = v.partition_at(100);
+    assert!(v[..mid].iter().all(|x| *x < v[mid]));
+    assert!(v[mid + equal..].iter().all(|x| *x > v[mid]));
+    let mut v = original.clone();
+    let (mid, _) = v.partition_at_by(0, |a, b| b.lt(a));
+    assert!(v[..mid].iter().all(|x| *x > v[mid]));
+
+    let mut v = original.clone();
+    let (mid, high) = v.partition3_stable(|x| match x.0 {
+        0..=15 => Class::Low,
+        16..=47 => Class::Mid,
+        _ => Class::High,
+    });
+    assert!(v[..mid].iter().all(|x| x.0 < 16));
+    assert!(v[mid..high].iter().all(|x| x.0 >= 16 && x.0 < 48));
+    assert!(v[high..].iter().all(|x| x.0 >= 48));
+    for part in &[&v[..mid], &v[mid..high], &v[high..]] {
+        assert!(part.windows(2).all(|w| w[0].1 < w[1].1));
+    }
+
+    let mut v = [4, 40, 2, 20, 3, 30, 1, 10];
+    v.sort_strided(2, 1);
+    assert!(v == [4, 10, 2, 20, 3, 30, 1, 40]);
+    v.sort_strided_by(2, 0, |a, b| b.lt(a));
+    assert!(v == [4, 10, 3, 20, 2, 30, 1, 40]);
+
+    let mut v = vec!["pear", "Fig", "apple"];
+    v.sort_strings();
+    assert!(v == ["Fig", "apple", "pear"]);
+    v.sort_strings_ci();
+    assert!(v == ["apple", "Fig", "pear"]);
+}
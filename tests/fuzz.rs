@@ -0,0 +1,31 @@
+#![cfg(feature = "fuzz")]
+
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::fuzz::sort_oracle;
+
+#[test]
+fn test_sort_oracle_every_header() {
+    let data = thread_rng().gen_iter::<u8>().take(200).collect::<Vec<u8>>();
+    for algorithm in 0..12u8 {
+        for element in 0..4u8 {
+            for pattern in 0..5u8 {
+                for quirk in 0..5u8 {
+                    let mut input = vec![algorithm, element, pattern, quirk, 3];
+                    input.extend_from_slice(&data);
+                    sort_oracle(&input);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_sort_oracle_random_inputs() {
+    for len in 0usize..300 {
+        let input = thread_rng().gen_iter::<u8>().take(len).collect::<Vec<u8>>();
+        sort_oracle(&input);
+    }
+}
@@ -0,0 +1,242 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    aggregate_sorted_by_key, introsort, par_group_by_key, par_group_by_key_with, rle_decode,
+    rle_encode, top_k_frequent, Scheduler, Task,
+};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn test_top_k_frequent() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let original = v.clone();
+
+            let top = top_k_frequent(&mut v, 3);
+            assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(top.len() <= 3);
+            assert!(top
+                .windows(2)
+                .all(|w| w[0].1 > w[1].1 || (w[0].1 == w[1].1 && w[0].0 < w[1].0)));
+            for &(x, count) in &top {
+                assert_eq!(original.iter().filter(|&&y| y == x).count(), count);
+            }
+            // nothing left out is more frequent than the least frequent returned
+            if let Some(&(_, min_count)) = top.last() {
+                for x in 0..8u8 {
+                    if !top.iter().any(|&(y, _)| y == x) {
+                        assert!(original.iter().filter(|&&y| y == x).count() <= min_count);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut v = ["b", "a", "b", "c", "a", "b"];
+    assert!(top_k_frequent(&mut v, 10) == [("b", 3), ("a", 2), ("c", 1)]);
+    assert!(top_k_frequent(&mut v, 0).is_empty());
+}
+
+#[test]
+fn test_rle() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .collect::<Vec<u8>>();
+            introsort(&mut v);
+
+            let runs = rle_encode(&v);
+            assert!(runs.windows(2).all(|w| w[0].0 < w[1].0));
+            assert!(runs.iter().all(|run| run.1 > 0));
+            assert!(rle_decode(&runs) == v);
+        }
+    }
+
+    // unsorted input still round trips, one pair per run
+    let v = [2, 2, 1, 2];
+    assert!(rle_encode(&v) == [(2, 2), (1, 1), (2, 1)]);
+    assert!(rle_decode(&rle_encode(&v)) == v);
+}
+
+#[test]
+fn test_aggregate_sorted_by_key() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| (x % 8, x as u32))
+                .take(len)
+                .collect::<Vec<(u8, u32)>>();
+            introsort(&mut v);
+
+            let sums = aggregate_sorted_by_key(&v, |x| x.0, 0, |sum, x| sum + x.1);
+            assert!(sums.windows(2).all(|w| w[0].0 < w[1].0));
+            for key in 0..8u8 {
+                let expected = v
+                    .iter()
+                    .filter(|x| x.0 == key)
+                    .map(|x| x.1)
+                    .collect::<Vec<u32>>();
+                let found = sums.iter().find(|s| s.0 == key).map(|s| s.1);
+                if expected.is_empty() {
+                    assert!(found.is_none());
+                } else {
+                    assert_eq!(found, Some(expected.iter().sum()));
+                }
+            }
+
+            // the elements of each group are folded in order
+            let groups = aggregate_sorted_by_key(
+                &v,
+                |x| x.0,
+                Vec::new(),
+                |mut acc, x| {
+                    acc.push(*x);
+                    acc
+                },
+            );
+            assert!(
+                groups
+                    .iter()
+                    .flat_map(|g| g.1.iter().cloned())
+                    .collect::<Vec<_>>()
+                    == v
+            );
+            assert!(
+                groups.iter().map(|g| (g.0, g.1.len())).collect::<Vec<_>>()
+                    == rle_encode(&v.iter().map(|x| x.0).collect::<Vec<u8>>())
+            );
+        }
+    }
+}
+
+#[test]
+fn test_par_group_by_key() {
+    for &threads in &[1usize, 2, 3, 8] {
+        for &len in &[0usize, 1, 2, 10, 100, 1000] {
+            for &modulus in &[1u32, 7, 1000] {
+                let mut v = thread_rng()
+                    .gen_iter::<u32>()
+                    .map(|x| x % modulus)
+                    .take(len)
+                    .enumerate()
+                    .map(|(i, x)| (x, i))
+                    .collect::<Vec<(u32, usize)>>();
+                let mut expected = v.clone();
+                // the input positions break ties, so this is the stable order
+                introsort(&mut expected);
+
+                let groups = par_group_by_key(&mut v, |x| x.0, threads);
+                assert!(v == expected);
+                assert!(groups
+                    .windows(2)
+                    .all(|w| w[0].0 < w[1].0 && w[0].1.end == w[1].1.start));
+                assert_eq!(groups.first().map_or(0, |g| g.1.start), 0);
+                assert_eq!(groups.last().map_or(0, |g| g.1.end), len);
+                for &(key, ref range) in &groups {
+                    assert!(v[range.clone()].iter().all(|x| x.0 == key));
+                }
+            }
+        }
+    }
+}
+
+/// A scheduler that runs the tasks one at a time on the calling thread,
+/// starting from task `rotate` and going backwards if `reverse`.
+struct Sequential {
+    rotate: usize,
+    reverse: bool,
+    tasks: Cell<usize>,
+}
+
+impl Scheduler for Sequential {
+    fn join_all<'a>(&self, tasks: Vec<Task<'a>>) {
+        let len = tasks.len();
+        self.tasks.set(len);
+        let mut tasks = tasks.into_iter().map(Some).collect::<Vec<_>>();
+        for i in 0..len {
+            let j = if self.reverse { len - 1 - i } else { i };
+            let task = tasks[(j + self.rotate) % len].take().unwrap();
+            task();
+        }
+    }
+}
+
+#[test]
+fn test_par_group_by_key_with() {
+    for &threads in &[1usize, 2, 3, 4] {
+        for &len in &[0usize, 1, 10, 100, 1000] {
+            let original = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| x % 50)
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u32, usize)>>();
+            let mut expected = original.clone();
+            let expected_groups = par_group_by_key(&mut expected, |x| x.0, threads);
+
+            // every order the tasks can finish in gives the same result
+            for rotate in 0..threads {
+                for &reverse in &[false, true] {
+                    let scheduler = Sequential {
+                        rotate,
+                        reverse,
+                        tasks: Cell::new(0),
+                    };
+                    let mut v = original.clone();
+                    let groups = par_group_by_key_with(&mut v, |x| x.0, threads, &scheduler);
+                    assert!(v == expected);
+                    assert!(groups == expected_groups);
+                    assert!(scheduler.tasks.get() <= threads);
+                }
+            }
+        }
+    }
+}
+
+/// A scheduler that runs every task on its own thread, all released at once
+/// once they have started, to make overlapping tasks as likely as possible.
+struct Barrier;
+
+impl Scheduler for Barrier {
+    fn join_all<'a>(&self, tasks: Vec<Task<'a>>) {
+        let waiting = &AtomicUsize::new(tasks.len());
+        std::thread::scope(|scope| {
+            for task in tasks {
+                scope.spawn(move || {
+                    waiting.fetch_sub(1, Ordering::SeqCst);
+                    while waiting.load(Ordering::SeqCst) != 0 {
+                        std::thread::yield_now();
+                    }
+                    task();
+                });
+            }
+        });
+    }
+}
+
+#[test]
+fn test_par_group_by_key_overlapping_tasks() {
+    for _ in 0..20 {
+        let mut v = thread_rng()
+            .gen_iter::<u16>()
+            .take(10000)
+            .collect::<Vec<u16>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+        par_group_by_key_with(&mut v, |&x| x, 8, &Barrier);
+        assert!(v == expected);
+    }
+}
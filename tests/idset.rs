@@ -0,0 +1,95 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::SortedIdSet;
+
+/// Random IDs, sparse, dense or in runs, over a few chunks.
+fn random_ids(len: usize, shape: usize) -> Vec<u32> {
+    let mut rng = thread_rng();
+    match shape {
+        0 => rng.gen_iter::<u32>().take(len).collect(),
+        1 => rng
+            .gen_iter::<u32>()
+            .map(|x| x % 200_000)
+            .take(len)
+            .collect(),
+        _ => {
+            let mut ids = Vec::with_capacity(len);
+            while ids.len() < len {
+                let start = rng.gen_range(0, 300_000);
+                ids.extend(start..start + rng.gen_range(1, 500));
+            }
+            ids
+        }
+    }
+}
+
+fn sorted_set(ids: &[u32]) -> Vec<u32> {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+#[test]
+fn test_sorted_id_set() {
+    for &len in &[0usize, 1, 10, 1000, 100_000] {
+        for shape in 0..3 {
+            let a = random_ids(len, shape);
+            let b = random_ids(len / 2 + 1, (shape + 1) % 3);
+            let (a_set, b_set) = (sorted_set(&a), sorted_set(&b));
+
+            let x = SortedIdSet::from_ids(&a);
+            let y = SortedIdSet::from_ids(&b);
+            assert!(x == SortedIdSet::from_sorted_ids(&a_set));
+            assert!(x.to_sorted_ids() == a_set);
+            assert!(x.iter().eq(a_set.iter().cloned()));
+            assert_eq!(x.len(), a_set.len());
+            assert_eq!(x.is_empty(), a_set.is_empty());
+            for &id in b_set.iter().take(1000) {
+                assert_eq!(x.contains(id), a_set.binary_search(&id).is_ok());
+                assert!(y.contains(id));
+            }
+
+            let mut union = a_set.clone();
+            union.extend_from_slice(&b_set);
+            let union = sorted_set(&union);
+            let intersection = a_set
+                .iter()
+                .cloned()
+                .filter(|id| b_set.binary_search(id).is_ok())
+                .collect::<Vec<u32>>();
+            let difference = a_set
+                .iter()
+                .cloned()
+                .filter(|id| b_set.binary_search(id).is_err())
+                .collect::<Vec<u32>>();
+            assert!(x.union(&y).to_sorted_ids() == union);
+            assert!(x.intersection(&y).to_sorted_ids() == intersection);
+            assert!(x.difference(&y).to_sorted_ids() == difference);
+            // every chunk is stored the same way however it was made
+            assert!(x.union(&y) == SortedIdSet::from_sorted_ids(&union));
+            assert!(x.intersection(&y) == SortedIdSet::from_sorted_ids(&intersection));
+            assert!(x.difference(&x) == SortedIdSet::new());
+        }
+    }
+}
+
+#[test]
+fn test_sorted_id_set_chunk_edges() {
+    let ids = [0, 1, 65534, 65535, 65536, 131071, u32::MAX - 1, u32::MAX];
+    let set = SortedIdSet::from_ids(&ids);
+    assert!(set.to_sorted_ids() == ids);
+    // a full chunk is a single run
+    let full = SortedIdSet::from_sorted_ids(&(65536..131072).collect::<Vec<u32>>());
+    assert_eq!(full.len(), 65536);
+    assert!(full.contains(65536) && full.contains(131071) && !full.contains(131072));
+    assert_eq!(full.intersection(&set).to_sorted_ids(), [65536, 131071]);
+}
+
+#[test]
+#[should_panic]
+fn test_sorted_id_set_unsorted() {
+    SortedIdSet::from_sorted_ids(&[2, 1]);
+}
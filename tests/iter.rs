@@ -0,0 +1,142 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    incremental_sort, incremental_sort_by, introsort, ksorted, ksorted_by, merge, merge_by,
+    merge_plan, sort_by, Side,
+};
+
+#[test]
+fn test_ksorted() {
+    for len in 0usize..100 {
+        for &k in &[0usize, 1, 3, 8, 200] {
+            // displace elements of a sorted sequence by at most k positions
+            let sorted = (0..len).collect::<Vec<usize>>();
+            let mut v = sorted.clone();
+            for block in v.chunks_mut(k + 1) {
+                thread_rng().shuffle(block);
+            }
+
+            let mut iter = ksorted(v.clone(), k);
+            assert_eq!(iter.size_hint(), (len, Some(len)));
+            if len > 0 {
+                iter.next();
+                assert_eq!(iter.size_hint(), (len - 1, Some(len - 1)));
+            }
+            assert!(ksorted(v.clone(), k).collect::<Vec<_>>() == sorted);
+
+            let reversed = ksorted_by(v.iter().rev(), k, |a, b| b.lt(a)).cloned();
+            assert!(reversed.eq(sorted.iter().rev().cloned()));
+        }
+    }
+}
+
+#[test]
+fn test_ksorted_too_displaced() {
+    // out of range displacement can't sort but must not lose items
+    let v = (0..50usize).rev().collect::<Vec<usize>>();
+    let mut out = ksorted(v, 3).collect::<Vec<usize>>();
+    assert_eq!(out.len(), 50);
+    introsort(&mut out);
+    assert!(out == (0..50).collect::<Vec<usize>>());
+}
+
+#[test]
+fn test_incremental_sort() {
+    for len in 0usize..100 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 32)
+            .take(len)
+            .collect::<Vec<u8>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+
+        let mut sorted = incremental_sort(v.clone());
+        assert_eq!(sorted.len(), len);
+        let m = len / 3;
+        assert!(sorted.by_ref().take(m).eq(expected[..m].iter().cloned()));
+        assert_eq!(sorted.len(), len - m);
+        assert!(sorted.by_ref().eq(expected[m..].iter().cloned()));
+        assert_eq!(sorted.next(), None);
+
+        // references into a slice that is left as it was
+        let sorted = incremental_sort_by(&v, |a, b| b < a).cloned();
+        assert!(sorted.eq(expected.iter().rev().cloned()));
+    }
+}
+
+fn sorted_tagged(len: usize, tag: char) -> Vec<(u8, char)> {
+    let mut v = thread_rng()
+        .gen_iter::<u8>()
+        .map(|x| (x % 8, tag))
+        .take(len)
+        .collect::<Vec<(u8, char)>>();
+    introsort(&mut v);
+    v
+}
+
+#[test]
+fn test_merge_by() {
+    for a_len in 0usize..20 {
+        for b_len in 0usize..20 {
+            let a = sorted_tagged(a_len, 'a');
+            let b = sorted_tagged(b_len, 'b');
+
+            // a stable sort of a followed by b gives the expected merge
+            let mut expected = a.clone();
+            expected.extend(b.iter().cloned());
+            sort_by(&mut expected, |x, y| x.0 < y.0);
+
+            let mut merged = merge_by(a.iter().cloned(), b.iter().cloned(), |x, y| x.0 < y.0);
+            assert_eq!(merged.size_hint(), (a_len + b_len, Some(a_len + b_len)));
+            assert!(merged.by_ref().collect::<Vec<_>>() == expected);
+            assert_eq!(merged.next(), None);
+            assert_eq!(merged.size_hint(), (0, Some(0)));
+
+            let keys = merge(a.iter().map(|x| x.0), b.iter().map(|x| x.0)).collect::<Vec<u8>>();
+            assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_merge_plan() {
+    for a_len in 0usize..20 {
+        for b_len in 0usize..20 {
+            let mut a = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| (x % 8, 'a'))
+                .take(a_len)
+                .collect::<Vec<(u8, char)>>();
+            let mut b = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| (x % 8, 'b'))
+                .take(b_len)
+                .collect::<Vec<(u8, char)>>();
+            sort_by(&mut a, |x, y| x.0 < y.0);
+            sort_by(&mut b, |x, y| x.0 < y.0);
+
+            let plan = merge_plan(a_len, b_len, |i, j| b[j].0 < a[i].0);
+            assert_eq!(plan.len(), a_len + b_len);
+            let (mut i, mut j) = (0, 0);
+            let mut followed = Vec::new();
+            for side in plan {
+                match side {
+                    Side::A => {
+                        followed.push(a[i]);
+                        i += 1;
+                    }
+                    Side::B => {
+                        followed.push(b[j]);
+                        j += 1;
+                    }
+                }
+            }
+            let merged = merge_by(a.iter().cloned(), b.iter().cloned(), |x, y| x.0 < y.0)
+                .collect::<Vec<_>>();
+            assert!(followed == merged);
+        }
+    }
+}
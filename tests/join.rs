@@ -0,0 +1,109 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    sort_by, sorted_anti_join, sorted_join, sorted_left_join, sorted_outer_join, sorted_semi_join,
+};
+
+/// A sorted vector of (key, position) pairs.
+fn sorted_keys(len: usize, modulus: u8) -> Vec<(u8, usize)> {
+    let mut v = thread_rng()
+        .gen_iter::<u8>()
+        .map(|x| x % modulus)
+        .take(len)
+        .collect::<Vec<u8>>()
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| (x, i))
+        .collect::<Vec<(u8, usize)>>();
+    sort_by(&mut v, |a, b| a.0 < b.0);
+    v
+}
+
+#[test]
+fn test_sorted_join() {
+    for &(a_len, b_len) in &[(0, 0), (0, 10), (10, 0), (20, 20), (5, 50), (50, 5)] {
+        for &modulus in &[1u8, 4, 32] {
+            let a = sorted_keys(a_len, modulus);
+            let b = sorted_keys(b_len, modulus);
+
+            // nested loop joins in the same order as the merge join
+            let mut inner = Vec::new();
+            let mut left = Vec::new();
+            for x in &a {
+                let matches = b.iter().filter(|y| y.0 == x.0).collect::<Vec<_>>();
+                for &y in &matches {
+                    inner.push((*x, *y));
+                    left.push((*x, Some(*y)));
+                }
+                if matches.is_empty() {
+                    left.push((*x, None));
+                }
+            }
+
+            let joined = sorted_join(&a, &b, |x| x.0, |y| y.0)
+                .map(|(x, y)| (*x, *y))
+                .collect::<Vec<_>>();
+            assert!(joined == inner);
+
+            let joined = sorted_left_join(&a, &b, |x| x.0, |y| y.0)
+                .map(|(x, y)| (*x, y.cloned()))
+                .collect::<Vec<_>>();
+            assert!(joined == left);
+
+            let outer = sorted_outer_join(&a, &b, |x| x.0, |y| y.0)
+                .map(|(x, y)| (x.cloned(), y.cloned()))
+                .collect::<Vec<_>>();
+            let matched = outer
+                .iter()
+                .filter_map(|p| match *p {
+                    (Some(x), Some(y)) => Some((x, y)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            assert!(matched == inner);
+            for y in &b {
+                let unmatched = !a.iter().any(|x| x.0 == y.0);
+                assert_eq!(outer.contains(&(None, Some(*y))), unmatched);
+            }
+            for x in &a {
+                let unmatched = !b.iter().any(|y| y.0 == x.0);
+                assert_eq!(outer.contains(&(Some(*x), None)), unmatched);
+            }
+            // in key order
+            let keys = outer
+                .iter()
+                .map(|p| p.0.or(p.1).unwrap().0)
+                .collect::<Vec<u8>>();
+            assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_sorted_join_different_types() {
+    // names sorted by length, joined to tags keyed by length
+    let names = ["cy", "ann", "bob"];
+    let tags = [(2usize, 'x'), (3, 'y'), (4, 'z')];
+    let joined = sorted_join(&names, &tags, |n| n.len(), |t| t.0)
+        .map(|(n, t)| (*n, t.1))
+        .collect::<Vec<_>>();
+    assert!(joined == [("cy", 'x'), ("ann", 'y'), ("bob", 'y')]);
+}
+
+#[test]
+fn test_sorted_semi_anti_join() {
+    for &(a_len, b_len) in &[(0, 0), (0, 10), (10, 0), (20, 20), (5, 50), (50, 5)] {
+        for &modulus in &[1u8, 4, 32] {
+            let a = sorted_keys(a_len, modulus);
+            let b = sorted_keys(b_len, modulus);
+
+            let matched = |x: &&(u8, usize)| b.iter().any(|y| y.0 == x.0);
+            let semi = sorted_semi_join(&a, &b, |x| x.0, |y| y.0);
+            assert!(semi.eq(a.iter().filter(|x| matched(x))));
+            let anti = sorted_anti_join(&a, &b, |x| x.0, |y| y.0);
+            assert!(anti.eq(a.iter().filter(|x| !matched(x))));
+        }
+    }
+}
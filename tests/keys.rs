@@ -0,0 +1,128 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::{Duration, UNIX_EPOCH};
+
+use rand::{Rng, thread_rng};
+use sortrs::keys;
+
+#[test]
+fn test_signed_keys() {
+    for _ in 0..1000 {
+        let (a, b) = thread_rng().gen::<(i64, i64)>();
+        assert_eq!(a < b, keys::i64_key(a) < keys::i64_key(b));
+        assert_eq!(keys::i64_from_key(keys::i64_key(a)), a);
+        let (a, b) = thread_rng().gen::<(i32, i32)>();
+        assert_eq!(a < b, keys::i32_key(a) < keys::i32_key(b));
+        assert_eq!(keys::i32_from_key(keys::i32_key(a)), a);
+    }
+    // every i8 round trips and keeps its order
+    let all = (-128..128)
+        .map(|x| keys::i8_key(x as i8))
+        .collect::<Vec<u8>>();
+    assert!(all == (0..=255).collect::<Vec<u8>>());
+    assert_eq!(keys::i128_key(i128::MIN), 0);
+    assert_eq!(keys::isize_key(isize::MAX), usize::MAX);
+}
+
+#[test]
+fn test_float_keys() {
+    let ordered = [
+        -f64::NAN,
+        f64::NEG_INFINITY,
+        f64::MIN,
+        -1.0,
+        -f64::MIN_POSITIVE,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        1.0,
+        f64::MAX,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+    let keys = ordered
+        .iter()
+        .map(|&x| keys::f64_key(x))
+        .collect::<Vec<u64>>();
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    for &x in &ordered {
+        assert_eq!(keys::f64_from_key(keys::f64_key(x)).to_bits(), x.to_bits());
+    }
+    for _ in 0..1000 {
+        let (a, b) = thread_rng().gen::<(f32, f32)>();
+        let (a, b) = (a - 0.5, b - 0.5);
+        assert_eq!(a < b, keys::f32_key(a) < keys::f32_key(b));
+        assert_eq!(keys::f32_from_key(keys::f32_key(a)), a);
+    }
+}
+
+#[test]
+fn test_time_and_address_keys() {
+    let d = [
+        Duration::new(0, 0),
+        Duration::new(0, 999_999_999),
+        Duration::new(1, 0),
+        Duration::new(u64::MAX, 999_999_999),
+    ];
+    assert!(d
+        .windows(2)
+        .all(|w| keys::duration_key(w[0]) < keys::duration_key(w[1])));
+
+    let t = [
+        UNIX_EPOCH - Duration::new(10, 1),
+        UNIX_EPOCH - Duration::new(0, 1),
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::new(0, 1),
+        UNIX_EPOCH + Duration::new(10, 0),
+    ];
+    assert!(t
+        .windows(2)
+        .all(|w| keys::system_time_key(w[0]) < keys::system_time_key(w[1])));
+
+    let a = [Ipv4Addr::new(9, 255, 255, 255), Ipv4Addr::new(10, 0, 0, 1)];
+    assert!(keys::ipv4_key(a[0]) < keys::ipv4_key(a[1]));
+    let a = [
+        Ipv6Addr::LOCALHOST,
+        Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1),
+    ];
+    assert!(keys::ipv6_key(a[0]) < keys::ipv6_key(a[1]));
+}
+
+#[test]
+fn test_64_bit_keys() {
+    let d = [
+        Duration::new(0, 0),
+        Duration::new(0, 999_999_999),
+        Duration::new(1, 0),
+        Duration::new(584 * 365 * 24 * 3600, 0),
+    ];
+    assert!(d
+        .windows(2)
+        .all(|w| keys::duration_key64(w[0]) < keys::duration_key64(w[1])));
+    assert_eq!(keys::duration_key64(Duration::new(u64::MAX, 0)), u64::MAX);
+
+    let t = [
+        UNIX_EPOCH - Duration::new(10, 1),
+        UNIX_EPOCH - Duration::new(0, 1),
+        UNIX_EPOCH,
+        UNIX_EPOCH + Duration::new(0, 1),
+        UNIX_EPOCH + Duration::new(10, 0),
+    ];
+    assert!(t
+        .windows(2)
+        .all(|w| keys::system_time_key64(w[0]) < keys::system_time_key64(w[1])));
+    assert_eq!(keys::system_time_key64(UNIX_EPOCH), 1 << 63);
+    let far = Duration::new(400 * 365 * 24 * 3600, 0);
+    assert_eq!(keys::system_time_key64(UNIX_EPOCH + far), u64::MAX);
+
+    for _ in 0..1000 {
+        let (a, b) = thread_rng().gen::<(i64, i64)>();
+        let (a, b) = ((a as i128) << 40, (b as i128) << 40);
+        assert_eq!(
+            a < b,
+            keys::key_halves(keys::i128_key(a)) < keys::key_halves(keys::i128_key(b))
+        );
+    }
+}
@@ -0,0 +1,96 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, kmerge_dedup, kmerge_dedup_by, kmerge_dedup_prioritized_by};
+
+#[test]
+fn test_kmerge_dedup() {
+    for k in 0usize..8 {
+        for _ in 0..20 {
+            let inputs = (0..k)
+                .map(|_| {
+                    let len = thread_rng().gen_range(0, 40);
+                    let mut v = thread_rng()
+                        .gen_iter::<u8>()
+                        .map(|x| x % 32)
+                        .take(len)
+                        .collect::<Vec<u8>>();
+                    introsort(&mut v);
+                    v
+                })
+                .collect::<Vec<Vec<u8>>>();
+            let slices = inputs.iter().map(|v| &v[..]).collect::<Vec<&[u8]>>();
+
+            let mut expected = inputs.concat();
+            introsort(&mut expected);
+            expected.dedup();
+            assert!(kmerge_dedup(&slices) == expected);
+        }
+    }
+}
+
+#[test]
+fn test_kmerge_dedup_keeps_first_source() {
+    // keys 0..16, each input tagged with its index
+    let inputs = (0..5usize)
+        .map(|i| {
+            (0..16usize)
+                .filter(|_| thread_rng().gen())
+                .map(|key| (key, i))
+                .collect::<Vec<(usize, usize)>>()
+        })
+        .collect::<Vec<_>>();
+    let slices = inputs
+        .iter()
+        .map(|v| &v[..])
+        .collect::<Vec<&[(usize, usize)]>>();
+    let merged = kmerge_dedup_by(&slices, |a, b| a.0 < b.0);
+    assert!(merged.windows(2).all(|w| w[0].0 < w[1].0));
+    for key in 0..16 {
+        let first = inputs.iter().position(|v| v.iter().any(|x| x.0 == key));
+        let found = merged.iter().find(|x| x.0 == key).map(|x| x.1);
+        assert_eq!(found, first);
+    }
+}
+
+#[test]
+fn test_kmerge_dedup_prioritized() {
+    // keys 0..16, each input tagged with its index
+    let inputs = (0..5usize)
+        .map(|i| {
+            (0..16usize)
+                .filter(|_| thread_rng().gen())
+                .map(|key| (key, i))
+                .collect::<Vec<(usize, usize)>>()
+        })
+        .collect::<Vec<_>>();
+    let slices = inputs
+        .iter()
+        .map(|v| &v[..])
+        .collect::<Vec<&[(usize, usize)]>>();
+
+    // the last input wins
+    let merged = kmerge_dedup_prioritized_by(&slices, |a, b| a.0 < b.0, |i, j| i > j);
+    assert!(merged.windows(2).all(|w| w[0].0 < w[1].0));
+    for key in 0..16 {
+        let last = inputs.iter().rposition(|v| v.iter().any(|x| x.0 == key));
+        let found = merged.iter().find(|x| x.0 == key).map(|x| x.1);
+        assert_eq!(found, last);
+    }
+
+    // an arbitrary ranking of the inputs
+    let rank = [2, 4, 0, 3, 1];
+    let merged = kmerge_dedup_prioritized_by(&slices, |a, b| a.0 < b.0, |i, j| rank[i] < rank[j]);
+    for key in 0..16 {
+        let best = (0..5)
+            .filter(|&i| inputs[i].iter().any(|x| x.0 == key))
+            .min_by_key(|&i| rank[i]);
+        let found = merged.iter().find(|x| x.0 == key).map(|x| x.1);
+        assert_eq!(found, best);
+    }
+    assert!(
+        kmerge_dedup_prioritized_by(&slices, |a, b| a.0 < b.0, |i, j| i < j)
+            == kmerge_dedup_by(&slices, |a, b| a.0 < b.0)
+    );
+}
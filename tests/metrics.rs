@@ -0,0 +1,101 @@
+#![cfg(feature = "metrics")]
+
+extern crate metrics;
+extern crate rand;
+extern crate sortrs;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use rand::{Rng, thread_rng};
+use sortrs::{
+    introsort, introsort_with_config_by, partial_sort, quantiles, radix_sort_by_key,
+    sort_by_key_slice, sort_strided, timsort, IntrosortConfig, SortedIdSet,
+};
+
+/// A recorder that only keeps the totals of its counters.
+#[derive(Default)]
+struct Counters(Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+impl Counters {
+    fn get(&self, name: &str) -> u64 {
+        self.0
+            .lock()
+            .unwrap()
+            .get(name)
+            .map_or(0, |c| c.load(Ordering::Relaxed))
+    }
+}
+
+impl Recorder for Counters {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.0.lock().unwrap();
+        let counter = counters.entry(key.name().to_string()).or_default();
+        Counter::from_arc(counter.clone())
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+/// Runs `f` with a fresh recorder and returns the elements sorted and the
+/// introsort fallbacks it counted.
+fn count<F: FnOnce()>(f: F) -> (u64, u64) {
+    let counters = Counters::default();
+    metrics::with_local_recorder(&counters, f);
+    (
+        counters.get("sortrs.elements_sorted"),
+        counters.get("sortrs.introsort_fallbacks"),
+    )
+}
+
+fn random_vec(len: usize) -> Vec<u32> {
+    thread_rng().gen_iter::<u32>().take(len).collect()
+}
+
+#[test]
+fn test_metrics_elements_sorted() {
+    let v = random_vec(100);
+    assert_eq!(count(|| introsort(&mut v.clone())), (100, 0));
+    assert_eq!(count(|| timsort(&mut v.clone())), (100, 0));
+    assert_eq!(
+        count(|| radix_sort_by_key(&mut v.clone(), |&x| x as u64)),
+        (100, 0)
+    );
+    assert_eq!(count(|| partial_sort(&mut v.clone(), 10)), (10, 0));
+    // only the 50 elements of one channel are sorted
+    assert_eq!(count(|| sort_strided(&mut v.clone(), 2, 1)), (50, 0));
+    // the index sort behind it is not counted again
+    let mut keys = v.clone();
+    assert_eq!(
+        count(|| sort_by_key_slice(&mut v.clone(), &mut keys)),
+        (100, 0)
+    );
+    // selection sorts its ranks but sorts none of the data
+    let mut f = v.iter().map(|&x| x as f64).collect::<Vec<f64>>();
+    assert_eq!(count(|| drop(quantiles(&mut f, &[0.1, 0.5, 0.9]))), (0, 0));
+    // nor does building a set from a scratch copy of the IDs
+    assert_eq!(count(|| drop(SortedIdSet::from_ids(&v))), (0, 0));
+}
+
+#[test]
+fn test_metrics_introsort_fallbacks() {
+    let v = random_vec(100);
+    let config = IntrosortConfig {
+        depth_multiplier: 0,
+        ..Default::default()
+    };
+    let sorted = count(|| introsort_with_config_by(&mut v.clone(), &config, |a, b| a.lt(b)));
+    assert_eq!(sorted, (100, 1));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "offload")]
+
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::offload::{sort_by_key, CpuOffload, Offload};
+use sortrs::sort_by;
+
+/// A backend that counts submissions and sorts with the standard library.
+struct CountingOffload {
+    submitted: usize,
+}
+
+impl Offload for CountingOffload {
+    fn sort_keys(&mut self, keys: &[u64]) -> Vec<usize> {
+        self.submitted += 1;
+        let mut perm = (0..keys.len()).collect::<Vec<usize>>();
+        perm.sort_by_key(|&i| keys[i]);
+        perm
+    }
+}
+
+#[test]
+fn test_sort_by_key() {
+    let mut counting = CountingOffload { submitted: 0 };
+    for &len in &[0usize, 1, 2, 100, 1000] {
+        for &modulus in &[1u64, 16, u64::MAX] {
+            let original = thread_rng()
+                .gen_iter::<u64>()
+                .map(|x| x % modulus)
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u64, usize)>>();
+            let mut expected = original.clone();
+            sort_by(&mut expected, |a, b| a.0 < b.0);
+
+            let mut v = original.clone();
+            sort_by_key(&mut v, |x| x.0, &mut CpuOffload);
+            assert!(v == expected);
+
+            let mut v = original.clone();
+            sort_by_key(&mut v, |x| x.0, &mut counting);
+            assert!(v == expected);
+        }
+    }
+    // slices too short to need sorting are not submitted
+    assert_eq!(counting.submitted, 9);
+}
+
+struct BrokenOffload;
+
+impl Offload for BrokenOffload {
+    fn sort_keys(&mut self, keys: &[u64]) -> Vec<usize> {
+        vec![0; keys.len()]
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_sort_by_key_not_a_permutation() {
+    sort_by_key(&mut [2u64, 1], |&x| x, &mut BrokenOffload);
+}
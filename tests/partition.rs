@@ -0,0 +1,82 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, partition3_stable, partition_at, partition_at_by, Class};
+
+fn classify(x: &(u8, usize)) -> Class {
+    match x.0 % 3 {
+        0 => Class::Low,
+        1 => Class::Mid,
+        _ => Class::High,
+    }
+}
+
+#[test]
+fn test_partition3_stable() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u8, usize)>>();
+            let original = v.clone();
+
+            let (mid, high) = partition3_stable(&mut v, classify);
+            // a stable partition is a stable sort by class
+            let mut expected = original.clone();
+            expected.sort_by_key(classify);
+            assert!(v == expected);
+            assert!(v[..mid].iter().all(|x| classify(x) == Class::Low));
+            assert!(v[mid..high].iter().all(|x| classify(x) == Class::Mid));
+            assert!(v[high..].iter().all(|x| classify(x) == Class::High));
+        }
+    }
+}
+
+#[test]
+fn test_partition_at() {
+    for len in 1usize..80 {
+        for &modulus in &[2u8, 8, 255] {
+            let v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % modulus)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut sorted = v.clone();
+            introsort(&mut sorted);
+            for pivot in 0..len {
+                let mut v1 = v.clone();
+                let key = v1[pivot];
+                let (mid, equal) = partition_at(&mut v1, pivot);
+                assert!(equal >= 1 && mid + equal <= len);
+                assert!(v1[..mid].iter().all(|&x| x < key));
+                assert!(v1[mid..mid + equal].iter().all(|&x| x == key));
+                assert!(v1[mid + equal..].iter().all(|&x| x > key));
+                introsort(&mut v1);
+                assert!(v1 == sorted);
+            }
+        }
+    }
+
+    let mut v = [(); 5];
+    assert_eq!(partition_at(&mut v, 2), (0, 5));
+}
+
+#[test]
+fn test_partition_at_by() {
+    let mut v = (0..50usize).map(|x| x % 7).collect::<Vec<usize>>();
+    let (mid, equal) = partition_at_by(&mut v, 10, |a, b| b.lt(a));
+    assert_eq!((mid, equal), (21, 7));
+    assert!(v[..mid].iter().all(|&x| x > 3));
+    assert!(v[mid..mid + equal].iter().all(|&x| x == 3));
+    assert!(v[mid + equal..].iter().all(|&x| x < 3));
+}
+
+#[test]
+#[should_panic]
+fn test_partition_at_out_of_bounds() {
+    partition_at(&mut [1, 2, 3], 3);
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "pool")]
+
+extern crate rand;
+extern crate sortrs;
+
+use std::thread;
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, pool, sort_by, timsort, timsort_by};
+
+#[test]
+fn test_pool_reuse() {
+    pool::clear();
+    assert_eq!(pool::capacity(), 0);
+    let mut v = thread_rng()
+        .gen_iter::<u32>()
+        .take(1000)
+        .collect::<Vec<u32>>();
+    let mut expected = v.clone();
+    introsort(&mut expected);
+    timsort(&mut v);
+    assert!(v == expected);
+    let capacity = pool::capacity();
+    // at most half the slice is ever held in the buffer
+    assert!(capacity > 0 && capacity <= 500 * 4);
+
+    // a smaller sort keeps the block, a larger one grows it
+    let mut small = thread_rng()
+        .gen_iter::<u32>()
+        .take(200)
+        .collect::<Vec<u32>>();
+    timsort(&mut small);
+    assert!(small.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(pool::capacity(), capacity);
+    let mut wide = thread_rng()
+        .gen_iter::<(u64, u64)>()
+        .take(1000)
+        .collect::<Vec<(u64, u64)>>();
+    sort_by(&mut wide, |a, b| a.0 < b.0);
+    assert!(wide.windows(2).all(|w| w[0].0 <= w[1].0));
+    assert!(pool::capacity() > capacity);
+
+    pool::clear();
+    assert_eq!(pool::capacity(), 0);
+
+    // other threads have their own pools
+    thread::spawn(|| assert_eq!(pool::capacity(), 0))
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn test_pool_nested_sort() {
+    // a comparator that sorts too must not share the outer sort's buffer
+    let mut v = thread_rng()
+        .gen_iter::<u16>()
+        .take(300)
+        .map(|x| {
+            thread_rng()
+                .gen_iter::<u16>()
+                .take(100 + x as usize % 100)
+                .collect::<Vec<u16>>()
+        })
+        .collect::<Vec<Vec<u16>>>();
+    timsort_by(&mut v, |a, b| {
+        let (mut a, mut b) = (a.clone(), b.clone());
+        timsort(&mut a);
+        timsort(&mut b);
+        a.len() < b.len()
+    });
+    assert!(v.windows(2).all(|w| w[0].len() <= w[1].len()));
+}
@@ -0,0 +1,72 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{radix_sort_by_key, radix_sort_multi_key, sort_by};
+
+#[test]
+fn test_radix_sort_by_key() {
+    for len in 0usize..200 {
+        for &mask in &[0u64, 0x7, 0xff00, 0xffff_0000_0000_ffff, !0] {
+            // decorate each key with its original index to check stability
+            let v = thread_rng()
+                .gen_iter::<u64>()
+                .map(|x| x & mask)
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u64, usize)>>();
+            let mut expected = v.clone();
+            sort_by(&mut expected, |a, b| a.0 < b.0);
+
+            let mut v1 = v.clone();
+            radix_sort_by_key(&mut v1, |x| x.0);
+            assert!(v1 == expected);
+        }
+    }
+}
+
+#[test]
+fn test_radix_sort_chained() {
+    // sorting by the minor key then the major key sorts by both
+    let mut v = thread_rng()
+        .gen_iter::<(u8, u8)>()
+        .take(500)
+        .collect::<Vec<(u8, u8)>>();
+    let mut expected = v.clone();
+    sort_by(&mut expected, |a, b| a < b);
+
+    radix_sort_by_key(&mut v, |x| x.1 as u64);
+    radix_sort_by_key(&mut v, |x| x.0 as u64);
+    assert!(v == expected);
+}
+
+#[test]
+fn test_radix_sort_multi_key() {
+    for len in 0usize..200 {
+        let v = thread_rng()
+            .gen_iter::<(u8, u16, u8)>()
+            .map(|x| (x.0 % 4, x.1 % 8, x.2 % 4))
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<((u8, u16, u8), usize)>>();
+        let mut expected = v.clone();
+        sort_by(&mut expected, |a, b| a.0 < b.0);
+
+        let mut v1 = v.clone();
+        radix_sort_multi_key(
+            &mut v1,
+            &[
+                |x: &((u8, u16, u8), usize)| (x.0).0 as u64,
+                |x: &((u8, u16, u8), usize)| (x.0).1 as u64,
+                |x: &((u8, u16, u8), usize)| (x.0).2 as u64,
+            ],
+        );
+        assert!(v1 == expected);
+
+        // no keys leaves the slice as it is
+        radix_sort_multi_key(&mut v1, &[]);
+        assert!(v1 == expected);
+    }
+}
@@ -0,0 +1,62 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::ReorderBuffer;
+
+#[test]
+fn test_reorder_buffer() {
+    for &capacity in &[1usize, 2, 8, 64] {
+        // shuffle within blocks of the capacity so every item fits the window
+        let mut seqs = (0..1000u64).collect::<Vec<u64>>();
+        for block in seqs.chunks_mut(capacity) {
+            thread_rng().shuffle(block);
+        }
+
+        let mut buffer = ReorderBuffer::new(capacity);
+        let mut released = Vec::new();
+        for &seq in &seqs {
+            assert!(buffer.insert(seq, seq * 10).is_ok());
+            while let Some(x) = buffer.pop() {
+                released.push(x);
+            }
+            assert!(buffer.len() <= capacity);
+        }
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.next_seq(), 1000);
+        assert!(released == (0..1000u64).map(|x| x * 10).collect::<Vec<u64>>());
+    }
+}
+
+#[test]
+fn test_reorder_buffer_window() {
+    let mut buffer = ReorderBuffer::starting_at(4, 10);
+    assert_eq!(buffer.capacity(), 4);
+    // before the window, and past its end
+    assert_eq!(buffer.insert(9, 'x'), Err('x'));
+    assert_eq!(buffer.insert(14, 'x'), Err('x'));
+    assert!(buffer.insert(13, 'd').is_ok());
+    assert!(buffer.insert(11, 'b').is_ok());
+    assert!(buffer.insert(11, 'z').is_ok());
+    assert!(buffer.insert(12, 'c').is_ok());
+    // full
+    assert_eq!(buffer.insert(10, 'a'), Err('a'));
+    assert_eq!(buffer.pop(), None);
+    assert_eq!(buffer.len(), 4);
+}
+
+#[test]
+fn test_reorder_buffer_duplicates() {
+    let mut buffer = ReorderBuffer::new(8);
+    for &(seq, x) in &[(2, 'c'), (1, 'b'), (2, 'z'), (0, 'a'), (1, 'y')] {
+        buffer.insert(seq, x).unwrap();
+    }
+    let mut released = Vec::new();
+    while let Some(x) = buffer.pop() {
+        released.push(x);
+    }
+    assert_eq!(released.len(), 3);
+    assert_eq!(released[0], 'a');
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.insert(1, 'x'), Err('x'));
+}
@@ -0,0 +1,56 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{move_range, rotate_range_left, rotate_range_right};
+
+#[test]
+fn test_rotate_range() {
+    for len in 0usize..30 {
+        for _ in 0..20 {
+            let v = (0..len).collect::<Vec<usize>>();
+            let start = thread_rng().gen_range(0, len + 1);
+            let end = thread_rng().gen_range(start, len + 1);
+            let k = thread_rng().gen_range(0, end - start + 1);
+
+            let mut v1 = v.clone();
+            rotate_range_left(&mut v1, start..end, k);
+            let mut expected = v.clone();
+            expected[start..end].rotate_left(k);
+            assert!(v1 == expected);
+
+            let mut v1 = v.clone();
+            rotate_range_right(&mut v1, start..end, k);
+            let mut expected = v.clone();
+            expected[start..end].rotate_right(k);
+            assert!(v1 == expected);
+        }
+    }
+}
+
+#[test]
+fn test_move_range() {
+    for len in 0usize..30 {
+        for _ in 0..20 {
+            let v = (0..len).collect::<Vec<usize>>();
+            let start = thread_rng().gen_range(0, len + 1);
+            let end = thread_rng().gen_range(start, len + 1);
+            let to = thread_rng().gen_range(0, len - (end - start) + 1);
+
+            let mut v1 = v.clone();
+            move_range(&mut v1, start..end, to);
+            let mut expected = v.clone();
+            let block = expected.drain(start..end).collect::<Vec<usize>>();
+            for (i, x) in block.into_iter().enumerate() {
+                expected.insert(to + i, x);
+            }
+            assert!(v1 == expected);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_move_range_out_of_bounds() {
+    move_range(&mut [0, 1, 2], 0..2, 2);
+}
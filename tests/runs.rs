@@ -0,0 +1,284 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    approx_sort, approx_sort_by, detect_order, detect_order_by, introsort, is_permutation_of,
+    merge_by, repair_sorted, repair_sorted_by, reverse_descending_runs, reverse_descending_runs_by,
+    sort_by, sort_presorted, sort_presorted_by, split_into_sorted_chunks,
+    split_into_sorted_chunks_by, Order,
+};
+
+#[test]
+fn test_reverse_descending_runs() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let original = v.clone();
+
+            // reverse each maximal strictly descending run by hand
+            let mut expected = Vec::new();
+            let mut expected_runs = 0;
+            for run in original.chunk_by(|a, b| b < a) {
+                expected.extend(run.iter().rev());
+                if run.len() > 1 {
+                    expected_runs += 1;
+                }
+            }
+
+            assert_eq!(reverse_descending_runs(&mut v), expected_runs);
+            assert!(v == expected);
+
+            let mut v1 = original.clone();
+            assert_eq!(reverse_descending_runs_by(&mut v1, |a, b| b.lt(a)), {
+                let mut v2 = original.clone();
+                v2.reverse();
+                reverse_descending_runs(&mut v2)
+            });
+        }
+    }
+
+    let mut v = [3, 2, 1];
+    assert_eq!(reverse_descending_runs(&mut v), 1);
+    assert!(v == [1, 2, 3]);
+    assert_eq!(reverse_descending_runs(&mut v), 0);
+    // equal neighbours end a run
+    let mut v = [2, 2, 1, 1];
+    assert_eq!(reverse_descending_runs(&mut v), 1);
+    assert!(v == [2, 1, 2, 1]);
+}
+
+#[test]
+fn test_detect_order() {
+    for len in 0usize..40 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let original = v.clone();
+            let mut sorted = v.clone();
+            introsort(&mut sorted);
+
+            // whatever is detected must be fixed by the matching operation
+            let order = detect_order(&v);
+            let mut fixed = v.clone();
+            match order {
+                Order::Ascending => {}
+                Order::Descending => fixed.reverse(),
+                Order::Rotated(k) => {
+                    assert!(k > 0 && k < len);
+                    fixed.rotate_left(k);
+                }
+                Order::Unsorted => assert!(v != sorted),
+            }
+            if order != Order::Unsorted {
+                assert!(fixed == sorted);
+            }
+            assert_eq!(sort_presorted(&mut v), order);
+            assert!(v == sorted);
+
+            // known shapes are detected
+            assert_eq!(detect_order(&sorted), Order::Ascending);
+            let mut reversed = sorted.clone();
+            reversed.reverse();
+            if sorted.first() != sorted.last() {
+                assert_eq!(detect_order(&reversed), Order::Descending);
+                assert_eq!(detect_order_by(&reversed, |a, b| b.lt(a)), Order::Ascending);
+            }
+            // with distinct ends the wrap point is a strict descent
+            if len > 2 && sorted[0] < sorted[len - 1] {
+                let mut rotated = sorted.clone();
+                let k = thread_rng().gen_range(1, len);
+                rotated.rotate_right(k);
+                // only two distinct values can rotate into a descending run
+                let expected = if rotated.windows(2).all(|w| w[0] >= w[1]) {
+                    Order::Descending
+                } else if rotated[len - 1] < rotated[0] {
+                    Order::Rotated(k)
+                } else {
+                    // equal elements either side of the wrap point
+                    Order::Unsorted
+                };
+                assert_eq!(detect_order(&rotated), expected);
+            }
+
+            let mut v = original;
+            sort_presorted_by(&mut v, |a, b| b.lt(a));
+            assert!(v.windows(2).all(|w| w[0] >= w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_sort_presorted_stable_rotation() {
+    // the ends are equal, so rotating would swap (1, 'b') and (1, 'a')
+    let mut v = [(1, 'b'), (0, 'a'), (1, 'a')];
+    assert_eq!(detect_order_by(&v, |a, b| a.0 < b.0), Order::Unsorted);
+    sort_presorted_by(&mut v, |a, b| a.0 < b.0);
+    assert!(v.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    for len in 2usize..40 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u8, usize)>>();
+            sort_by(&mut v, |a, b| a.0 < b.0);
+            let sorted = v.clone();
+            let k = thread_rng().gen_range(1, len);
+            v.rotate_right(k);
+            // a rotation is only undone when that keeps equal keys in order
+            if let Order::Rotated(_) = sort_presorted_by(&mut v, |a, b| a.0 < b.0) {
+                assert!(v == sorted);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_approx_sort() {
+    for len in 0usize..100 {
+        for &k in &[0usize, 1, 2, 5, 16, 200] {
+            // displace elements of a sorted sequence by at most k positions by
+            // shuffling within blocks of k + 1
+            let sorted = (0..len).collect::<Vec<usize>>();
+            let mut v = sorted.clone();
+            for block in v.chunks_mut(k + 1) {
+                thread_rng().shuffle(block);
+            }
+            assert!(v.iter().enumerate().all(|(i, &x)| i.max(x) - i.min(x) <= k));
+
+            let mut v1 = v.clone();
+            approx_sort(&mut v1, k);
+            assert!(v1 == sorted);
+
+            let mut reversed = v.iter().map(|&x| len - x).collect::<Vec<usize>>();
+            approx_sort_by(&mut reversed, k, |a, b| b.lt(a));
+            assert!(reversed.windows(2).all(|w| w[0] > w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_split_into_sorted_chunks() {
+    for len in 0usize..200 {
+        for &target in &[1usize, 2, 3, 8, 300] {
+            // runs of random length
+            let mut v = Vec::with_capacity(len);
+            while v.len() < len {
+                let run = thread_rng().gen_range(1, 40);
+                // equal keys within runs, tagged with their position
+                let start = v.len();
+                v.extend((0..run).map(|x| (x / 3, start + x)));
+            }
+            v.truncate(len);
+
+            let bounds = split_into_sorted_chunks_by(&v, target, |a, b| a.0 < b.0);
+            assert!(bounds == split_into_sorted_chunks(&v, target));
+            assert_eq!(bounds.first(), Some(&0));
+            assert_eq!(bounds.last(), if len == 0 { Some(&0) } else { Some(&len) });
+            assert!(bounds.len() - 1 <= target.max(1));
+            assert!(bounds.windows(2).all(|w| w[0] < w[1]));
+
+            // sorting each piece and merging them is a stable sort
+            let mut expected = v.clone();
+            sort_by(&mut expected, |a, b| a.0 < b.0);
+            let mut merged = Vec::new();
+            for w in bounds.windows(2) {
+                let mut piece = v[w[0]..w[1]].to_vec();
+                sort_by(&mut piece, |a, b| a.0 < b.0);
+                merged = merge_by(merged, piece, |a, b| a.0 < b.0).collect();
+            }
+            assert!(merged == expected);
+        }
+    }
+}
+
+#[test]
+fn test_split_into_sorted_chunks_at_runs() {
+    // runs of 100 split into 10 pieces cut exactly at the runs, even when
+    // the runs are offset from the even cuts
+    for offset in 0usize..20 {
+        let v = (0..1000 + offset)
+            .map(|x| (x + 100 - offset) % 100)
+            .collect::<Vec<usize>>();
+        let bounds = split_into_sorted_chunks(&v, 10);
+        for &cut in &bounds[1..bounds.len() - 1] {
+            assert!(v[cut] < v[cut - 1]);
+        }
+    }
+}
+
+#[test]
+fn test_repair_sorted() {
+    for &len in &[0usize, 1, 2, 10, 100, 1000] {
+        for displaced in 0usize..6 {
+            let mut v = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| x % 64)
+                .take(len)
+                .collect::<Vec<u32>>();
+            introsort(&mut v);
+            // overwrite a few elements with new values
+            for _ in 0..displaced.min(len) {
+                let i = thread_rng().gen_range(0, len);
+                v[i] = thread_rng().gen::<u32>() % 64;
+            }
+            let original = v.clone();
+            let mut expected = v.clone();
+            introsort(&mut expected);
+
+            let moved = repair_sorted(&mut v, 2 * displaced);
+            assert!(v == expected);
+            // the untouched elements are in order, so at most the changed
+            // ones are set aside
+            assert!(moved.unwrap() <= displaced);
+
+            let mut v = original.clone();
+            expected.reverse();
+            v.reverse();
+            repair_sorted_by(&mut v, 2 * displaced, |a, b| b.lt(a));
+            assert!(v == expected);
+        }
+    }
+}
+
+#[test]
+fn test_repair_sorted_fallback() {
+    // long enough that random values are never within 3 of sorted
+    for &len in &[100usize, 1000] {
+        let mut v = thread_rng()
+            .gen_iter::<u32>()
+            .take(len)
+            .collect::<Vec<u32>>();
+        let original = v.clone();
+        assert_eq!(repair_sorted(&mut v, 3), None);
+        assert!(v.windows(2).all(|w| w[0] <= w[1]));
+        assert!(is_permutation_of(&v, &original));
+    }
+}
+
+#[test]
+fn test_repair_sorted_inconsistent_comparator() {
+    // a comparator that answers at random must not take the merge out of
+    // bounds, the result is just not sorted
+    for len in 0usize..100 {
+        for _ in 0..20 {
+            let mut v = (0..len as u32)
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>();
+            let original = v.clone();
+            repair_sorted_by(&mut v, len, |_, _| thread_rng().gen_weighted_bool(4));
+            assert!(is_permutation_of(&v, &original));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::ops::Bound;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    contains_sorted_by_key, find_sorted_mut, intersect_sorted, intersect_sorted_by, introsort,
+    introsort_by, position_sorted_by_key, range_sorted, select_nth_from_sorted,
+    select_nth_from_sorted_by, split_sorted, split_sorted_by,
+};
+
+#[test]
+fn test_split_sorted() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let key = thread_rng().gen::<u8>() % 18;
+
+            introsort(&mut v);
+            let (less, equal, greater) = split_sorted(&v, &key);
+            assert_eq!(less.len() + equal.len() + greater.len(), len);
+            assert!(less.iter().all(|&x| x < key));
+            assert!(equal.iter().all(|&x| x == key));
+            assert!(greater.iter().all(|&x| x > key));
+
+            introsort_by(&mut v, |a, b| b.lt(a));
+            let (greater, equal, less) = split_sorted_by(&v, &key, |a, b| b.lt(a));
+            assert_eq!(less.len() + equal.len() + greater.len(), len);
+            assert!(less.iter().all(|&x| x < key));
+            assert!(equal.iter().all(|&x| x == key));
+            assert!(greater.iter().all(|&x| x > key));
+        }
+    }
+}
+
+#[test]
+fn test_range_sorted() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let lo = thread_rng().gen::<u8>() % 18;
+            let hi = thread_rng().gen::<u8>() % 18;
+
+            introsort(&mut v);
+            let key = |x: &u8| *x as u32;
+            let (lo32, hi32) = (lo as u32, hi as u32);
+            let r = range_sorted(&v, lo32..hi32, key);
+            assert!(
+                r == &v
+                    .iter()
+                    .cloned()
+                    .filter(|&x| x >= lo && x < hi)
+                    .collect::<Vec<_>>()[..]
+            );
+            let r = range_sorted(&v, lo32..=hi32, key);
+            assert!(
+                r == &v
+                    .iter()
+                    .cloned()
+                    .filter(|&x| x >= lo && x <= hi)
+                    .collect::<Vec<_>>()[..]
+            );
+            let r = range_sorted(&v, lo32.., key);
+            assert!(r == &v.iter().cloned().filter(|&x| x >= lo).collect::<Vec<_>>()[..]);
+            let r = range_sorted(&v, ..hi32, key);
+            assert!(r == &v.iter().cloned().filter(|&x| x < hi).collect::<Vec<_>>()[..]);
+            let r = range_sorted(&v, (Bound::Excluded(lo32), Bound::Unbounded), key);
+            assert!(r == &v.iter().cloned().filter(|&x| x > lo).collect::<Vec<_>>()[..]);
+            assert!(range_sorted(&v, .., key) == &v[..]);
+        }
+    }
+}
+
+fn sorted_u8s(len: usize, modulus: u8) -> Vec<u8> {
+    let mut v = thread_rng()
+        .gen_iter::<u8>()
+        .map(|x| x % modulus)
+        .take(len)
+        .collect::<Vec<u8>>();
+    introsort(&mut v);
+    v
+}
+
+#[test]
+fn test_select_nth_from_sorted() {
+    let mut rng = thread_rng();
+    for &parts_len in &[0usize, 1, 2, 5, 16] {
+        for &modulus in &[1u8, 4, 255] {
+            let shards = (0..parts_len)
+                .map(|_| sorted_u8s(rng.gen_range(0, 100), modulus))
+                .collect::<Vec<Vec<u8>>>();
+            let parts = shards.iter().map(|s| &s[..]).collect::<Vec<&[u8]>>();
+            let mut merged = shards.concat();
+            introsort(&mut merged);
+            for (n, x) in merged.iter().enumerate() {
+                assert_eq!(select_nth_from_sorted(&parts, n), Some(x));
+            }
+            assert_eq!(select_nth_from_sorted(&parts, merged.len()), None);
+
+            let desc = shards
+                .iter()
+                .map(|s| s.iter().rev().cloned().collect::<Vec<u8>>())
+                .collect::<Vec<Vec<u8>>>();
+            let parts = desc.iter().map(|s| &s[..]).collect::<Vec<&[u8]>>();
+            for (n, x) in merged.iter().rev().enumerate() {
+                assert_eq!(select_nth_from_sorted_by(&parts, n, |a, b| b < a), Some(x));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_select_nth_from_sorted_inconsistent() {
+    // these must return rather than loop forever
+    let a = [1, 2, 3];
+    let b = [4, 5];
+    let parts: [&[i32]; 2] = [&a, &b];
+    for n in 0..5 {
+        assert!(select_nth_from_sorted_by(&parts, n, |_, _| true).is_some());
+        assert!(select_nth_from_sorted_by(&parts, n, |_, _| false).is_some());
+        assert!(select_nth_from_sorted_by(&parts, n, |a, b| a <= b).is_some());
+    }
+}
+
+#[test]
+fn test_intersect_sorted() {
+    for &(a_len, b_len) in &[(0, 0), (0, 10), (5, 5), (3, 200), (200, 3), (100, 120)] {
+        for &modulus in &[4u8, 64, 255] {
+            let a = sorted_u8s(a_len, modulus);
+            let b = sorted_u8s(b_len, modulus);
+
+            // multiset intersection by counting
+            let mut expected = Vec::new();
+            for x in 0..modulus {
+                let m = a.iter().filter(|&&y| y == x).count();
+                let n = b.iter().filter(|&&y| y == x).count();
+                expected.extend((0..m.min(n)).map(|_| x));
+            }
+            assert!(intersect_sorted(&a, &b) == expected);
+            assert!(intersect_sorted(&b, &a) == expected);
+        }
+    }
+}
+
+#[test]
+fn test_intersect_sorted_by_keeps_a() {
+    let a = (0..10usize).map(|x| (x * 3, 'a')).collect::<Vec<_>>();
+    let b = (0..1000usize).map(|x| (x, 'b')).collect::<Vec<_>>();
+    let expected = a.clone();
+    assert!(intersect_sorted_by(&a, &b, |x, y| x.0 < y.0) == expected);
+    let expected = b
+        .iter()
+        .filter(|x| x.0 % 3 == 0 && x.0 < 30)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert!(intersect_sorted_by(&b, &a, |x, y| x.0 < y.0) == expected);
+}
+
+#[test]
+fn test_position_sorted_by_key() {
+    for len in 0usize..50 {
+        let mut v = sorted_u8s(len, 16)
+            .into_iter()
+            .map(|x| (x, 0u32))
+            .collect::<Vec<(u8, u32)>>();
+        for key in 0u8..18 {
+            let expected = v.iter().position(|x| x.0 == key);
+            assert_eq!(position_sorted_by_key(&v, &key, |x| x.0), expected);
+            assert_eq!(
+                contains_sorted_by_key(&v, &key, |x| x.0),
+                expected.is_some()
+            );
+            if let Some(x) = find_sorted_mut(&mut v, &key, |x| x.0) {
+                x.1 += 1;
+            }
+        }
+        // only the first of each key was found
+        for (i, x) in v.iter().enumerate() {
+            assert_eq!(x.1 == 1, i == 0 || v[i - 1].0 != x.0);
+        }
+    }
+}
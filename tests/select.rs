@@ -0,0 +1,357 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    extremes_k, extremes_k_by, interquartile_range, introsort, max_by_lt, median, median_by,
+    min_by_lt, minmax_by_lt, quantiles, quantiles_by, select_nth_unstable, select_nth_unstable_by,
+    select_nth_unstable_by_key, select_nth_unstable_equal, select_nth_unstable_equal_by, top_k,
+    top_k_by, trimmed_mean, SlidingMedian,
+};
+use std::cell::Cell;
+
+#[test]
+fn test_sliding_median() {
+    for window in 1usize..10 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 16)
+            .take(100)
+            .collect::<Vec<u8>>();
+
+        let mut median = SlidingMedian::with_capacity(window);
+        assert!(median.is_empty());
+        assert_eq!(median.median(), None);
+        for (i, &x) in v.iter().enumerate() {
+            median.push(x);
+            if median.len() > window {
+                assert_eq!(median.pop(), Some(v[i - window]));
+            }
+
+            let mut expected = v[(i + 1).saturating_sub(window)..i + 1].to_vec();
+            introsort(&mut expected);
+            assert_eq!(median.len(), expected.len());
+            assert_eq!(median.median(), Some(&expected[(expected.len() - 1) / 2]));
+            assert_eq!(median.upper_median(), Some(&expected[expected.len() / 2]));
+        }
+        while median.pop().is_some() {}
+        assert!(median.is_empty());
+    }
+}
+
+#[test]
+fn test_sliding_median_nan() {
+    let mut median = SlidingMedian::new();
+    for &x in &[1.0, 2.0, f64::NAN, 0.0] {
+        median.push(x);
+    }
+    assert_eq!(median.pop(), Some(1.0));
+    assert_eq!(median.pop(), Some(2.0));
+    assert!(median.pop().unwrap().is_nan());
+    assert_eq!(median.median(), Some(&0.0));
+    assert_eq!(median.pop(), Some(0.0));
+    assert!(median.is_empty());
+    assert_eq!(median.median(), None);
+}
+
+fn sorted_floats(len: usize, modulus: u32) -> (Vec<f64>, Vec<f64>) {
+    let v = thread_rng()
+        .gen_iter::<u32>()
+        .map(|x| (x % modulus) as f64)
+        .take(len)
+        .collect::<Vec<f64>>();
+    let mut sorted = v.clone();
+    introsort(&mut sorted);
+    (v, sorted)
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+#[test]
+fn test_trimmed_mean() {
+    for &len in &[1usize, 2, 3, 10, 17, 100, 1000] {
+        for &modulus in &[3u32, 1000] {
+            for &fraction in &[0.0, 0.1, 0.25, 0.49] {
+                let (mut v, sorted) = sorted_floats(len, modulus);
+                let trim = (fraction * len as f64).floor() as usize;
+                let kept = &sorted[trim..len - trim];
+                let expected = kept.iter().sum::<f64>() / kept.len() as f64;
+                let mean = trimmed_mean(&mut v, fraction).unwrap();
+                assert!((mean - expected).abs() < 1e-9);
+            }
+        }
+    }
+    assert_eq!(trimmed_mean(&mut [], 0.1), None);
+}
+
+#[test]
+#[should_panic]
+fn test_trimmed_mean_bad_fraction() {
+    trimmed_mean(&mut [1.0, 2.0], 0.5);
+}
+
+#[test]
+fn test_interquartile_range() {
+    for &len in &[1usize, 2, 3, 4, 10, 17, 100, 1000] {
+        for &modulus in &[3u32, 1000] {
+            let (mut v, sorted) = sorted_floats(len, modulus);
+            let expected = percentile(&sorted, 0.75) - percentile(&sorted, 0.25);
+            let iqr = interquartile_range(&mut v).unwrap();
+            assert!((iqr - expected).abs() < 1e-9);
+        }
+    }
+    assert_eq!(interquartile_range(&mut []), None);
+}
+
+#[test]
+fn test_select_nth_unstable() {
+    for len in 1usize..100 {
+        for &modulus in &[1u32, 4, 1000] {
+            let original = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| x % modulus)
+                .take(len)
+                .collect::<Vec<u32>>();
+            let mut sorted = original.clone();
+            introsort(&mut sorted);
+
+            for index in 0..len {
+                let mut v = original.clone();
+                let (left, nth, right) = select_nth_unstable(&mut v, index);
+                assert_eq!(*nth, sorted[index]);
+                assert_eq!(left.len(), index);
+                assert!(left.iter().all(|x| x <= nth));
+                assert!(right.iter().all(|x| x >= nth));
+
+                let mut v = original.clone();
+                let (_, nth, right) = select_nth_unstable_by(&mut v, index, |a, b| b.lt(a));
+                assert_eq!(*nth, sorted[len - 1 - index]);
+                assert!(right.iter().all(|x| x <= nth));
+
+                let mut v = original.iter().map(|&x| (x, 'a')).collect::<Vec<_>>();
+                let (_, nth, _) = select_nth_unstable_by_key(&mut v, index, |x| x.0);
+                assert_eq!(nth.0, sorted[index]);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_select_nth_unstable_equal() {
+    for len in 1usize..100 {
+        for &modulus in &[1u32, 4, 1000] {
+            let original = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| x % modulus)
+                .take(len)
+                .collect::<Vec<u32>>();
+            let mut sorted = original.clone();
+            introsort(&mut sorted);
+
+            for index in 0..len {
+                let nth = sorted[index];
+                let mid = sorted.iter().filter(|&&x| x < nth).count();
+                let equal = sorted.iter().filter(|&&x| x == nth).count();
+
+                let mut v = original.clone();
+                assert_eq!(select_nth_unstable_equal(&mut v, index), (mid, equal));
+                assert!(v[..mid].iter().all(|&x| x < nth));
+                assert!(v[mid..mid + equal].iter().all(|&x| x == nth));
+                assert!(v[mid + equal..].iter().all(|&x| x > nth));
+
+                let mut v = original.clone();
+                let reversed =
+                    select_nth_unstable_equal_by(&mut v, len - 1 - index, |a, b| b.lt(a));
+                assert_eq!(reversed, (len - mid - equal, equal));
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_select_nth_unstable_out_of_bounds() {
+    select_nth_unstable(&mut [1, 2, 3], 3);
+}
+
+#[test]
+fn test_select_nth_unstable_adversary() {
+    // McIlroy's adversary decides the order of the elements as they are
+    // compared so that each pivot is as bad as possible, which drives
+    // quickselect to its depth limit and the median of medians fallback
+    let len = 10000;
+    let gas = len;
+    let values = (0..len)
+        .map(|_| Cell::new(gas))
+        .collect::<Vec<Cell<usize>>>();
+    let solid = Cell::new(0);
+    let candidate = Cell::new(0);
+    let comparisons = Cell::new(0);
+    let freeze = |x: usize| {
+        values[x].set(solid.get());
+        solid.set(solid.get() + 1);
+    };
+    let lt = |&x: &usize, &y: &usize| {
+        comparisons.set(comparisons.get() + 1);
+        if values[x].get() == gas && values[y].get() == gas {
+            freeze(if x == candidate.get() { x } else { y });
+        }
+        if values[x].get() == gas {
+            candidate.set(x);
+        } else if values[y].get() == gas {
+            candidate.set(y);
+        }
+        values[x].get() < values[y].get()
+    };
+
+    let mut v = (0..len).collect::<Vec<usize>>();
+    let index = len / 2;
+    let (left, &mut nth, right) = select_nth_unstable_by(&mut v, index, lt);
+    let value = |x: &usize| values[*x].get();
+    assert!(left.iter().all(|x| value(x) <= value(&nth)));
+    assert!(right.iter().all(|x| value(x) >= value(&nth)));
+    // linear, where a quickselect depth limit would allow n log n
+    assert!(comparisons.get() < 20 * len);
+}
+
+#[test]
+fn test_min_max_by_lt() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            // equal keys tagged with their position to check which is returned
+            let v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect::<Vec<(u8, usize)>>();
+            let by_key = |a: &&(u8, usize), b: &&(u8, usize)| a.0 < b.0;
+
+            let min = v.iter().min_by_key(|x| x.0);
+            let max = v.iter().max_by_key(|x| x.0);
+            assert_eq!(min_by_lt(&v, by_key), min);
+            assert_eq!(max_by_lt(&v, by_key), max);
+            assert_eq!(
+                minmax_by_lt(&v, by_key),
+                min.and_then(|min| max.map(|max| (min, max)))
+            );
+            // by value, over an iterator of owned elements
+            let (lo, hi) = (v.iter().cloned().min(), v.iter().cloned().max());
+            assert_eq!(
+                minmax_by_lt(v.iter().cloned(), |a, b| a < b),
+                lo.and_then(|lo| hi.map(|hi| (lo, hi)))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_extremes_k() {
+    for len in 0usize..60 {
+        for k in 0..len / 2 + 3 {
+            let original = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 32)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut sorted = original.clone();
+            introsort(&mut sorted);
+            let k_len = k.min(len);
+
+            let mut v = original.clone();
+            let (least, greatest) = extremes_k(&mut v, k);
+            assert!(least == &sorted[..k_len]);
+            assert!(greatest == &sorted[len - k_len..]);
+
+            let mut v = original.clone();
+            let (greatest, least) = extremes_k_by(&mut v, k, |a, b| b < a);
+            sorted.reverse();
+            assert!(greatest == &sorted[..k_len]);
+            assert!(least == &sorted[len - k_len..]);
+        }
+    }
+}
+
+#[test]
+fn test_top_k() {
+    for len in 0usize..60 {
+        for k in 0..len + 3 {
+            let v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 32)
+                .enumerate()
+                .take(len)
+                .collect::<Vec<(usize, u8)>>();
+            let k_len = k.min(len);
+
+            let values = v.iter().map(|x| x.1).collect::<Vec<u8>>();
+            let mut expected = values.clone();
+            introsort(&mut expected);
+            expected.reverse();
+            assert!(top_k(&values, k) == expected[..k_len]);
+            // equal keys keep no particular order, but each is a distinct element
+            let top = top_k_by(&v, k, |a, b| b.1 < a.1);
+            let mut ids = top.iter().map(|x| x.0).collect::<Vec<usize>>();
+            introsort(&mut ids);
+            ids.dedup();
+            assert_eq!(ids.len(), k_len);
+            assert!(top
+                .iter()
+                .map(|x| x.1)
+                .eq(expected.iter().rev().cloned().take(k_len)));
+        }
+    }
+}
+
+#[test]
+fn test_median_and_quantiles() {
+    let ps = [0.0, 0.01, 0.25, 0.5, 0.5, 0.95, 0.99, 1.0, 0.1];
+    for len in 0usize..100 {
+        let original = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 32)
+            .take(len)
+            .collect::<Vec<u8>>();
+        let mut sorted = original.clone();
+        introsort(&mut sorted);
+
+        let mut v = original.clone();
+        assert_eq!(median(&mut v), sorted.get(len.saturating_sub(1) / 2));
+        let mut v = original.clone();
+        let expected = sorted.get(len / 2);
+        assert_eq!(median_by(&mut v, |a, b| b < a), expected);
+
+        let mut v = original.clone();
+        let q = quantiles(&mut v, &ps);
+        if len == 0 {
+            assert!(q.is_none());
+            continue;
+        }
+        let expected = ps
+            .iter()
+            .map(|p| &sorted[(p * (len - 1) as f64).floor() as usize])
+            .collect::<Vec<&u8>>();
+        assert!(q.unwrap() == expected);
+
+        let mut v = original.clone();
+        let q = quantiles_by(&mut v, &ps, |a, b| b < a).unwrap();
+        sorted.reverse();
+        let expected = ps
+            .iter()
+            .map(|p| &sorted[(p * (len - 1) as f64).floor() as usize])
+            .collect::<Vec<&u8>>();
+        assert!(q == expected);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_quantiles_out_of_range() {
+    quantiles(&mut [1, 2, 3], &[0.5, 1.5]);
+}
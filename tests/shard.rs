@@ -0,0 +1,217 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    equi_depth_splitters, equi_depth_splitters_by, introsort, is_permutation_of, range_partition,
+    range_partition_by, sample_splitters, sample_splitters_by, shard_for_key, shard_for_key_by,
+    splitters_from_sample, splitters_from_sample_by,
+};
+
+#[test]
+fn test_sample_splitters() {
+    for &len in &[0usize, 1, 5, 100, 10000] {
+        for &k in &[1usize, 2, 7, 16] {
+            let v = thread_rng()
+                .gen_iter::<u32>()
+                .take(len)
+                .collect::<Vec<u32>>();
+            let splitters = sample_splitters(v.iter().cloned(), k);
+            if len == 0 {
+                assert!(splitters.is_empty());
+                continue;
+            }
+            assert_eq!(splitters.len(), k - 1);
+            assert!(splitters.windows(2).all(|w| w[0] <= w[1]));
+            assert!(splitters.iter().all(|s| v.contains(s)));
+            assert!(sample_splitters(v.iter().cloned(), k) == splitters);
+
+            let reversed = sample_splitters_by(v.iter().cloned(), k, |a, b| b.lt(a));
+            assert_eq!(reversed.len(), k - 1);
+            assert!(reversed.windows(2).all(|w| w[0] >= w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_sample_splitters_balance() {
+    let mut v = thread_rng()
+        .gen_iter::<u32>()
+        .take(100000)
+        .collect::<Vec<u32>>();
+    let k = 8;
+    let splitters = sample_splitters(v.iter().cloned(), k);
+    let bounds = range_partition(&mut v, &splitters);
+    for w in bounds.windows(2) {
+        // each range holds an eighth of the input, give or take
+        let size = w[1] - w[0];
+        assert!(size > 100000 / k / 2 && size < 100000 / k * 2);
+    }
+}
+
+#[test]
+fn test_range_partition() {
+    for len in 0usize..100 {
+        for &num_splitters in &[0usize, 1, 3, 10] {
+            let original = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 32)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut splitters = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 32)
+                .take(num_splitters)
+                .collect::<Vec<u8>>();
+            introsort(&mut splitters);
+
+            let mut v = original.clone();
+            let bounds = range_partition(&mut v, &splitters);
+            assert!(is_permutation_of(&v, &original));
+            assert_eq!(bounds.len(), num_splitters + 2);
+            assert_eq!(bounds[0], 0);
+            assert_eq!(bounds[num_splitters + 1], len);
+            for (r, w) in bounds.windows(2).enumerate() {
+                for x in &v[w[0]..w[1]] {
+                    assert!(r == 0 || *x >= splitters[r - 1]);
+                    assert!(r == num_splitters || *x < splitters[r]);
+                }
+            }
+
+            // a sorted slice is left as it is
+            let mut sorted = original.clone();
+            introsort(&mut sorted);
+            let expected = sorted.clone();
+            range_partition(&mut sorted, &splitters);
+            assert!(sorted == expected);
+
+            splitters.reverse();
+            let mut v = original.clone();
+            let bounds = range_partition_by(&mut v, &splitters, |a, b| b.lt(a));
+            for (r, w) in bounds.windows(2).enumerate() {
+                for x in &v[w[0]..w[1]] {
+                    assert!(r == 0 || *x <= splitters[r - 1]);
+                    assert!(r == num_splitters || *x > splitters[r]);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_splitters_from_sample() {
+    for &len in &[0usize, 1, 10, 1000] {
+        // distinct values, as copies of a splitter all go in the range above it
+        let mut sample = (0..len as u16).collect::<Vec<u16>>();
+        thread_rng().shuffle(&mut sample);
+        for &k in &[1usize, 3, 16] {
+            let splitters = splitters_from_sample(&mut sample.clone(), k);
+            assert_eq!(splitters.len(), if len == 0 { 0 } else { k - 1 });
+            assert!(splitters.windows(2).all(|w| w[0] <= w[1]));
+
+            // the sample is the whole input, so each range gets its share
+            if len == 1000 {
+                let mut v = sample.clone();
+                let bounds = range_partition(&mut v, &splitters);
+                for w in bounds.windows(2) {
+                    assert!(w[1] - w[0] <= len / k + 1);
+                }
+            }
+
+            let reversed = splitters_from_sample_by(&mut sample.clone(), k, |a, b| b.lt(a));
+            assert!(reversed.windows(2).all(|w| w[0] >= w[1]));
+        }
+    }
+}
+
+#[test]
+fn test_equi_depth_splitters() {
+    let mut rng = thread_rng();
+    for &num_parts in &[0usize, 1, 3, 8] {
+        for &k in &[1usize, 2, 5, 16] {
+            // distinct values, so every range is exactly its share
+            let mut all = (0..rng.gen_range(0, 500) * 7u32).collect::<Vec<u32>>();
+            rng.shuffle(&mut all);
+            let mut shards = vec![Vec::new(); num_parts];
+            if num_parts == 0 {
+                all.clear();
+            }
+            for (i, &x) in all.iter().enumerate() {
+                shards[i % num_parts.max(1)].push(x);
+            }
+            for shard in &mut shards {
+                introsort(shard);
+            }
+            let parts = shards.iter().map(|s| &s[..]).collect::<Vec<&[u32]>>();
+            let len = all.len();
+
+            let splitters = equi_depth_splitters(&parts, k);
+            if len == 0 {
+                assert!(splitters.is_empty());
+                continue;
+            }
+            introsort(&mut all);
+            let expected = (1..k).map(|i| all[i * len / k]).collect::<Vec<u32>>();
+            assert!(splitters == expected);
+            let bounds = range_partition(&mut all, &splitters);
+            assert!(bounds == (0..k + 1).map(|i| i * len / k).collect::<Vec<usize>>());
+
+            for shard in &mut shards {
+                shard.reverse();
+            }
+            let parts = shards.iter().map(|s| &s[..]).collect::<Vec<&[u32]>>();
+            let reversed = equi_depth_splitters_by(&parts, k, |a, b| b < a);
+            assert!(
+                reversed
+                    == (1..k)
+                        .map(|i| all[len - 1 - i * len / k])
+                        .collect::<Vec<u32>>()
+            );
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_equi_depth_splitters_zero() {
+    let parts: [&[u32]; 1] = [&[1, 2, 3]];
+    equi_depth_splitters(&parts, 0);
+}
+
+#[test]
+fn test_shard_for_key() {
+    for &num_splitters in &[0usize, 1, 5, 20] {
+        let mut v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 32)
+            .take(500)
+            .collect::<Vec<u8>>();
+        let mut splitters = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 32)
+            .take(num_splitters)
+            .collect::<Vec<u8>>();
+        introsort(&mut splitters);
+
+        let bounds = range_partition(&mut v, &splitters);
+        for (shard, w) in bounds.windows(2).enumerate() {
+            for key in &v[w[0]..w[1]] {
+                assert_eq!(shard_for_key(key, &splitters), shard);
+            }
+        }
+
+        splitters.reverse();
+        let bounds = range_partition_by(&mut v, &splitters, |a, b| b.lt(a));
+        for (shard, w) in bounds.windows(2).enumerate() {
+            for key in &v[w[0]..w[1]] {
+                assert_eq!(shard_for_key_by(key, &splitters, |a, b| b.lt(a)), shard);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_sample_splitters_zero() {
+    sample_splitters(0..10, 0);
+}
@@ -0,0 +1,179 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::cmp::Ordering;
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    dedup_sorted_by_key, extract_if_sorted, introsort, merge_insert, remove_sorted_items, sort_by,
+};
+
+#[test]
+fn test_remove_sorted_items() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut to_remove = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len / 4)
+                .collect::<Vec<u8>>();
+            introsort(&mut v);
+            introsort(&mut to_remove);
+
+            let expected = v
+                .iter()
+                .cloned()
+                .filter(|x| !to_remove.contains(x))
+                .collect::<Vec<u8>>();
+            let removed = remove_sorted_items(&mut v, &to_remove);
+            assert_eq!(removed, len - expected.len());
+            assert!(v == expected);
+        }
+    }
+
+    let mut v = vec![1, 2, 3];
+    assert_eq!(remove_sorted_items(&mut v, &[]), 0);
+    assert!(v == [1, 2, 3]);
+    assert_eq!(remove_sorted_items(&mut v, &[0, 1, 2, 3, 4]), 3);
+    assert!(v.is_empty());
+}
+
+/// Orders by the first field only so equal keys can be told apart.
+#[derive(Clone, Debug)]
+struct Keyed(u8, char);
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Keyed) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Keyed) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+#[test]
+fn test_merge_insert() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut new_items = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len / 2)
+                .collect::<Vec<u8>>();
+            introsort(&mut v);
+
+            let mut expected = v
+                .iter()
+                .chain(new_items.iter())
+                .cloned()
+                .collect::<Vec<u8>>();
+            introsort(&mut expected);
+            merge_insert(&mut v, &mut new_items);
+            assert!(v == expected);
+        }
+    }
+
+    // new items go after existing equal elements
+    let mut v = vec![Keyed(1, 'a'), Keyed(2, 'a')];
+    merge_insert(&mut v, &mut [Keyed(1, 'b'), Keyed(3, 'b')]);
+    assert!(v.iter().map(|x| x.1).collect::<String>() == "abab");
+
+    let mut v: Vec<String> = Vec::new();
+    merge_insert(&mut v, &mut ["b".to_string(), "a".to_string()]);
+    assert!(v == ["a", "b"]);
+}
+
+#[test]
+fn test_dedup_sorted_by_key() {
+    for len in 0usize..50 {
+        for _ in 0..20 {
+            let mut keys = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 8)
+                .take(len)
+                .collect::<Vec<u8>>();
+            introsort(&mut keys);
+            let mut v = keys.into_iter().zip(0..len).collect::<Vec<(u8, usize)>>();
+            let original = v.clone();
+
+            let removed = dedup_sorted_by_key(&mut v, |x| x.0);
+            assert_eq!(removed, len - v.len());
+            assert!(v.windows(2).all(|w| w[0].0 < w[1].0));
+            // the kept element is the first with its key
+            for x in &v {
+                assert!(original.iter().find(|y| y.0 == x.0) == Some(x));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_extract_if_sorted() {
+    for len in 0usize..100 {
+        let mut v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| (x % 16, 0usize))
+            .take(len)
+            .collect::<Vec<(u8, usize)>>();
+        sort_by(&mut v, |a, b| a.0 < b.0);
+        for (i, x) in v.iter_mut().enumerate() {
+            x.1 = i;
+        }
+        let original = v.clone();
+
+        let mut calls = 0;
+        let extracted = extract_if_sorted(&mut v, |x| {
+            calls += 1;
+            x.0 % 3 == 0
+        });
+        assert_eq!(calls, len);
+        assert!(v.len() + extracted.len() == len);
+        assert!(v.iter().all(|x| x.0 % 3 != 0));
+        assert!(extracted.iter().all(|x| x.0 % 3 == 0));
+        // both sides keep their original order
+        assert!(v.windows(2).all(|w| w[0].1 < w[1].1));
+        assert!(extracted.windows(2).all(|w| w[0].1 < w[1].1));
+        let mut rejoined = v.clone();
+        rejoined.extend(extracted);
+        sort_by(&mut rejoined, |a, b| a.1 < b.1);
+        assert!(rejoined == original);
+    }
+}
+
+#[test]
+fn test_extract_if_sorted_panic() {
+    // a panicking predicate leaves every element in vec or already returned
+    for panic_at in 0usize..20 {
+        let mut v = (0..20u32).map(|x| x.to_string()).collect::<Vec<String>>();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            extract_if_sorted(&mut v, |x| {
+                calls += 1;
+                if calls > panic_at {
+                    panic!("predicate failed");
+                }
+                x.len() == 1
+            })
+        }));
+        assert!(result.is_err());
+        let kept = (0..20u32)
+            .map(|x| x.to_string())
+            .filter(|x| x.len() > 1 || x.parse::<usize>().unwrap() >= panic_at)
+            .collect::<Vec<String>>();
+        assert!(v == kept);
+    }
+}
@@ -1,8 +1,16 @@
 extern crate rand;
 extern crate sortrs;
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use rand::{Rng, thread_rng};
-use sortrs::{insertsort, insertsort_by, heapsort, heapsort_by, introsort, introsort_by};
+use sortrs::{
+    heap_pop_k, heap_pop_k_by, heapsort, heapsort_by, heapsort_by_key, insertsort, insertsort_by,
+    insertsort_by_key, introsort, introsort_by, introsort_by_cached_key, introsort_by_key,
+    introsort_with_config_by, is_permutation_of, is_permutation_of_by, partial_sort,
+    partial_sort_by, partial_sort_copy, partial_sort_copy_by, sort, sort_by, sort_ord,
+    sort_unstable, sort_unstable_by, sort_unstable_ord, Fallback, Insertion, IntrosortConfig,
+};
 
 #[test]
 fn test_insertsort() {
@@ -12,16 +20,19 @@ fn test_insertsort() {
                 .gen_iter::<usize>()
                 .take(len)
                 .collect::<Vec<usize>>();
+            let original = v.clone();
             let mut v1 = v.clone();
 
             insertsort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_permutation_of(&v, &original));
 
             insertsort_by(&mut v1, |a, b| a.lt(b));
             assert!(v1.windows(2).all(|w| w[0] <= w[1]));
 
             insertsort_by(&mut v1[..], |a, b| b.lt(a));
             assert!(v1.windows(2).all(|w| w[0] >= w[1]));
+            assert!(is_permutation_of(&v1, &original));
         }
     }
 
@@ -42,16 +53,19 @@ fn test_heapsort() {
                 .gen_iter::<usize>()
                 .take(len)
                 .collect::<Vec<usize>>();
+            let original = v.clone();
             let mut v1 = v.clone();
 
             heapsort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_permutation_of(&v, &original));
 
             heapsort_by(&mut v1, |a, b| a.lt(b));
             assert!(v1.windows(2).all(|w| w[0] <= w[1]));
 
             heapsort_by(&mut v1, |a, b| b.lt(a));
             assert!(v1.windows(2).all(|w| w[0] >= w[1]));
+            assert!(is_permutation_of(&v1, &original));
         }
     }
 
@@ -64,6 +78,80 @@ fn test_heapsort() {
     assert!(v == [0xDEADBEEF]);
 }
 
+#[test]
+fn test_heap_pop_k() {
+    for len in 0usize..50 {
+        for k in 0..len + 2 {
+            let mut v = thread_rng().gen_iter::<u8>().take(len).collect::<Vec<u8>>();
+            let mut sorted = v.clone();
+            introsort(&mut sorted);
+            let mut v1 = v.clone();
+            let k_len = k.min(len);
+
+            heap_pop_k(&mut v, k);
+            assert!(v[len - k_len..] == sorted[len - k_len..]);
+
+            heap_pop_k_by(&mut v1, k, |a, b| b.lt(a));
+            sorted.reverse();
+            assert!(v1[len - k_len..] == sorted[len - k_len..]);
+        }
+    }
+}
+
+#[test]
+fn test_partial_sort() {
+    for len in 0usize..50 {
+        for k in 0..len + 2 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .collect::<Vec<u8>>();
+            let mut sorted = v.clone();
+            introsort(&mut sorted);
+            let mut v1 = v.clone();
+            let original = v.clone();
+            let k_len = k.min(len);
+
+            partial_sort(&mut v, k);
+            assert!(v[..k_len] == sorted[..k_len]);
+            assert!(is_permutation_of(&v, &original));
+
+            partial_sort_by(&mut v1, k, |a, b| b.lt(a));
+            sorted.reverse();
+            assert!(v1[..k_len] == sorted[..k_len]);
+        }
+    }
+}
+
+#[test]
+fn test_partial_sort_copy() {
+    for len in 0usize..50 {
+        for k in 0..len + 2 {
+            let src = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| x % 16)
+                .take(len)
+                .map(|x| x.to_string())
+                .collect::<Vec<String>>();
+            let mut sorted = src.clone();
+            introsort(&mut sorted);
+            let k_len = k.min(len);
+
+            // the part of dst past the end of src is left alone
+            let mut dst = vec!["x".to_string(); k];
+            assert_eq!(partial_sort_copy(&src, &mut dst), k_len);
+            assert!(dst[..k_len] == sorted[..k_len]);
+            assert!(dst[k_len..].iter().all(|x| x == "x"));
+
+            let mut dst = vec![String::new(); k];
+            partial_sort_copy_by(&src, &mut dst, |a, b| b.lt(a));
+            sorted.reverse();
+            assert!(dst[..k_len] == sorted[..k_len]);
+        }
+    }
+}
+
 #[test]
 fn test_introsort() {
     for len in 4usize..25 {
@@ -72,16 +160,19 @@ fn test_introsort() {
                 .gen_iter::<usize>()
                 .take(len)
                 .collect::<Vec<usize>>();
+            let original = v.clone();
             let mut v1 = v.clone();
 
             introsort(&mut v);
             assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_permutation_of(&v, &original));
 
             introsort_by(&mut v1, |a, b| a.lt(b));
             assert!(v1.windows(2).all(|w| w[0] <= w[1]));
 
             introsort_by(&mut v1, |a, b| b.lt(a));
             assert!(v1.windows(2).all(|w| w[0] >= w[1]));
+            assert!(is_permutation_of(&v1, &original));
         }
     }
 
@@ -93,3 +184,254 @@ fn test_introsort() {
     introsort(&mut v);
     assert!(v == [0xDEADBEEF]);
 }
+
+/// An element that can only be ordered by one of its fields.
+#[derive(Clone, Debug)]
+struct Record {
+    id: u8,
+    name: String,
+}
+
+#[test]
+fn test_sort_by_key() {
+    let sorts: [fn(&mut [Record]); 3] = [
+        |v| insertsort_by_key(v, |r| r.id),
+        |v| heapsort_by_key(v, |r| r.id),
+        |v| introsort_by_key(v, |r| r.id),
+    ];
+    for len in 0usize..40 {
+        let mut original = thread_rng()
+            .gen_iter::<u8>()
+            .take(len)
+            .map(|id| Record {
+                id,
+                name: id.to_string(),
+            })
+            .collect::<Vec<Record>>();
+        let mut ids = original.iter().map(|r| r.id).collect::<Vec<u8>>();
+        introsort(&mut ids);
+        for sort in &sorts {
+            let mut v = original.clone();
+            sort(&mut v);
+            assert!(v.iter().map(|r| r.id).collect::<Vec<u8>>() == ids);
+            assert!(v.iter().all(|r| r.name == r.id.to_string()));
+        }
+        // a key computed from the element
+        introsort_by_key(&mut original, |r| r.name.len());
+        assert!(original
+            .windows(2)
+            .all(|w| w[0].name.len() <= w[1].name.len()));
+    }
+}
+
+#[test]
+fn test_introsort_by_cached_key() {
+    for len in 0usize..100 {
+        let mut v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 8)
+            .take(len)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u8, usize)>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+
+        let calls = AtomicUsize::new(0);
+        introsort_by_cached_key(&mut v, |x| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            x.0.to_string()
+        });
+        // equal keys keep their input order
+        assert!(v == expected);
+        assert_eq!(calls.load(Ordering::Relaxed), if len < 2 { 0 } else { len });
+    }
+}
+
+fn insertsort_fallback(v: &mut [usize], lt: &dyn Fn(&usize, &usize) -> bool) {
+    insertsort_by(v, |a, b| lt(a, b));
+}
+
+#[test]
+fn test_introsort_with_config() {
+    let fallbacks = [
+        Fallback::Heapsort,
+        Fallback::Insertsort,
+        Fallback::Custom(insertsort_fallback),
+    ];
+    for fallback in &fallbacks {
+        let config = IntrosortConfig {
+            fallback: *fallback,
+            ..Default::default()
+        };
+        for &len in &[0usize, 1, 2, 33, 100, 1000] {
+            let mut v = thread_rng()
+                .gen_iter::<usize>()
+                .map(|x| x % 64)
+                .take(len)
+                .collect::<Vec<usize>>();
+            let original = v.clone();
+
+            introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+            assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_permutation_of(&v, &original));
+
+            introsort_with_config_by(&mut v, &config, |a, b| b.lt(a));
+            assert!(v.windows(2).all(|w| w[0] >= w[1]));
+            assert!(is_permutation_of(&v, &original));
+        }
+    }
+}
+
+static FALLBACK_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+fn counting_fallback(v: &mut [usize], lt: &dyn Fn(&usize, &usize) -> bool) {
+    FALLBACK_CALLS.fetch_add(1, Ordering::SeqCst);
+    heapsort_by(v, |a, b| lt(a, b));
+}
+
+#[test]
+fn test_introsort_depth_multiplier() {
+    for &depth_multiplier in &[0usize, 1, 2, 8, usize::MAX] {
+        let config = IntrosortConfig {
+            fallback: Fallback::Custom(counting_fallback),
+            depth_multiplier,
+            ..Default::default()
+        };
+        FALLBACK_CALLS.store(0, Ordering::SeqCst);
+        for &len in &[0usize, 1, 32, 33, 1000] {
+            let mut v = thread_rng()
+                .gen_iter::<usize>()
+                .take(len)
+                .collect::<Vec<usize>>();
+            let original = v.clone();
+            introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+            assert!(v.windows(2).all(|w| w[0] <= w[1]));
+            assert!(is_permutation_of(&v, &original));
+        }
+        // with no depth allowed anything above the insertsort threshold falls
+        // back straight away
+        if depth_multiplier == 0 {
+            assert_eq!(FALLBACK_CALLS.load(Ordering::SeqCst), 2);
+        }
+    }
+}
+
+#[test]
+fn test_introsort_insertion_threshold() {
+    for &insertion in &[Insertion::Final, Insertion::PerLeaf] {
+        for &insertion_threshold in &[0usize, 3, 4, 16, 100] {
+            let config = IntrosortConfig {
+                insertion_threshold,
+                insertion,
+                ..Default::default()
+            };
+            for &len in &[0usize, 1, 3, 4, 5, 50, 1000] {
+                let mut v = thread_rng()
+                    .gen_iter::<usize>()
+                    .take(len)
+                    .collect::<Vec<usize>>();
+                let original = v.clone();
+                introsort_with_config_by(&mut v, &config, |a, b| a.lt(b));
+                assert!(v.windows(2).all(|w| w[0] <= w[1]));
+                assert!(is_permutation_of(&v, &original));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_inconsistent_comparator() {
+    // a comparator that answers at random must not take the partition scans
+    // out of bounds, the result is just not sorted
+    for &len in &[33usize, 100, 1000] {
+        let mut v = thread_rng()
+            .gen_iter::<usize>()
+            .take(len)
+            .collect::<Vec<usize>>();
+        let original = v.clone();
+        introsort_by(&mut v, |_, _| thread_rng().gen());
+        assert!(is_permutation_of(&v, &original));
+        sort_unstable_by(&mut v, |_, _| true);
+        assert!(is_permutation_of(&v, &original));
+    }
+}
+
+#[test]
+fn test_permutation() {
+    // long enough to exercise partitioning and the heapsort fallback, with
+    // few distinct values so equal elements are common
+    for &len in &[33usize, 100, 1000, 10000] {
+        for &modulus in &[2u32, 100, u32::MAX] {
+            let original = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| (x % modulus).to_string())
+                .take(len)
+                .collect::<Vec<String>>();
+            let sorts: [fn(&mut [String]); 4] = [heapsort, introsort, sort, sort_unstable];
+            for sort_fn in &sorts {
+                let mut v = original.clone();
+                sort_fn(&mut v);
+                assert!(v.windows(2).all(|w| w[0] <= w[1]));
+                assert!(is_permutation_of(&v, &original));
+            }
+        }
+    }
+
+    assert!(is_permutation_of::<u8>(&[], &[]));
+    assert!(!is_permutation_of(&[1, 2], &[1]));
+    assert!(!is_permutation_of(&[1, 1, 2], &[1, 2, 2]));
+    assert!(is_permutation_of_by(
+        &[0.5, -0.0, 1.0],
+        &[1.0, 0.0, 0.5],
+        |a, b| a.lt(b)
+    ));
+    assert!(!is_permutation_of_by(&[0.5, 0.5], &[0.5, 1.0], |a, b| a.lt(b)));
+}
+
+#[test]
+fn test_zero_sized() {
+    // zero-sized elements have no size to measure pointer distances with
+    let mut v = vec![(); 100];
+    insertsort(&mut v);
+    heapsort(&mut v);
+    introsort(&mut v);
+    introsort_by(&mut v, |_, _| true);
+    assert_eq!(v.len(), 100);
+}
+
+#[test]
+fn test_sort() {
+    for len in 4usize..50 {
+        for _ in 0..100 {
+            let mut v = thread_rng()
+                .gen_iter::<u8>()
+                .map(|x| (x % 8, 0usize))
+                .take(len)
+                .collect::<Vec<(u8, usize)>>();
+            for (i, x) in v.iter_mut().enumerate() {
+                x.1 = i;
+            }
+            let mut v1 = v.clone();
+
+            sort_by(&mut v, |a, b| a.0 < b.0);
+            assert!(v
+                .windows(2)
+                .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+            sort(&mut v1);
+            assert!(v == v1);
+
+            sort_unstable_by(&mut v, |a, b| b.0 < a.0);
+            assert!(v.windows(2).all(|w| w[0].0 >= w[1].0));
+            sort_unstable(&mut v);
+            assert!(v == v1);
+
+            v.reverse();
+            sort_ord(&mut v);
+            assert!(v == v1);
+            v.reverse();
+            sort_unstable_ord(&mut v);
+            assert!(v == v1);
+        }
+    }
+}
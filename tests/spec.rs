@@ -0,0 +1,77 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{sort_with, SortSpec};
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Row {
+    name: u8,
+    age: u8,
+    score: f64,
+    id: usize,
+}
+
+#[test]
+fn test_sort_spec() {
+    for &len in &[0usize, 1, 2, 10, 100, 1000] {
+        let rows = (0..len)
+            .map(|id| Row {
+                name: thread_rng().gen::<u8>() % 4,
+                age: thread_rng().gen::<u8>() % 8,
+                score: f64::from(thread_rng().gen::<u8>() % 3),
+                id,
+            })
+            .collect::<Vec<Row>>();
+
+        // name ascending, then age descending, then score ascending
+        let mut expected = rows.clone();
+        expected.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then(b.age.cmp(&a.age))
+                .then(a.score.partial_cmp(&b.score).unwrap())
+        });
+        let spec = SortSpec::<Row>::new()
+            .asc_by_key(|r| r.name)
+            .desc_by_key(|r| r.age)
+            .asc_by_key(|r| r.score);
+
+        // apply is stable, so ties keep their input order as in std's sort
+        let mut v = rows.clone();
+        spec.apply(&mut v);
+        assert!(v == expected);
+
+        let mut v = rows.clone();
+        spec.apply_unstable(&mut v);
+        assert!(v
+            .iter()
+            .zip(expected.iter())
+            .all(|(a, b)| spec.compare(a, b) == Ordering::Equal));
+
+        let mut v = rows.clone();
+        sort_with(&mut v, &spec);
+        assert!(v == expected);
+    }
+}
+
+#[test]
+fn test_sort_spec_no_keys() {
+    // without keys every element is equal, so a stable sort changes nothing
+    let mut v = [3, 1, 2];
+    SortSpec::new().apply(&mut v);
+    assert!(v == [3, 1, 2]);
+    assert_eq!(SortSpec::default().compare(&1, &2), Ordering::Equal);
+}
+
+#[test]
+fn test_sort_spec_incomparable_keys() {
+    // NaN is equal to everything, so the next key decides
+    let mut v = [(f64::NAN, 2), (1.0, 1), (f64::NAN, 0)];
+    SortSpec::<(f64, i32)>::new()
+        .asc_by_key(|x| x.0)
+        .asc_by_key(|x| x.1)
+        .apply(&mut v);
+    assert!(v.iter().map(|x| x.1).collect::<Vec<i32>>() == [0, 1, 2]);
+}
@@ -0,0 +1,71 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, sort_strided, sort_strided_by};
+
+#[test]
+fn test_sort_strided() {
+    for len in 0usize..100 {
+        for stride in 1usize..6 {
+            for channel in 0..stride {
+                let original = thread_rng()
+                    .gen_iter::<u8>()
+                    .map(|x| x % 32)
+                    .take(len)
+                    .collect::<Vec<u8>>();
+                let mut expected = original.clone();
+                let mut values = expected
+                    .iter()
+                    .skip(channel)
+                    .step_by(stride)
+                    .cloned()
+                    .collect::<Vec<u8>>();
+                introsort(&mut values);
+                for (x, y) in expected
+                    .iter_mut()
+                    .skip(channel)
+                    .step_by(stride)
+                    .zip(values)
+                {
+                    *x = y;
+                }
+
+                let mut v = original.clone();
+                sort_strided(&mut v, stride, channel);
+                assert!(v == expected);
+
+                let mut v = original.clone();
+                sort_strided_by(&mut v, stride, channel, |a, b| b < a);
+                let mut expected = expected.clone();
+                let channel_values = expected
+                    .iter()
+                    .skip(channel)
+                    .step_by(stride)
+                    .cloned()
+                    .collect::<Vec<u8>>();
+                for (x, y) in expected
+                    .iter_mut()
+                    .skip(channel)
+                    .step_by(stride)
+                    .zip(channel_values.into_iter().rev())
+                {
+                    *x = y;
+                }
+                assert!(v == expected);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_sort_strided_bad_channel() {
+    sort_strided(&mut [1, 2, 3, 4], 2, 2);
+}
+
+#[test]
+#[should_panic]
+fn test_sort_strided_zero_stride() {
+    sort_strided(&mut [1, 2, 3, 4], 0, 0);
+}
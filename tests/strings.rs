@@ -0,0 +1,72 @@
+extern crate rand;
+extern crate sortrs;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    byte_len, char_count, introsort, introsort_by, sort_by, sort_strings, sort_strings_ci,
+};
+
+fn random_strings(len: usize) -> Vec<String> {
+    // short strings from a small alphabet give many shared prefixes,
+    // including prefixes longer than 8 bytes and embedded zero bytes
+    (0..len)
+        .map(|_| {
+            let n = thread_rng().gen_range(0, 14);
+            (0..n)
+                .map(|_| ['\0', 'a', 'B', 'b', 'é', 'É', '\u{1F600}'][thread_rng().gen_range(0, 7)])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_sort_strings() {
+    for len in 0usize..100 {
+        let mut v = random_strings(len);
+        let mut expected = v.clone();
+        introsort(&mut expected);
+        let mut strs = v.iter().map(|s| &s[..]).collect::<Vec<&str>>();
+        sort_strings(&mut strs);
+        assert!(strs == expected);
+        sort_strings(&mut v);
+        assert!(v == expected);
+    }
+}
+
+fn fold(s: &str) -> String {
+    s.chars().flat_map(char::to_lowercase).collect()
+}
+
+#[test]
+fn test_sort_strings_ci() {
+    for len in 0usize..100 {
+        let mut v = random_strings(len);
+        let mut expected = v.clone();
+        introsort_by(&mut expected, |a, b| {
+            let (x, y) = (fold(a), fold(b));
+            x < y || (x == y && a < b)
+        });
+        sort_strings_ci(&mut v);
+        assert!(v == expected);
+    }
+
+    let mut v = ["ÉTÉ", "b", "été", "A", "a", "Été"];
+    sort_strings_ci(&mut v);
+    assert!(v == ["A", "a", "b", "ÉTÉ", "Été", "été"]);
+}
+
+#[test]
+fn test_length_keys() {
+    let mut v = random_strings(200);
+    for s in &v {
+        assert_eq!(byte_len(s), s.len());
+        assert_eq!(char_count(s), s.chars().collect::<Vec<char>>().len());
+    }
+
+    // by char count, then bytes
+    sort_by(&mut v, |a, b| (char_count(a), a) < (char_count(b), b));
+    assert!(v.windows(2).all(|w| {
+        let (a, b) = (char_count(&w[0]), char_count(&w[1]));
+        a < b || (a == b && w[0] <= w[1])
+    }));
+}
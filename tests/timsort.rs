@@ -0,0 +1,141 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::{Rng, thread_rng};
+use sortrs::{introsort, is_permutation_of, timsort, timsort_by};
+
+/// Checks that `v` is sorted by key and equal keys are in position order.
+fn is_stably_sorted(v: &[(u32, usize)]) -> bool {
+    v.windows(2)
+        .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1))
+}
+
+fn tagged(keys: Vec<u32>) -> Vec<(u32, usize)> {
+    keys.into_iter().enumerate().map(|(i, x)| (x, i)).collect()
+}
+
+#[test]
+fn test_timsort() {
+    for &len in &[0usize, 1, 2, 3, 10, 63, 64, 65, 100, 1000, 10000] {
+        for &modulus in &[1u32, 2, 10, 1000, u32::MAX] {
+            let mut v = tagged(
+                thread_rng()
+                    .gen_iter::<u32>()
+                    .map(|x| x % modulus)
+                    .take(len)
+                    .collect(),
+            );
+            let original = v.clone();
+            timsort_by(&mut v, |a, b| a.0 < b.0);
+            assert!(is_stably_sorted(&v));
+            assert!(is_permutation_of(&v, &original));
+
+            let mut keys = original.iter().map(|x| x.0).collect::<Vec<u32>>();
+            let mut expected = keys.clone();
+            introsort(&mut expected);
+            timsort(&mut keys);
+            assert!(keys == expected);
+        }
+    }
+}
+
+#[test]
+fn test_timsort_patterns() {
+    // runs of all kinds exercise run detection and galloping
+    let len = 5000;
+    let patterns: Vec<Vec<u32>> = vec![
+        (0..len).collect(),
+        (0..len).rev().collect(),
+        (0..len).map(|x| x % 7).collect(),
+        (0..len).map(|x| (x * 37) % 100).collect(),
+        (0..len)
+            .map(|x| if x < len / 2 { x } else { x - len / 2 })
+            .collect(),
+        (0..len).map(|x| if x % 100 == 0 { 0 } else { x }).collect(),
+        (0..len)
+            .map(|x| (x / 500) * 1000 + (x % 500) * 2 % 999)
+            .collect(),
+        {
+            let mut v = (0..len).collect::<Vec<u32>>();
+            for _ in 0..20 {
+                let i = thread_rng().gen_range(0, len as usize);
+                let j = thread_rng().gen_range(0, len as usize);
+                v.swap(i, j);
+            }
+            v
+        },
+    ];
+    for keys in patterns {
+        let mut v = tagged(keys);
+        let original = v.clone();
+        timsort_by(&mut v, |a, b| a.0 < b.0);
+        assert!(is_stably_sorted(&v));
+        assert!(is_permutation_of(&v, &original));
+
+        timsort_by(&mut v, |a, b| b.0 < a.0);
+        assert!(v.windows(2).all(|w| w[0].0 >= w[1].0));
+        assert!(is_permutation_of(&v, &original));
+    }
+}
+
+#[test]
+fn test_timsort_large_runs() {
+    // runs of megabytes, with long stretches taken from each run during the
+    // merges, are what the tiled-merge feature splits and streams
+    let len = 200000u32;
+    let patterns: Vec<Vec<u32>> = vec![
+        (0..len).map(|x| (x % (len / 2)) / 3).collect(),
+        (0..len).map(|x| (x % (len / 2)) / 3 + x % 2).collect(),
+        (0..len).map(|x| ((x * 7) % len) / 1000 * 1000).collect(),
+        thread_rng()
+            .gen_iter::<u32>()
+            .map(|x| x % 100)
+            .take(len as usize)
+            .collect(),
+    ];
+    for keys in patterns {
+        let mut v = tagged(keys);
+        let original = v.clone();
+        timsort_by(&mut v, |a, b| a.0 < b.0);
+        assert!(is_stably_sorted(&v));
+        assert!(is_permutation_of(&v, &original));
+    }
+}
+
+#[test]
+fn test_timsort_panic_safety() {
+    // a comparator that panics part way through must leave every element in
+    // the slice exactly once
+    for &len in &[50usize, 500, 5000] {
+        for &panic_at in &[1usize, 100, 1000, 20000] {
+            let original = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| (x % 100).to_string())
+                .take(len)
+                .collect::<Vec<String>>();
+            let mut v = original.clone();
+            let calls = Cell::new(0);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                timsort_by(&mut v, |a, b| {
+                    calls.set(calls.get() + 1);
+                    if calls.get() == panic_at {
+                        panic!("comparison failed");
+                    }
+                    a < b
+                });
+            }));
+            assert_eq!(result.is_err(), calls.get() >= panic_at);
+            assert!(is_permutation_of(&v, &original));
+        }
+    }
+}
+
+#[test]
+fn test_timsort_zero_sized() {
+    let mut v = vec![(); 1000];
+    timsort(&mut v);
+    assert_eq!(v.len(), 1000);
+}
@@ -0,0 +1,123 @@
+extern crate rand;
+extern crate sortrs;
+
+use std::mem::MaybeUninit;
+use std::rc::Rc;
+
+use rand::{Rng, thread_rng};
+use sortrs::{
+    heapsort_uninit, heapsort_uninit_by, introsort, introsort_uninit, introsort_uninit_by,
+    sort_copy_into_uninit, sort_copy_into_uninit_by, SortedBuffer,
+};
+
+type Sort = for<'a> fn(&'a mut [MaybeUninit<u32>], usize) -> &'a mut [u32];
+
+#[test]
+fn test_introsort_uninit_by() {
+    for len in 0usize..100 {
+        let v = thread_rng()
+            .gen_iter::<u32>()
+            .take(len)
+            .collect::<Vec<u32>>();
+        let mut expected = v.clone();
+        introsort(&mut expected);
+
+        let mut buf = (0..len + 10)
+            .map(|_| MaybeUninit::uninit())
+            .collect::<Vec<MaybeUninit<u32>>>();
+        for (slot, &x) in buf.iter_mut().zip(&v) {
+            slot.write(x);
+        }
+        let sorted = unsafe { introsort_uninit_by(&mut buf, len, |a, b| a.lt(b)) };
+        assert!(sorted == &expected[..]);
+
+        let sorts: [Sort; 3] = [
+            |buf, init| unsafe { introsort_uninit(buf, init) },
+            |buf, init| unsafe { heapsort_uninit(buf, init) },
+            |buf, init| unsafe { heapsort_uninit_by(buf, init, |a, b| a.lt(b)) },
+        ];
+        for sort in &sorts {
+            for (slot, &x) in buf.iter_mut().zip(&v) {
+                slot.write(x);
+            }
+            assert!(sort(&mut buf, len) == &expected[..]);
+        }
+    }
+}
+
+#[test]
+fn test_sorted_buffer() {
+    for cap in 0usize..50 {
+        let v = thread_rng()
+            .gen_iter::<u8>()
+            .map(|x| x % 8)
+            .take(cap + 5)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect::<Vec<(u8, usize)>>();
+        let mut storage = (0..cap)
+            .map(|_| MaybeUninit::uninit())
+            .collect::<Vec<MaybeUninit<(u8, usize)>>>();
+        let mut buf = SortedBuffer::new(&mut storage);
+        assert_eq!(buf.capacity(), cap);
+        for (i, &x) in v.iter().enumerate() {
+            if i < cap {
+                assert!(buf.push(x).is_ok());
+            } else {
+                assert_eq!(buf.push(x), Err(x));
+            }
+        }
+        assert_eq!(buf.len(), cap);
+        assert_eq!(buf.is_empty(), cap == 0);
+
+        // pushing after equal elements keeps the buffer stably sorted
+        let mut expected = v[..cap].to_vec();
+        introsort(&mut expected);
+        assert!(buf.into_slice() == &expected[..]);
+    }
+}
+
+#[test]
+fn test_sorted_buffer_drops() {
+    let counted = Rc::new(());
+    {
+        let mut storage = (0..4)
+            .map(|_| MaybeUninit::uninit())
+            .collect::<Vec<MaybeUninit<(u8, Rc<()>)>>>();
+        let mut buf = SortedBuffer::new(&mut storage);
+        for i in 0..3 {
+            buf.push((3 - i, counted.clone())).unwrap();
+        }
+        assert_eq!(Rc::strong_count(&counted), 4);
+    }
+    assert_eq!(Rc::strong_count(&counted), 1);
+}
+
+#[test]
+fn test_sort_copy_into_uninit() {
+    for len in 0usize..200 {
+        for &modulus in &[1u32, 3, 1000] {
+            let src = thread_rng()
+                .gen_iter::<u32>()
+                .map(|x| x % modulus)
+                .take(len)
+                .collect::<Vec<u32>>();
+            let mut expected = src.clone();
+            introsort(&mut expected);
+
+            let mut dst = vec![MaybeUninit::uninit(); len + 3];
+            assert!(sort_copy_into_uninit(&src, &mut dst) == &expected[..]);
+
+            expected.reverse();
+            let sorted = sort_copy_into_uninit_by(&src, &mut dst, |a, b| b.lt(a));
+            assert!(sorted == &expected[..]);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_sort_copy_into_uninit_short() {
+    let mut dst = [MaybeUninit::uninit(); 2];
+    sort_copy_into_uninit(&[3, 2, 1], &mut dst);
+}